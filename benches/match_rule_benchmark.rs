@@ -184,7 +184,8 @@ fn impact(c: &mut Criterion) {
         b.iter(|| {
             let rules = router.match_request(&request);
             let mut action = Action::from_routes_rule(rules.clone(), &request, None);
-            let unit_trace = Some(Rc::new(RefCell::new(UnitTrace::default())));
+            let trace = Rc::new(RefCell::new(UnitTrace::default()));
+            let unit_trace = Some(trace.clone());
 
             let action_status_code = action.get_status_code(0, unit_trace.clone());
             let (_, backend_status_code) = if action_status_code != 0 {
@@ -210,7 +211,7 @@ fn impact(c: &mut Criterion) {
                 body_filter.end(unit_trace.clone());
             }
 
-            let mut unit_trace = unit_trace.unwrap().take();
+            let mut unit_trace = trace.take();
             unit_trace.squash_with_target_unit_traces();
         });
     });
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::{BenchmarkId, Criterion};
+use redirectionio::{api::Rule, router::Router};
+
+fn create_rules(count: usize) -> Vec<Rule> {
+    (0..count)
+        .map(|i| {
+            let rule = if i % 2 == 0 {
+                format!(r#"{{"id": "rule-{i}", "rank": 0, "source": {{"path": "/static/{i}"}}, "target": "/target/{i}", "status_code": 301}}"#)
+            } else {
+                format!(
+                    r#"{{"id": "rule-{i}", "rank": 0, "source": {{"path": "/blog/{i}/@slug"}}, "markers": [{{"name": "slug", "regex": "[a-z0-9-]+"}}], "target": "/news/{i}/@slug", "status_code": 302}}"#
+                )
+            };
+
+            Rule::from_json(rule.as_str()).expect("Cannot deserialize rule")
+        })
+        .collect()
+}
+
+// Loading a snapshot skips the conversion of rules into routes, the matcher tree is still rebuilt and the regexes are
+// still compiled by `Router::cache`
+fn router_snapshot_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("router_snapshot");
+    group.sample_size(10);
+
+    for count in [1_000, 10_000] {
+        let rules = create_rules(count);
+
+        let rules_serialized = serde_json::to_string(&rules).expect("Cannot serialize rules");
+
+        // Both start from the data a proxy would read at start, the rules in json or the snapshot
+        group.bench_with_input(BenchmarkId::new("from_rules", count), &rules_serialized, |b, rules_serialized| {
+            b.iter(|| {
+                let rules: Vec<Rule> = serde_json::from_str(rules_serialized).expect("Cannot deserialize rules");
+                let mut router = Router::<Rule>::default();
+
+                for rule in rules {
+                    router.insert(rule);
+                }
+
+                router.cache(None);
+
+                assert_eq!(router.len(), count);
+            });
+        });
+
+        let mut router = Router::<Rule>::default();
+
+        for rule in rules {
+            router.insert(rule);
+        }
+
+        let snapshot = router.to_snapshot().expect("Cannot create snapshot");
+
+        group.bench_with_input(BenchmarkId::new("from_snapshot", count), &snapshot, |b, snapshot| {
+            b.iter(|| {
+                let mut router = Router::<Rule>::from_snapshot(snapshot, None).expect("Cannot load snapshot");
+                router.cache(None);
+
+                assert_eq!(router.len(), count);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, router_snapshot_bench);
+criterion_main!(benches);
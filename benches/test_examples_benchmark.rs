@@ -1,3 +1,6 @@
+// The router type nests deeply, see the same limit in the library
+#![recursion_limit = "256"]
+
 #[macro_use]
 extern crate criterion;
 use std::fs::File;
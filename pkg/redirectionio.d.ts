@@ -1,3 +1,433 @@
 export * from './wasm/redirectionio';
 
 export function init(instantiate?: () => Promise<WebAssembly.Instance>): Promise<void>;
+
+// Json payloads accepted and returned by `Router` and the `explain_request`, `impact`,
+// `test_examples`, `unit_ids`, `next_activations`, `flatten_redirections`, `lint_rules` and `validate_rules` functions, they are passed as serialized strings.
+
+export interface RouterConfig {
+    ignore_host_case?: boolean;
+    ignore_header_case?: boolean;
+    ignore_path_and_query_case?: boolean;
+    ignore_all_query_parameters?: boolean;
+    ignore_marketing_query_params?: boolean;
+    marketing_query_params?: string[];
+    pass_marketing_query_params_to_target?: boolean;
+    always_match_any_host?: boolean;
+    ignore_query_param_order?: boolean;
+    geoip_database_path?: string;
+    default_timezone?: string;
+}
+
+export type IpConstraint =
+    | { in_range: string }
+    | { not_in_range: string }
+    | { not_one_of: string[] }
+    | { in_countries: string[] }
+    | { not_in_countries: string[] }
+    | { in_continents: string[] }
+    | { not_in_continents: string[] }
+    | { in_asns: number[] }
+    | { not_in_asns: number[] };
+
+export type DateTimeConstraint = [string | null, string | null];
+
+export interface SourceHeader {
+    type: string;
+    name: string;
+    value: string | null;
+}
+
+export interface SourceQueryParam {
+    type: string;
+    name: string;
+    value: string | null;
+}
+
+export interface SourceCookie {
+    type: string;
+    name: string;
+    value: string | null;
+}
+
+export interface SourceAcceptLanguage {
+    supported: string[];
+    matches?: string[];
+    default?: string | null;
+}
+
+export interface Source {
+    scheme: string | null;
+    host: string | null;
+    ips: IpConstraint[] | null;
+    datetime?: DateTimeConstraint[] | null;
+    time?: DateTimeConstraint[] | null;
+    path: string;
+    query: string | null;
+    headers: SourceHeader[] | null;
+    query_params?: SourceQueryParam[] | null;
+    cookies?: SourceCookie[] | null;
+    accept_language?: SourceAcceptLanguage | null;
+    methods: string[] | null;
+    exclude_methods: boolean | null;
+    response_status_codes: number[] | null;
+    exclude_response_status_codes: boolean | null;
+    response_headers?: SourceHeader[] | null;
+    sampling: number | null;
+    weekdays?: string[] | null;
+    schedules?: string[] | null;
+    timezone?: string | null;
+}
+
+export interface Transformer {
+    type: string | null;
+    options: Record<string, string> | null;
+}
+
+export interface Marker {
+    name: string;
+    regex: string;
+    transformers?: Transformer[];
+}
+
+export interface Variable {
+    name: string;
+    type: string | Record<string, unknown>;
+    transformers?: Transformer[];
+}
+
+export interface HeaderFilter {
+    action: string;
+    header: string;
+    value: string;
+    id: string | null;
+    target_hash: string | null;
+}
+
+export interface HTMLBodyFilter {
+    action: string;
+    value: string;
+    inner_value: string | null;
+    element_tree: string[];
+    css_selector: string | null;
+    attribute?: string | null;
+    id: string | null;
+    target_hash: string | null;
+}
+
+export interface TextBodyFilter {
+    action: string;
+    content: string;
+    pattern?: string | null;
+    id: string | null;
+    target_hash: string | null;
+}
+
+export type BodyFilter = HTMLBodyFilter | TextBodyFilter;
+
+export interface Peer {
+    address: string;
+    sni_host: string | null;
+    request_host: string | null;
+    allow_invalid_certificates: boolean;
+    tls: boolean;
+    upstream_path?: string | null;
+}
+
+export interface WeightedPeer {
+    peer: Peer;
+    weight?: number;
+}
+
+export type PeerSelection = { type: 'weighted_random' } | { type: 'consistent_hash'; key: string };
+
+export interface PeerPool {
+    peers: WeightedPeer[];
+    selection?: PeerSelection;
+}
+
+export interface StaticResponse {
+    status_code: number;
+    content_type: string | null;
+    body?: string;
+}
+
+export interface RateLimit {
+    key?: string;
+    window: number;
+    limit: number;
+    status_code?: number;
+}
+
+export interface ExampleHeader {
+    name: string;
+    value: string;
+}
+
+export interface Example {
+    url: string;
+    method: string | null;
+    headers: ExampleHeader[] | null;
+    datetime?: string | null;
+    ip_address: string | null;
+    response_status_code: number | null;
+    must_match: boolean;
+    unit_ids_applied: string[] | null;
+}
+
+export interface Rule {
+    id: string;
+    source: Source;
+    target: string | null;
+    status_code: number | null;
+    rank: number;
+    markers?: Marker[];
+    variables?: Variable[];
+    body_filters: BodyFilter[] | null;
+    header_filters: HeaderFilter[] | null;
+    request_header_filters?: HeaderFilter[] | null;
+    log_override: boolean | null;
+    peer_override: Peer | null;
+    peer_pool?: PeerPool | null;
+    respond_with?: StaticResponse | null;
+    rate_limit?: RateLimit | null;
+    reset: boolean | null;
+    stop: boolean | null;
+    examples: Example[] | null;
+    redirect_unit_id: string | null;
+    configuration_log_unit_id: string | null;
+    configuration_reset_unit_id: string | null;
+    peer_unit_id: string | null;
+    target_hash: string | null;
+}
+
+export interface RulesMessage {
+    'hydra:member': Rule[];
+}
+
+export interface RuleChangeSet {
+    added: Rule[];
+    updated: Rule[];
+    deleted: string[];
+}
+
+export interface Header {
+    name: string;
+    value: string;
+}
+
+export interface UnitTrace {
+    rule_ids_applied: string[];
+    unit_ids_applied: string[];
+    unit_ids_seen: string[];
+    value_computed_by_units: Record<string, string>;
+}
+
+export interface RedirectionHop {
+    url: string;
+    status_code: number;
+    method: string;
+}
+
+export interface RedirectionLoop {
+    hops: RedirectionHop[];
+    error: 'AtLeastOneHop' | 'TooManyHops' | 'Loop' | null;
+}
+
+export interface Trace {
+    type: string;
+    matched: boolean;
+    executed: boolean;
+    count: number;
+    children: Trace[];
+    [info: string]: unknown;
+}
+
+export interface Response {
+    status_code: number;
+    headers: Header[];
+    body: string;
+}
+
+export interface ExplainRequestInput {
+    router_config: RouterConfig;
+    example: Example;
+    rules: Rule[];
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface ExplainRequestProjectInput {
+    example: Example;
+    change_set: RuleChangeSet;
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface ExplainRequestOutput {
+    example: Example;
+    unit_trace: UnitTrace;
+    backend_status_code: number;
+    response: Response;
+    match_traces: Trace[];
+    redirection_loop: RedirectionLoop | null;
+    should_log_request: boolean;
+}
+
+export interface ExplainRequestOutputError {
+    message: string;
+}
+
+export interface ImpactInput {
+    router_config: RouterConfig;
+    max_hops: number;
+    with_redirection_loop: boolean;
+    domains?: string[];
+    rule: Rule;
+    action: string;
+    rules: Rule[];
+}
+
+export interface ImpactProjectInput {
+    max_hops: number;
+    with_redirection_loop: boolean;
+    domains?: string[];
+    rule: Rule;
+    action: string;
+    change_set: RuleChangeSet;
+}
+
+export interface Impact {
+    example: Example;
+    unit_trace: UnitTrace;
+    backend_status_code: number;
+    response: Response;
+    match_traces: Trace[];
+    error: string | null;
+    redirection_loop: RedirectionLoop | null;
+    should_log_request: boolean;
+}
+
+export interface ImpactOutput {
+    impacts: Impact[];
+}
+
+export interface TestExamplesInput {
+    router_config: RouterConfig;
+    rules: Rule[];
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface TestExamplesProjectInput {
+    change_set: RuleChangeSet;
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface FailedExample {
+    example: Example;
+    rule_ids_applied: string[];
+    unit_ids_applied: string[];
+    unit_ids_not_applied_anymore: string[];
+    redirection_loop: RedirectionLoop | null;
+}
+
+export interface ErroredExample {
+    example: Example;
+    error: string;
+}
+
+export interface TestExamplesOutput {
+    example_count: number;
+    failure_count: number;
+    error_count: number;
+    first_ten_failures: Record<string, { rule: Rule; failed_examples: FailedExample[] }>;
+    first_ten_errors: Record<string, { rule: Rule; errored_examples: ErroredExample[] }>;
+}
+
+export interface UnitIdsInput {
+    router_config: RouterConfig;
+    rules: Rule[];
+}
+
+export interface UnitIdsProjectInput {
+    change_set: RuleChangeSet;
+}
+
+export interface UnitIdsOutput {
+    rules: Record<string, { examples: Example[] }>;
+}
+
+export interface NextActivationInput {
+    router_config: RouterConfig;
+    rules: Rule[];
+    after?: string | null;
+}
+
+export interface NextActivationProjectInput {
+    after?: string | null;
+}
+
+export interface NextActivationOutput {
+    rules: Record<string, string | null>;
+}
+
+export interface FlattenRedirectionsInput {
+    router_config: RouterConfig;
+    rules: Rule[];
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface FlattenRedirectionsProjectInput {
+    change_set: RuleChangeSet;
+    max_hops: number;
+    project_domains?: string[];
+}
+
+export interface RedirectionChain {
+    hops: RedirectionHop[];
+    final_url: string;
+    final_status_code: number;
+    error: 'TooManyHops' | 'Loop' | null;
+}
+
+export interface FlattenedRedirection {
+    target: string;
+    status_code: number;
+    collapsed_target: string | null;
+    chain: RedirectionChain;
+}
+
+export interface FlattenRedirectionsOutput {
+    rules: Record<string, FlattenedRedirection>;
+}
+
+export interface LintInput {
+    router_config: RouterConfig;
+    rules: Rule[];
+}
+
+export interface LintProjectInput {
+    change_set: RuleChangeSet;
+}
+
+export type LintIssue =
+    | { type: 'duplicate_source'; rule_ids: string[] }
+    | { type: 'shadowed'; by_rule_ids: string[] }
+    | { type: 'example_not_matching'; example: Example }
+    | { type: 'self_redirect'; url: string; target: string };
+
+export interface LintOutput {
+    rule_count: number;
+    issue_count: number;
+    rules: Record<string, { rule: Rule; issues: LintIssue[] }>;
+}
+
+export interface RuleDiagnostic {
+    rule_id: string;
+    path: string;
+    severity: 'error' | 'warning';
+    message: string;
+}
@@ -3,7 +3,9 @@ use std::{os::raw::c_char, ptr::null};
 use serde_json::{from_str as json_decode, to_string as json_encode};
 
 use crate::{
+    Error, RateLimiter,
     action::Action,
+    error::ffi::set_last_error,
     ffi_helpers::{c_char_to_str, string_to_c_char},
     filter::{Buffer, FilterBodyAction},
     http::ffi::{HeaderMap, header_map_to_http_headers, http_headers_to_header_map},
@@ -20,8 +22,8 @@ pub extern "C" fn redirectionio_action_json_deserialize(str: *mut c_char) -> *co
     };
 
     let action = match json_decode(action_str) {
-        Err(error) => {
-            log::error!("Unable to deserialize \"{action_str}\" to action: {error}");
+        Err(source) => {
+            set_last_error(Error::Decode { input: "action", source });
 
             return null();
         }
@@ -44,7 +46,7 @@ pub extern "C" fn redirectionio_action_json_serialize(_action: *mut Action) -> *
     let action = unsafe { &*_action };
     let action_serialized = match json_encode(action) {
         Err(error) => {
-            log::error!("Unable to serialize to action: {error}");
+            set_last_error(Error::Encode(error));
 
             return null();
         }
@@ -76,6 +78,71 @@ pub extern "C" fn redirectionio_action_get_status_code(_action: *mut Action, res
     action.get_status_code(response_status_code, None)
 }
 
+/// Get the status code to send once the backend response is known, so response header conditions can be evaluated
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_action_get_status_code_with_headers(
+    _action: *mut Action,
+    response_status_code: u16,
+    response_header_map: *const HeaderMap,
+) -> u16 {
+    if _action.is_null() {
+        return 0;
+    }
+
+    // SAFETY: _action is a valid pointer to an Action
+    let action = unsafe { &mut *_action };
+    let headers = header_map_to_http_headers(response_header_map);
+
+    action.get_status_code_with_headers(response_status_code, headers.as_ref(), None)
+}
+
+/// Count the request against the rate limits of the action
+///
+/// Returns the status code to send when a limit is exceeded, 0 otherwise
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_action_check_rate_limits(_action: *mut Action, _rate_limiter: *const RateLimiter) -> u16 {
+    if _action.is_null() || _rate_limiter.is_null() {
+        return 0;
+    }
+
+    // SAFETY: _action is a valid pointer to an Action
+    let action = unsafe { &mut *_action };
+    // SAFETY: _rate_limiter is a valid pointer to a RateLimiter
+    let rate_limiter = unsafe { &*_rate_limiter };
+
+    action.check_rate_limits(rate_limiter, None)
+}
+
+/// Whether the action serves a static response, the backend must not be called in this case
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_action_has_static_response(_action: *mut Action) -> bool {
+    if _action.is_null() {
+        return false;
+    }
+
+    // SAFETY: _action is a valid pointer to an Action
+    let action = unsafe { &*_action };
+
+    action.has_static_response()
+}
+
+/// Body of the static response, its status code is returned by `redirectionio_action_get_status_code` and its headers
+/// by `redirectionio_action_header_filter_filter`
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_action_get_static_response_body(_action: *mut Action) -> Buffer {
+    if _action.is_null() {
+        return Buffer::default();
+    }
+
+    // SAFETY: _action is a valid pointer to an Action
+    let action = unsafe { &mut *_action };
+
+    match action.get_static_response_body(None) {
+        None => Buffer::default(),
+        Some(body) => Buffer::from_string(body.to_string()),
+    }
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn redirectionio_action_header_filter_filter(
     _action: *mut Action,
@@ -96,6 +163,27 @@ pub extern "C" fn redirectionio_action_header_filter_filter(
     http_headers_to_header_map(headers)
 }
 
+/// Filter the headers of the request forwarded to the backend or the peer
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_action_request_header_filter_filter(
+    _action: *mut Action,
+    header_map: *const HeaderMap,
+) -> *const HeaderMap {
+    if _action.is_null() {
+        return header_map;
+    }
+
+    // SAFETY: _action is a valid pointer to an Action
+    let action = unsafe { &mut *_action };
+    let mut headers = header_map_to_http_headers(header_map);
+
+    headers = action.filter_request_headers(headers, None);
+
+    http_headers_to_header_map(headers)
+}
+
+/// Create a filter of the response body, returns null when there is nothing to filter or when the body cannot be
+/// filtered, like with an unsupported content-encoding, in which case the last error is set
 #[unsafe(no_mangle)]
 pub extern "C" fn redirectionio_action_body_filter_create(
     _action: *mut Action,
@@ -110,12 +198,19 @@ pub extern "C" fn redirectionio_action_body_filter_create(
     let action = unsafe { &mut *_action };
     let headers = header_map_to_http_headers(response_header_map);
 
-    match action.create_filter_body(response_status_code, headers.as_ref(), None) {
-        None => null(),
-        Some(filter_body) => Box::into_raw(Box::new(filter_body)),
+    match action.try_create_filter_body(response_status_code, headers.as_ref(), None) {
+        Ok(None) => null(),
+        Ok(Some(filter_body)) => Box::into_raw(Box::new(filter_body)),
+        Err(error) => {
+            set_last_error(Error::Filter(error));
+
+            null()
+        }
     }
 }
 
+/// Filter a chunk of the body, when filtering fails the last error is set and this chunk and the next ones are
+/// returned unmodified
 #[unsafe(no_mangle)]
 pub extern "C" fn redirectionio_action_body_filter_filter(_filter: *mut FilterBodyAction, buffer: Buffer) -> Buffer {
     if _filter.is_null() {
@@ -128,6 +223,10 @@ pub extern "C" fn redirectionio_action_body_filter_filter(_filter: *mut FilterBo
 
     let new_body = filter.filter(bytes, None);
 
+    if let Some(error) = filter.take_error() {
+        set_last_error(Error::Filter(error));
+    }
+
     Buffer::from_vec(new_body)
 }
 
@@ -139,9 +238,14 @@ pub extern "C" fn redirectionio_action_body_filter_close(_filter: *mut FilterBod
 
     // SAFETY: _filter is a valid pointer to a FilterBodyAction
     let filter = unsafe { Box::from_raw(_filter) };
-    let end_body = filter.end(None);
+    match filter.try_end(None) {
+        Ok(end_body) => Buffer::from_vec(end_body),
+        Err(error) => {
+            set_last_error(Error::Filter(error));
 
-    Buffer::from_vec(end_body)
+            Buffer::default()
+        }
+    }
 }
 
 #[unsafe(no_mangle)]
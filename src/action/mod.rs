@@ -2,18 +2,22 @@
 mod ffi;
 mod log_override;
 mod peer_override;
+mod rate_limit;
+mod response_header_condition;
 #[cfg(feature = "router")]
 mod run;
+mod static_response;
 mod status_code_update;
 #[cfg(feature = "router")]
 mod trace;
 mod unit_trace;
 
-use std::{cell::RefCell, fmt::Debug, rc::Rc};
+use std::{cell::RefCell, fmt::Debug, rc::Rc, time::Duration};
 #[cfg(feature = "router")]
 use std::{iter::FromIterator, sync::Arc};
 
 use linked_hash_set::LinkedHashSet;
+pub use response_header_condition::{ResponseHeaderCondition, ResponseHeaderConditionKind};
 #[cfg(feature = "router")]
 pub use run::RunExample;
 use serde::{Deserialize, Serialize};
@@ -25,7 +29,7 @@ pub use crate::action::unit_trace::UnitTrace;
 #[cfg(feature = "router")]
 use crate::api::Rule;
 #[cfg(feature = "router")]
-use crate::api::{HTMLBodyFilter, TextBodyFilter};
+use crate::api::{HTMLBodyFilter, TextAction, TextBodyFilter};
 #[cfg(feature = "router")]
 use crate::http::Request;
 #[cfg(feature = "router")]
@@ -33,9 +37,12 @@ use crate::marker::StaticOrDynamic;
 #[cfg(feature = "router")]
 use crate::router::Route;
 use crate::{
-    action::{log_override::LogOverride, peer_override::PeerOverride},
+    RateLimiter,
+    action::{
+        log_override::LogOverride, peer_override::PeerOverride, rate_limit::RateLimitAction, static_response::StaticResponseOverride,
+    },
     api::{BodyFilter, HeaderFilter, Peer, VariableValue},
-    filter::{FilterBodyAction, FilterHeaderAction},
+    filter::{FilterBodyAction, FilterBodyError, FilterHeaderAction},
     http::Header,
 };
 
@@ -44,6 +51,8 @@ pub struct Action {
     status_code_update: Option<StatusCodeUpdate>,
     header_filters: Vec<HeaderFilterAction>,
     body_filters: Vec<BodyFilterAction>,
+    #[serde(default)]
+    request_header_filters: Vec<RequestHeaderFilterAction>,
     // In 3.0 remove this
     pub rule_ids: LinkedHashSet<String>,
     #[serde(default)]
@@ -53,6 +62,10 @@ pub struct Action {
     log_override: Option<LogOverride>,
     peer_override: Option<PeerOverride>,
     #[serde(default)]
+    static_response: Option<StaticResponseOverride>,
+    #[serde(default)]
+    rate_limits: Vec<RateLimitAction>,
+    #[serde(default)]
     variables: Vec<(String, VariableValue)>,
 }
 
@@ -61,6 +74,8 @@ pub struct RuleTrace {
     id: String,
     on_response_status_codes: Vec<u16>,
     exclude_response_status_codes: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    on_response_headers: Vec<ResponseHeaderCondition>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -68,6 +83,17 @@ struct HeaderFilterAction {
     filter: HeaderFilter,
     on_response_status_codes: Vec<u16>,
     exclude_response_status_codes: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    on_response_headers: Vec<ResponseHeaderCondition>,
+    rule_id: Option<String>,
+    /// Location of the redirection of the rule, not sent when a static response is served instead
+    #[serde(default)]
+    redirect_location: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RequestHeaderFilterAction {
+    filter: HeaderFilter,
     rule_id: Option<String>,
 }
 
@@ -76,6 +102,8 @@ struct BodyFilterAction {
     filter: BodyFilter,
     on_response_status_codes: Vec<u16>,
     exclude_response_status_codes: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    on_response_headers: Vec<ResponseHeaderCondition>,
     rule_id: Option<String>,
 }
 
@@ -85,11 +113,14 @@ impl Default for Action {
             status_code_update: None,
             header_filters: Vec::new(),
             body_filters: Vec::new(),
+            request_header_filters: Vec::new(),
             rule_ids: LinkedHashSet::new(),
             rule_traces: Vec::new(),
             rules_applied: LinkedHashSet::new(),
             log_override: None,
             peer_override: None,
+            static_response: None,
+            rate_limits: Vec::new(),
             variables: Vec::new(),
         }
     }
@@ -148,13 +179,25 @@ impl Action {
             None => Vec::new(),
             Some(codes) => codes.clone(),
         };
+        let on_response_headers = rule.response_header_conditions();
+        // The backend is not called when serving a static response, so it cannot depend on the response
+        let respond_with = rule
+            .respond_with
+            .as_ref()
+            .filter(|_| on_response_status_codes.is_empty() && on_response_headers.is_empty());
+
+        let status_code = match respond_with {
+            Some(respond_with) => respond_with.status_code,
+            None => rule.status_code.unwrap_or(0),
+        };
 
-        let status_code_update = match rule.status_code.unwrap_or(0) {
+        let status_code_update = match status_code {
             0 => None,
             redirect_code => Some(StatusCodeUpdate {
                 status_code: redirect_code,
                 on_response_status_codes: on_response_status_codes.clone(),
                 exclude_response_status_codes: rule.source.exclude_response_status_codes.is_some(),
+                on_response_headers: on_response_headers.clone(),
                 fallback_status_code: 0,
                 rule_id: Some(rule.id.clone()),
                 fallback_rule_id: None,
@@ -194,7 +237,9 @@ impl Action {
                     Some(on_response) => on_response.clone(),
                 },
                 exclude_response_status_codes: rule.source.exclude_response_status_codes.is_some(),
+                on_response_headers: on_response_headers.clone(),
                 rule_id: Some(rule.id.clone()),
+                redirect_location: true,
             })
         }
 
@@ -210,7 +255,9 @@ impl Action {
                     },
                     on_response_status_codes: on_response_status_codes.clone(),
                     exclude_response_status_codes: rule.source.exclude_response_status_codes.is_some(),
+                    on_response_headers: on_response_headers.clone(),
                     rule_id: Some(rule.id.clone()),
+                    redirect_location: false,
                 });
             }
         }
@@ -223,6 +270,7 @@ impl Action {
                             action: html_body_filter.action.clone(),
                             css_selector: html_body_filter.css_selector.clone(),
                             element_tree: html_body_filter.element_tree.clone(),
+                            attribute: html_body_filter.attribute.clone(),
                             value: StaticOrDynamic::replace(html_body_filter.value.clone(), &variables, false),
                             inner_value: Some(StaticOrDynamic::replace(
                                 html_body_filter
@@ -237,7 +285,13 @@ impl Action {
                         }),
                         BodyFilter::Text(text_body_filter) => BodyFilter::Text(TextBodyFilter {
                             action: text_body_filter.action.clone(),
-                            content: StaticOrDynamic::replace(text_body_filter.content.clone(), &variables, true),
+                            content: if matches!(text_body_filter.action, TextAction::ReplaceRegex) {
+                                // `$` in the value of a variable must not be expanded as a captured group
+                                StaticOrDynamic::replace(text_body_filter.content.clone(), &escape_dollar(&variables), true)
+                            } else {
+                                StaticOrDynamic::replace(text_body_filter.content.clone(), &variables, true)
+                            },
+                            pattern: text_body_filter.pattern.clone(),
                             id: text_body_filter.id.clone(),
                             target_hash: text_body_filter.target_hash.clone(),
                         }),
@@ -247,6 +301,33 @@ impl Action {
                     },
                     on_response_status_codes: on_response_status_codes.clone(),
                     exclude_response_status_codes: rule.source.exclude_response_status_codes.is_some(),
+                    on_response_headers: on_response_headers.clone(),
+                    rule_id: Some(rule.id.clone()),
+                });
+            }
+        }
+
+        let mut peers = if on_response_status_codes.is_empty() && on_response_headers.is_empty() {
+            rule.peers(&variables)
+        } else {
+            Vec::new()
+        };
+        let mut request_header_filters = Vec::new();
+
+        // The request is forwarded before the response is known, so filters cannot depend on it
+        if let Some(rule_request_header_filters) = rule.request_header_filters.as_ref()
+            && on_response_status_codes.is_empty()
+            && on_response_headers.is_empty()
+        {
+            for filter in rule_request_header_filters {
+                request_header_filters.push(RequestHeaderFilterAction {
+                    filter: HeaderFilter {
+                        action: filter.action.clone(),
+                        header: filter.header.clone(),
+                        value: StaticOrDynamic::replace(filter.value.clone(), &variables, true),
+                        id: filter.id.clone(),
+                        target_hash: filter.target_hash.clone(),
+                    },
                     rule_id: Some(rule.id.clone()),
                 });
             }
@@ -256,10 +337,12 @@ impl Action {
             status_code_update,
             header_filters,
             body_filters,
+            request_header_filters,
             rule_ids: LinkedHashSet::from_iter(vec![rule.id.clone()]),
             rule_traces: vec![RuleTrace {
                 on_response_status_codes: on_response_status_codes.clone(),
                 exclude_response_status_codes: rule.source.exclude_response_status_codes.is_some(),
+                on_response_headers: on_response_headers.clone(),
                 id: rule.id.clone(),
             }],
             rules_applied: LinkedHashSet::new(),
@@ -272,16 +355,26 @@ impl Action {
                 fallback_rule_id: None,
                 unit_id: rule.configuration_log_unit_id.clone(),
             }),
-            peer_override: if let Some(peer) = rule.peer_override.clone()
-                && on_response_status_codes.is_empty()
-            {
+            peer_override: if peers.is_empty() {
+                None
+            } else {
                 Some(PeerOverride {
-                    peer,
+                    peer: peers.remove(0),
+                    fallback_peers: peers,
                     rule_id: Some(rule.id.clone()),
                     unit_id: rule.peer_unit_id.clone(),
                 })
-            } else {
-                None
+            },
+            static_response: respond_with.map(|respond_with| StaticResponseOverride::new(respond_with, &variables, rule.id.clone())),
+            rate_limits: match rule.rate_limit.as_ref() {
+                None => Vec::new(),
+                Some(rate_limit) => vec![RateLimitAction {
+                    key: format!("{}:{}", rule.id, StaticOrDynamic::replace(rate_limit.key.clone(), &variables, true)),
+                    window: rate_limit.window,
+                    limit: rate_limit.limit,
+                    status_code: rate_limit.status_code,
+                    rule_id: Some(rule.id.clone()),
+                }],
             },
             variables,
         };
@@ -296,20 +389,29 @@ impl Action {
 
     #[cfg(feature = "router")]
     pub fn merge(&mut self, other: Self) {
+        // A later rule always changing the status code no longer serves the static response of a previous one
+        if other.static_response.is_some()
+            || other
+                .status_code_update
+                .as_ref()
+                .is_some_and(|status_code_update| !status_code_update.has_response_conditions())
+        {
+            self.static_response = other.static_response;
+        }
+
         self.status_code_update = match other.status_code_update {
             None => self.status_code_update.clone(),
             Some(new_status_code_update) => match &self.status_code_update {
                 None => Some(new_status_code_update),
                 Some(old_status_code_update) => {
-                    if !old_status_code_update.on_response_status_codes.is_empty()
-                        || new_status_code_update.on_response_status_codes.is_empty()
-                    {
+                    if old_status_code_update.has_response_conditions() || !new_status_code_update.has_response_conditions() {
                         Some(new_status_code_update)
                     } else {
                         Some(StatusCodeUpdate {
                             status_code: new_status_code_update.status_code,
                             on_response_status_codes: new_status_code_update.on_response_status_codes,
                             exclude_response_status_codes: new_status_code_update.exclude_response_status_codes,
+                            on_response_headers: new_status_code_update.on_response_headers,
                             fallback_status_code: old_status_code_update.status_code,
                             rule_id: new_status_code_update.rule_id,
                             target_hash: new_status_code_update.target_hash,
@@ -329,6 +431,14 @@ impl Action {
             self.body_filters.push(filter);
         }
 
+        for filter in other.request_header_filters {
+            self.request_header_filters.push(filter);
+        }
+
+        for rate_limit in other.rate_limits {
+            self.rate_limits.push(rate_limit);
+        }
+
         for rule_id in other.rule_ids {
             self.rule_ids.insert(rule_id);
         }
@@ -422,6 +532,9 @@ impl Action {
 
                         if let Some(unit_id) = &peer_override.unit_id {
                             trace.borrow_mut().add_unit_id_with_target("peer_override", unit_id);
+                            trace
+                                .borrow_mut()
+                                .add_value_computed_by_unit(unit_id, peer_override.peer.address.as_str());
                         }
                     }
                 }
@@ -431,11 +544,78 @@ impl Action {
         }
     }
 
+    /// Count the request against the rate limits of the action, it must be called once per request before calling the
+    /// backend
+    ///
+    /// Returns the status code to send when a limit is exceeded, 0 otherwise
+    pub fn check_rate_limits(&mut self, rate_limiter: &RateLimiter, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> u16 {
+        let mut status_code = 0;
+
+        for rate_limit in &self.rate_limits {
+            let over_limit = rate_limiter.hit(rate_limit.key.as_str(), Duration::from_secs(rate_limit.window), rate_limit.limit);
+
+            if !over_limit || status_code != 0 {
+                continue;
+            }
+
+            status_code = rate_limit.status_code;
+
+            if let Some(rule_id) = rate_limit.rule_id.as_ref() {
+                self.rules_applied.insert(rule_id.clone());
+
+                if let Some(trace) = &unit_trace {
+                    trace.borrow_mut().rule_ids_applied.insert(rule_id.clone());
+                }
+            }
+        }
+
+        status_code
+    }
+
+    pub fn has_static_response(&self) -> bool {
+        self.static_response.is_some()
+    }
+
+    /// Body of the response to serve without calling the backend, its status code is returned by `get_status_code`
+    /// and its content type is added by `filter_headers`, which also drops the location of the redirections
+    pub fn get_static_response_body(&mut self, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Option<&str> {
+        let static_response = self.static_response.as_ref()?;
+
+        if let Some(rule_id) = static_response.rule_id.as_ref() {
+            self.rules_applied.insert(rule_id.clone());
+
+            if let Some(trace) = &unit_trace {
+                trace.borrow_mut().rule_ids_applied.insert(rule_id.clone());
+            }
+        }
+
+        Some(static_response.body.as_str())
+    }
+
+    /// Peers to try, in order, when the one returned by `get_peer` is not available
+    pub fn get_fallback_peers(&self) -> &[Peer] {
+        match self.peer_override.as_ref() {
+            None => &[],
+            Some(peer_override) => peer_override.fallback_peers.as_slice(),
+        }
+    }
+
     pub fn get_status_code(&mut self, response_status_code: u16, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> u16 {
+        self.get_status_code_with_headers(response_status_code, &[], unit_trace)
+    }
+
+    /// Same as `get_status_code`, once the backend response is known so its headers can be matched against the
+    /// response header conditions of the rules
+    pub fn get_status_code_with_headers(
+        &mut self,
+        response_status_code: u16,
+        response_headers: &[Header],
+        unit_trace: Option<Rc<RefCell<UnitTrace>>>,
+    ) -> u16 {
         match self.status_code_update.as_ref() {
             None => 0,
             Some(status_code_update) => {
-                let (status, rule_applied) = status_code_update.get_status_code(response_status_code);
+                let (status, rule_applied) = status_code_update.get_status_code(response_status_code, response_headers);
 
                 if let Some(rule_id) = rule_applied {
                     if let Some(trace) = unit_trace {
@@ -463,7 +643,25 @@ impl Action {
     ) -> Vec<Header> {
         let mut filters = Vec::new();
 
+        if let Some(content_type) = self
+            .static_response
+            .as_ref()
+            .and_then(|static_response| static_response.content_type.as_ref())
+        {
+            filters.push(HeaderFilter {
+                action: "override".to_string(),
+                header: "Content-Type".to_string(),
+                value: content_type.clone(),
+                id: None,
+                target_hash: None,
+            });
+        }
+
         for trace in &self.rule_traces {
+            if !ResponseHeaderCondition::match_all(&trace.on_response_headers, &headers) {
+                continue;
+            }
+
             if trace.on_response_status_codes.is_empty() {
                 self.rules_applied.insert(trace.id.clone());
                 continue;
@@ -480,6 +678,14 @@ impl Action {
         }
 
         for filter in self.header_filters.as_slice() {
+            if filter.redirect_location && self.static_response.is_some() {
+                continue;
+            }
+
+            if !ResponseHeaderCondition::match_all(&filter.on_response_headers, &headers) {
+                continue;
+            }
+
             if !filter.on_response_status_codes.is_empty() {
                 if !filter.exclude_response_status_codes && !filter.on_response_status_codes.contains(&response_status_code) {
                     continue;
@@ -516,14 +722,58 @@ impl Action {
         new_headers
     }
 
+    /// Filter the headers of the request forwarded to the backend or the peer
+    pub fn filter_request_headers(&mut self, headers: Vec<Header>, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Vec<Header> {
+        let mut filters = Vec::new();
+
+        for filter in self.request_header_filters.as_slice() {
+            filters.push(filter.filter.clone());
+
+            if let Some(rule_id) = filter.rule_id.as_ref() {
+                self.rules_applied.insert(rule_id.clone());
+
+                if let Some(trace) = &unit_trace {
+                    trace.borrow_mut().rule_ids_applied.insert(rule_id.clone());
+                }
+            }
+        }
+
+        match FilterHeaderAction::new(filters) {
+            None => headers,
+            Some(filter_action) => filter_action.filter(headers, unit_trace),
+        }
+    }
+
     pub fn create_filter_body(
         &mut self,
         response_status_code: u16,
         headers: &[Header],
         unit_trace: Option<Rc<RefCell<UnitTrace>>>,
     ) -> Option<FilterBodyAction> {
+        match self.try_create_filter_body(response_status_code, headers, unit_trace) {
+            Ok(body_filter) => body_filter,
+            Err(err) => {
+                log::error!("{err}, filtering will be disabled for this request");
+
+                None
+            }
+        }
+    }
+
+    /// Same as `create_filter_body`, but fails when the body cannot be filtered, e.g. with an unsupported
+    /// content-encoding, instead of disabling the filtering
+    pub fn try_create_filter_body(
+        &mut self,
+        response_status_code: u16,
+        headers: &[Header],
+        unit_trace: Option<Rc<RefCell<UnitTrace>>>,
+    ) -> Result<Option<FilterBodyAction>, FilterBodyError> {
         let mut filters = Vec::new();
         for filter in self.body_filters.as_slice() {
+            if !ResponseHeaderCondition::match_all(&filter.on_response_headers, headers) {
+                continue;
+            }
+
             if !filter.on_response_status_codes.is_empty() {
                 if !filter.exclude_response_status_codes && !filter.on_response_status_codes.contains(&response_status_code) {
                     continue;
@@ -541,9 +791,9 @@ impl Action {
             filters.push(filter.filter.clone());
         }
 
-        let body_filter = FilterBodyAction::new(filters, headers, unit_trace, self.variables.clone());
+        let body_filter = FilterBodyAction::try_new(filters, headers, unit_trace, self.variables.clone())?;
 
-        if body_filter.is_empty() { None } else { Some(body_filter) }
+        Ok(if body_filter.is_empty() { None } else { Some(body_filter) })
     }
 
     pub fn should_log_request(
@@ -571,8 +821,12 @@ impl Action {
     }
 
     pub fn need_proxification(&self) -> bool {
+        if self.static_response.is_some() {
+            return false;
+        }
+
         if let Some(update) = &self.status_code_update
-            && !update.on_response_status_codes.is_empty()
+            && update.has_response_conditions()
         {
             return true;
         }
@@ -591,6 +845,258 @@ impl Action {
             return true;
         }
 
+        if !self.request_header_filters.is_empty() {
+            return true;
+        }
+
         false
     }
 }
+
+#[cfg(feature = "router")]
+fn escape_dollar(variables: &[(String, VariableValue)]) -> Vec<(String, VariableValue)> {
+    variables
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                VariableValue::Value(value) => VariableValue::Value(value.replace('$', "$$")),
+                VariableValue::HtmlFilter {
+                    selector,
+                    default,
+                    transformers,
+                } => VariableValue::HtmlFilter {
+                    selector: selector.clone(),
+                    default: default.as_ref().map(|default| default.replace('$', "$$")),
+                    transformers: transformers.clone(),
+                },
+            };
+
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "router"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::{CounterStore, router::Router};
+
+    fn action(rules: &[&str], url: &str) -> Action {
+        let mut router = Router::<Rule>::default();
+
+        for rule in rules {
+            router.insert(Rule::from_json(rule).unwrap());
+        }
+
+        let request = Request::rebuild_with_config(&router.config, &url.parse().unwrap());
+        let routes = router.match_request(&request);
+
+        Action::from_routes_rule(routes, &request, None)
+    }
+
+    fn header(name: &str, value: &str) -> Header {
+        Header {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn pairs(headers: Vec<Header>) -> Vec<(String, String)> {
+        headers.into_iter().map(|header| (header.name, header.value)).collect()
+    }
+
+    fn request_headers() -> Vec<Header> {
+        vec![
+            header("Cookie", "session=abc"),
+            header("X-Forwarded-Prefix", "/old"),
+            header("Accept", "text/html"),
+        ]
+    }
+
+    #[test]
+    fn test_filter_request_headers() {
+        let mut action = action(
+            &[
+                r#"{"id":"a","rank":0,"source":{"path":"/blog/@slug"},"markers":[{"name":"slug","regex":"[a-z-]+","transformers":[]}],"request_header_filters":[{"action":"add","header":"X-Slug","value":"@slug","id":null,"target_hash":null},{"action":"override","header":"X-Forwarded-Prefix","value":"/blog","id":null,"target_hash":null},{"action":"remove","header":"Cookie","value":"","id":null,"target_hash":null}]}"#,
+            ],
+            "/blog/hello-world",
+        );
+
+        assert!(action.need_proxification());
+
+        let headers = action.filter_request_headers(request_headers(), None);
+
+        assert_eq!(
+            pairs(headers),
+            pairs(vec![
+                header("X-Forwarded-Prefix", "/blog"),
+                header("Accept", "text/html"),
+                header("X-Slug", "hello-world"),
+            ])
+        );
+        assert_eq!(action.get_applied_rule_ids_vec(), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_replace_regex_does_not_expand_variables() {
+        let mut action = action(
+            &[
+                r#"{"id":"a","rank":0,"source":{"path":"/price/@amount"},"markers":[{"name":"amount","regex":".+","transformers":[]}],"body_filters":[{"action":"replace_regex_text","pattern":"(PRICE)","content":"@amount for $1","id":null,"target_hash":null}]}"#,
+            ],
+            "/price/$1",
+        );
+
+        let mut filter = action
+            .create_filter_body(200, &[header("Content-Type", "text/plain")], None)
+            .unwrap();
+        let mut body = filter.filter(b"PRICE".to_vec(), None);
+        body.extend(filter.end(None));
+
+        assert_eq!(String::from_utf8(body).unwrap(), "$1 for PRICE");
+    }
+
+    #[test]
+    fn test_get_peer_upstream_path() {
+        let rule = r#"{"id":"a","rank":0,"source":{"path":"/blog/@slug"},"markers":[{"name":"slug","regex":"[^/]+","transformers":[]}],"peer_override":{"address":"127.0.0.1:8080","sni_host":null,"request_host":null,"allow_invalid_certificates":false,"tls":false,"upstream_path":"/wp/index.php?p=@slug"}}"#;
+
+        let mut hello_action = action(&[rule], "/blog/hello-world");
+        let peer = hello_action.get_peer(None).unwrap();
+
+        assert_eq!(peer.address, "127.0.0.1:8080");
+        assert_eq!(peer.upstream_path.as_deref(), Some("/wp/index.php?p=hello-world"));
+        assert_eq!(hello_action.get_applied_rule_ids_vec(), vec!["a".to_string()]);
+
+        let mut escaped_action = action(&[rule], "/blog/a&b=c+d");
+        let peer = escaped_action.get_peer(None).unwrap();
+
+        assert_eq!(peer.upstream_path.as_deref(), Some("/wp/index.php?p=a%26b%3Dc%2Bd"));
+    }
+
+    #[test]
+    fn test_filter_request_headers_with_response_conditions() {
+        let mut action = action(
+            &[
+                r#"{"id":"a","rank":0,"source":{"path":"/foo","response_status_codes":[404]},"request_header_filters":[{"action":"remove","header":"Cookie","value":"","id":null,"target_hash":null}]}"#,
+                r#"{"id":"b","rank":0,"source":{"path":"/foo","response_headers":[{"type":"is_defined","name":"X-Cache","value":null}]},"request_header_filters":[{"action":"add","header":"X-Foo","value":"bar","id":null,"target_hash":null}]}"#,
+            ],
+            "/foo",
+        );
+
+        assert_eq!(
+            pairs(action.filter_request_headers(request_headers(), None)),
+            pairs(request_headers())
+        );
+        assert!(action.get_applied_rule_ids().is_empty());
+    }
+
+    #[test]
+    fn test_static_response_wins_over_redirect() {
+        let mut action = action(
+            &[
+                r#"{"id":"static","rank":0,"source":{"path":"/gone"},"respond_with":{"status_code":410,"content_type":"text/plain","body":"gone"}}"#,
+                r#"{"id":"redirect","rank":1,"source":{"path":"/gone"},"target":"/b","status_code":301}"#,
+            ],
+            "/gone",
+        );
+
+        assert!(action.has_static_response());
+        assert!(!action.need_proxification());
+        assert_eq!(action.get_status_code(0, None), 410);
+        assert_eq!(action.get_static_response_body(None), Some("gone"));
+        assert_eq!(
+            pairs(action.filter_headers(Vec::new(), 410, false, None)),
+            pairs(vec![header("Content-Type", "text/plain")])
+        );
+    }
+
+    #[test]
+    fn test_redirect_wins_over_static_response() {
+        let mut action = action(
+            &[
+                r#"{"id":"static","rank":1,"source":{"path":"/gone"},"respond_with":{"status_code":410,"content_type":"text/plain","body":"gone"}}"#,
+                r#"{"id":"redirect","rank":0,"source":{"path":"/gone"},"target":"/b","status_code":301}"#,
+            ],
+            "/gone",
+        );
+
+        assert!(!action.has_static_response());
+        assert_eq!(action.get_status_code(0, None), 301);
+        assert_eq!(action.get_static_response_body(None), None);
+        assert_eq!(
+            pairs(action.filter_headers(Vec::new(), 301, false, None)),
+            pairs(vec![header("Location", "/b")])
+        );
+    }
+
+    #[test]
+    fn test_static_response_body_variables() {
+        let mut html_action = action(
+            &[
+                r#"{"id":"a","rank":0,"source":{"path":"/gone/@slug"},"markers":[{"name":"slug","regex":"[^/]+","transformers":[]}],"respond_with":{"status_code":410,"content_type":"text/html; charset=utf-8","body":"<p>@slug is gone</p>"}}"#,
+            ],
+            "/gone/a&b",
+        );
+
+        assert_eq!(html_action.get_static_response_body(None), Some("<p>a&amp;b is gone</p>"));
+
+        let mut text_action = action(
+            &[
+                r#"{"id":"a","rank":0,"source":{"path":"/gone/@slug"},"markers":[{"name":"slug","regex":"[^/]+","transformers":[]}],"respond_with":{"status_code":410,"content_type":"text/plain","body":"@slug is gone"}}"#,
+            ],
+            "/gone/a&b",
+        );
+
+        assert_eq!(text_action.get_static_response_body(None), Some("a&b is gone"));
+    }
+
+    // Record the keys counted by the rate limiter, counters never reset
+    #[derive(Debug, Clone, Default)]
+    struct RecordingStore {
+        keys: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl CounterStore for RecordingStore {
+        fn increment(&self, key: &str, _window: Duration, _now: DateTime<Utc>) -> u64 {
+            let mut keys = self.keys.lock().unwrap();
+            keys.push(key.to_string());
+
+            keys.iter().filter(|counted| counted.as_str() == key).count() as u64
+        }
+    }
+
+    #[test]
+    fn test_check_rate_limits() {
+        let store = RecordingStore::default();
+        let rate_limiter = RateLimiter::new(Box::new(store.clone()));
+        let rules = [
+            r#"{"id":"a","rank":1,"source":{"path":"/api/@slug"},"markers":[{"name":"slug","regex":"[a-z]+","transformers":[]}],"rate_limit":{"key":"@slug","window":60,"limit":2}}"#,
+            r#"{"id":"b","rank":0,"source":{"path":"/api/@slug"},"markers":[{"name":"slug","regex":"[a-z]+","transformers":[]}],"rate_limit":{"key":"","window":60,"limit":1,"status_code":503}}"#,
+        ];
+
+        let mut first_action = action(&rules, "/api/foo");
+
+        assert_eq!(first_action.check_rate_limits(&rate_limiter, None), 0);
+        assert!(first_action.get_applied_rule_ids().is_empty());
+        assert_eq!(*store.keys.lock().unwrap(), vec!["a:foo".to_string(), "b:".to_string()]);
+
+        let mut second_action = action(&rules, "/api/foo");
+
+        assert_eq!(second_action.check_rate_limits(&rate_limiter, None), 503);
+        assert_eq!(second_action.get_applied_rule_ids_vec(), vec!["b".to_string()]);
+
+        // The first rule exceeding its limit gives the status code, every rule is still counted
+        let mut third_action = action(&rules, "/api/foo");
+
+        assert_eq!(third_action.check_rate_limits(&rate_limiter, None), 429);
+        assert_eq!(third_action.get_applied_rule_ids_vec(), vec!["a".to_string()]);
+
+        let mut other_action = action(&rules, "/api/bar");
+
+        assert_eq!(other_action.check_rate_limits(&rate_limiter, None), 503);
+        assert_eq!(store.keys.lock().unwrap().len(), 8);
+    }
+}
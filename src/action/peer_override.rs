@@ -5,6 +5,9 @@ use crate::api::Peer;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PeerOverride {
     pub peer: Peer,
+    /// Peers to try, in order, when the selected one is not available
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_peers: Vec<Peer>,
     pub rule_id: Option<String>,
     pub unit_id: Option<String>,
 }
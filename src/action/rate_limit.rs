@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimitAction {
+    /// Counter key, prefixed by the rule id so rules never share their counters
+    pub key: String,
+    pub window: u64,
+    pub limit: u64,
+    pub status_code: u16,
+    pub rule_id: Option<String>,
+}
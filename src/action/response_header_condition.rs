@@ -0,0 +1,139 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{http::Header, regex::LazyRegex};
+
+/// Condition on a header of the backend response, it can only be evaluated once the response is known
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResponseHeaderCondition {
+    pub name: String,
+    pub kind: ResponseHeaderConditionKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseHeaderConditionKind {
+    IsDefined,
+    IsNotDefined,
+    IsEquals(String),
+    IsNotEqualTo(String),
+    Contains(String),
+    DoesNotContain(String),
+    EndsWith(String),
+    StartsWith(String),
+    /// Compiled when the condition is created, so it is not compiled again for each response
+    MatchRegex(#[serde(deserialize_with = "deserialize_regex")] LazyRegex),
+}
+
+impl ResponseHeaderCondition {
+    /// Match the condition against the response headers, header names are compared without case
+    pub fn match_headers(&self, headers: &[Header]) -> bool {
+        let values = headers
+            .iter()
+            .filter(|header| header.name.eq_ignore_ascii_case(self.name.as_str()))
+            .map(|header| header.value.as_str())
+            .collect::<Vec<_>>();
+
+        match &self.kind {
+            ResponseHeaderConditionKind::IsDefined => !values.is_empty(),
+            ResponseHeaderConditionKind::IsNotDefined => values.is_empty(),
+            ResponseHeaderConditionKind::IsEquals(str) => values.iter().any(|value| value == str),
+            ResponseHeaderConditionKind::IsNotEqualTo(str) => values.iter().all(|value| value != str),
+            ResponseHeaderConditionKind::Contains(str) => values.iter().any(|value| value.contains(str.as_str())),
+            ResponseHeaderConditionKind::DoesNotContain(str) => values.iter().all(|value| !value.contains(str.as_str())),
+            ResponseHeaderConditionKind::EndsWith(str) => values.iter().any(|value| value.ends_with(str.as_str())),
+            ResponseHeaderConditionKind::StartsWith(str) => values.iter().any(|value| value.starts_with(str.as_str())),
+            ResponseHeaderConditionKind::MatchRegex(regex) => match regex.regex() {
+                Some(regex) => values.iter().any(|value| regex.is_match(value)),
+                None => false,
+            },
+        }
+    }
+
+    /// Match all the conditions against the response headers
+    pub fn match_all(conditions: &[ResponseHeaderCondition], headers: &[Header]) -> bool {
+        conditions.iter().all(|condition| condition.match_headers(headers))
+    }
+}
+
+impl ResponseHeaderConditionKind {
+    pub fn match_regex(regex: String) -> Self {
+        Self::MatchRegex(LazyRegex::new(regex, false).compile())
+    }
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LazyRegex, D::Error> {
+    Ok(LazyRegex::new(String::deserialize(deserializer)?, false).compile())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, value: &str) -> Header {
+        Header {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn condition(name: &str, kind: ResponseHeaderConditionKind) -> ResponseHeaderCondition {
+        ResponseHeaderCondition {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_match_headers() {
+        let headers = vec![
+            header("Content-Type", "text/html; charset=utf-8"),
+            header("Cache-Control", "public"),
+            header("Cache-Control", "max-age=60"),
+        ];
+
+        assert!(condition("content-type", ResponseHeaderConditionKind::StartsWith("text/html".to_string())).match_headers(&headers));
+        assert!(condition("Cache-Control", ResponseHeaderConditionKind::DoesNotContain("private".to_string())).match_headers(&headers));
+        assert!(!condition("Cache-Control", ResponseHeaderConditionKind::IsNotEqualTo("public".to_string())).match_headers(&headers));
+        assert!(condition("X-Env", ResponseHeaderConditionKind::IsNotDefined).match_headers(&headers));
+        assert!(condition("X-Env", ResponseHeaderConditionKind::DoesNotContain("staging".to_string())).match_headers(&headers));
+        assert!(!condition("X-Env", ResponseHeaderConditionKind::IsEquals("staging".to_string())).match_headers(&headers));
+        assert!(
+            condition(
+                "Cache-Control",
+                ResponseHeaderConditionKind::match_regex("max-age=[0-9]+".to_string())
+            )
+            .match_headers(&headers)
+        );
+        assert!(
+            !condition(
+                "Cache-Control",
+                ResponseHeaderConditionKind::match_regex("no-(?:store|cache)".to_string())
+            )
+            .match_headers(&headers)
+        );
+    }
+
+    #[test]
+    fn test_regex_is_compiled_once() {
+        let condition = condition(
+            "Cache-Control",
+            ResponseHeaderConditionKind::match_regex("max-age=[0-9]+".to_string()),
+        );
+
+        let ResponseHeaderConditionKind::MatchRegex(regex) = &condition.kind else {
+            panic!("expected a regex condition");
+        };
+
+        assert!(regex.compiled.get().is_some());
+
+        let serialized = serde_json::to_string(&condition).unwrap();
+        let deserialized: ResponseHeaderCondition = serde_json::from_str(serialized.as_str()).unwrap();
+
+        let ResponseHeaderConditionKind::MatchRegex(regex) = &deserialized.kind else {
+            panic!("expected a regex condition");
+        };
+
+        assert!(regex.compiled.get().is_some());
+        assert!(deserialized.match_headers(&[header("Cache-Control", "max-age=60")]));
+    }
+}
@@ -3,6 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use serde::Serialize;
 
 use crate::{
+    Error,
     action::{Action, UnitTrace},
     api::{Example, RedirectionLoop, Rule},
     http::{Header, Request},
@@ -28,7 +29,7 @@ pub struct RunResponse {
 }
 
 impl RunExample {
-    pub fn new(router: &Router<Rule>, example: &Example) -> Result<Self, http::Error> {
+    pub fn new(router: &Router<Rule>, example: &Example) -> Result<Self, Error> {
         let request = Request::from_example(&router.config, example)?;
         let routes = router.match_request(&request);
         let unit_trace = Rc::new(RefCell::new(UnitTrace::default()));
@@ -56,6 +57,12 @@ impl RunExample {
     </body>
 </html>";
 
+        let static_body;
+        if let Some(static_response_body) = action.get_static_response_body(Some(unit_trace.clone())) {
+            static_body = static_response_body.to_string();
+            body = static_body.as_str();
+        }
+
         let mut b1;
         if let Some(mut body_filter) = action.create_filter_body(backend_status_code, &[], Some(unit_trace.clone())) {
             b1 = body_filter.filter(body.into(), Some(unit_trace.clone()));
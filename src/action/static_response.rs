@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "router")]
+use crate::{
+    api::{StaticResponse, VariableValue},
+    marker::StaticOrDynamic,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaticResponseOverride {
+    pub body: String,
+    /// Only sent with the static response, it must not leak into a response of the backend or of another rule
+    #[serde(default)]
+    pub content_type: Option<String>,
+    pub rule_id: Option<String>,
+}
+
+impl StaticResponseOverride {
+    #[cfg(feature = "router")]
+    pub fn new(respond_with: &StaticResponse, variables: &[(String, VariableValue)], rule_id: String) -> Self {
+        let is_html = respond_with
+            .content_type
+            .as_ref()
+            .is_some_and(|content_type| content_type.to_ascii_lowercase().contains("html"));
+
+        let body = if is_html {
+            let escaped_variables = variables
+                .iter()
+                .map(|(name, value)| (name.clone(), value.map_value(escape_html)))
+                .collect::<Vec<_>>();
+
+            StaticOrDynamic::replace(respond_with.body.clone(), &escaped_variables, true)
+        } else {
+            StaticOrDynamic::replace(respond_with.body.clone(), variables, true)
+        };
+
+        Self {
+            body,
+            content_type: respond_with.content_type.clone(),
+            rule_id: Some(rule_id),
+        }
+    }
+}
+
+#[cfg(feature = "router")]
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
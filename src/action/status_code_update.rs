@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
 
+use super::ResponseHeaderCondition;
+use crate::http::Header;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StatusCodeUpdate {
     pub status_code: u16,
     pub on_response_status_codes: Vec<u16>,
     pub exclude_response_status_codes: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_response_headers: Vec<ResponseHeaderCondition>,
     pub fallback_status_code: u16,
     pub rule_id: Option<String>,
     pub fallback_rule_id: Option<String>,
@@ -13,7 +18,11 @@ pub struct StatusCodeUpdate {
 }
 
 impl StatusCodeUpdate {
-    pub fn get_status_code(&self, response_status_code: u16) -> (u16, Option<&String>) {
+    pub fn get_status_code(&self, response_status_code: u16, response_headers: &[Header]) -> (u16, Option<&String>) {
+        if !self.on_response_headers.is_empty() {
+            return self.get_status_code_on_response(response_status_code, response_headers);
+        }
+
         if response_status_code == 0 && self.on_response_status_codes.is_empty() {
             return (self.status_code, self.rule_id.as_ref());
         }
@@ -32,4 +41,25 @@ impl StatusCodeUpdate {
 
         (0, None)
     }
+
+    /// Whether the status code depends on the backend response
+    pub fn has_response_conditions(&self) -> bool {
+        !self.on_response_status_codes.is_empty() || !self.on_response_headers.is_empty()
+    }
+
+    // Response headers are only known once the backend has been called, so the status code cannot be applied before
+    fn get_status_code_on_response(&self, response_status_code: u16, response_headers: &[Header]) -> (u16, Option<&String>) {
+        if response_status_code == 0 {
+            return (0, None);
+        }
+
+        let status_code_matched = self.on_response_status_codes.is_empty()
+            || self.exclude_response_status_codes != self.on_response_status_codes.contains(&response_status_code);
+
+        if status_code_matched && ResponseHeaderCondition::match_all(&self.on_response_headers, response_headers) {
+            return (self.status_code, self.rule_id.as_ref());
+        }
+
+        (self.fallback_status_code, self.fallback_rule_id.as_ref())
+    }
 }
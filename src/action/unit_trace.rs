@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "router")]
 use crate::{
+    Error,
     action::run::RunExample,
     api::{Example, Rule},
     router::Router,
@@ -39,7 +40,7 @@ pub struct UnitTrace {
 
 impl UnitTrace {
     #[cfg(feature = "router")]
-    pub fn from_example(router: &Router<Rule>, example: &Example) -> Result<Self, http::Error> {
+    pub fn from_example(router: &Router<Rule>, example: &Example) -> Result<Self, Error> {
         let run = RunExample::new(router, example)?;
 
         Ok(run.unit_trace)
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AcceptLanguage {
+    /// Languages available for this source, negotiated against the `Accept-Language` header of the request
+    pub supported: Vec<String>,
+    /// Negotiated languages matching this source, any supported language matches when empty
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub matches: Vec<String>,
+    /// Language used when the request does not accept any of the supported languages
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default: Option<String>,
+}
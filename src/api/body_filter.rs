@@ -7,6 +7,9 @@ pub struct HTMLBodyFilter {
     pub inner_value: Option<String>,
     pub element_tree: Vec<String>,
     pub css_selector: Option<String>,
+    /// Name of the attribute, only used by set_attribute and remove_attribute actions
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub attribute: Option<String>,
     pub id: Option<String>,
     pub target_hash: Option<String>,
 }
@@ -15,6 +18,9 @@ pub struct HTMLBodyFilter {
 pub struct TextBodyFilter {
     pub action: TextAction,
     pub content: String,
+    /// Regex or string to search for, only used by replace_regex and replace_string actions
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pattern: Option<String>,
     pub id: Option<String>,
     pub target_hash: Option<String>,
 }
@@ -27,6 +33,10 @@ pub enum TextAction {
     Prepend,
     #[serde(rename = "replace_text")]
     Replace,
+    #[serde(rename = "replace_regex_text", alias = "replace_regex")]
+    ReplaceRegex,
+    #[serde(rename = "replace_string_text", alias = "replace_string")]
+    ReplaceString,
     #[serde(untagged)]
     Other(serde_json::Value),
 }
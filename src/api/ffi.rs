@@ -6,8 +6,10 @@ use std::{
 use serde_json::to_string as json_encode;
 
 use crate::{
+    Error,
     action::Action,
     api::Log,
+    error::ffi::set_last_error,
     ffi_helpers::{c_char_to_str, string_to_c_char},
     http::{
         Request,
@@ -57,7 +59,11 @@ pub extern "C" fn redirectionio_api_create_log_in_json(
     );
 
     let log_serialized = match json_encode(&log) {
-        Err(_) => return null(),
+        Err(error) => {
+            set_last_error(Error::Encode(error));
+
+            return null();
+        }
         Ok(s) => s,
     };
 
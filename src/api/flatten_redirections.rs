@@ -0,0 +1,251 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use url::{Position, Url};
+
+use super::{Example, Rule};
+use crate::{
+    api::{
+        redirection_chain::RedirectionChain,
+        redirection_loop::{REDIRECTION_CODES, join_url},
+        rules_message::RuleChangeSet,
+    },
+    http::Request,
+    router::Router,
+    router_config::RouterConfig,
+};
+
+// Input
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FlattenRedirectionsInput {
+    pub router_config: RouterConfig,
+    pub rules: Vec<Rule>,
+    pub max_hops: u8,
+    #[serde(default)]
+    pub project_domains: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FlattenRedirectionsProjectInput {
+    pub change_set: RuleChangeSet,
+    pub max_hops: u8,
+    #[serde(default)]
+    pub project_domains: Vec<String>,
+}
+
+// Output
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlattenRedirectionsOutput {
+    /// Rules redirecting to a target which is itself redirected, by rule id
+    pub rules: HashMap<String, FlattenedRedirection>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FlattenedRedirection {
+    pub target: String,
+    pub status_code: u16,
+    /// Final url of the chain, which can be used as the target of the rule, `None` when the chain has a loop or is
+    /// too long to be resolved
+    pub collapsed_target: Option<String>,
+    pub chain: RedirectionChain,
+}
+
+// Implementation
+
+impl FlattenRedirectionsOutput {
+    pub fn from_project(input: FlattenRedirectionsProjectInput, existing_router: Arc<Router<Rule>>) -> FlattenRedirectionsOutput {
+        let router = if input.change_set.is_empty() {
+            existing_router
+        } else {
+            Arc::new(input.change_set.update_existing_router(existing_router))
+        };
+
+        Self::create_result(&router, input.max_hops, &input.project_domains)
+    }
+
+    pub fn create_result_without_project(input: FlattenRedirectionsInput) -> FlattenRedirectionsOutput {
+        let mut router = Router::<Rule>::from_config(input.router_config.clone());
+
+        for rule in input.rules.iter() {
+            router.insert(rule.clone());
+        }
+
+        router.cache(None);
+
+        Self::create_result(&router, input.max_hops, &input.project_domains)
+    }
+
+    fn create_result(router: &Router<Rule>, max_hops: u8, project_domains: &[String]) -> FlattenRedirectionsOutput {
+        let mut rules = HashMap::new();
+
+        for (id, route) in router.routes() {
+            let rule = route.handler();
+
+            let (target, status_code) = match (&rule.target, rule.status_code) {
+                (Some(target), Some(status_code)) if !target.is_empty() && REDIRECTION_CODES.contains(&status_code) => {
+                    (target, status_code)
+                }
+                _ => continue,
+            };
+
+            // Targets using markers or variables depend on the request, they cannot be resolved on their own
+            if !rule.markers.is_empty() || !rule.variables.is_empty() {
+                continue;
+            }
+
+            let base_url = rule
+                .source
+                .host
+                .as_ref()
+                .map(|host| format!("{}://{}/", rule.source.scheme.as_deref().unwrap_or("https"), host));
+
+            let target_url = match &base_url {
+                Some(base_url) => join_url(base_url, target),
+                None => target.clone(),
+            };
+
+            let example = Example {
+                url: target_url,
+                method: None,
+                headers: None,
+                datetime: None,
+                ip_address: None,
+                response_status_code: None,
+                must_match: false,
+                unit_ids_applied: None,
+            };
+
+            let request = match Request::from_example(&router.config, &example) {
+                Ok(request) => request,
+                Err(err) => {
+                    log::warn!("cannot create request from target of rule {id}: {err}");
+
+                    continue;
+                }
+            };
+
+            let chain = router.resolve_chain_in_domains(&request, max_hops, project_domains);
+
+            if chain.redirection_count() == 0 {
+                continue;
+            }
+
+            let collapsed_target = if chain.has_error() {
+                None
+            } else if Url::parse(target).is_err() {
+                // Keep relative targets relative when the chain stays on the same host
+                Some(relative_url(chain.final_url.as_str(), base_url.as_deref()))
+            } else {
+                Some(chain.final_url.clone())
+            };
+
+            rules.insert(
+                id.clone(),
+                FlattenedRedirection {
+                    target: target.clone(),
+                    status_code,
+                    collapsed_target,
+                    chain,
+                },
+            );
+        }
+
+        FlattenRedirectionsOutput { rules }
+    }
+}
+
+fn relative_url(url: &str, base_url: Option<&str>) -> String {
+    let (url, base_url) = match (Url::parse(url), base_url.map(Url::parse)) {
+        (Ok(url), Some(Ok(base_url))) => (url, base_url),
+        _ => return url.to_string(),
+    };
+
+    if url.origin() != base_url.origin() {
+        return url.to_string();
+    }
+
+    url[Position::BeforePath..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RedirectionChainError;
+
+    fn rule(id: &str, path: &str, target: &str) -> Rule {
+        Rule::from_json(
+            format!(r#"{{"id": "{id}", "rank": 0, "source": {{"path": "{path}"}}, "target": "{target}", "status_code": 301}}"#).as_str(),
+        )
+        .unwrap()
+    }
+
+    fn flatten(rules: Vec<Rule>) -> FlattenRedirectionsOutput {
+        flatten_with_max_hops(rules, 5)
+    }
+
+    fn flatten_with_max_hops(rules: Vec<Rule>, max_hops: u8) -> FlattenRedirectionsOutput {
+        FlattenRedirectionsOutput::create_result_without_project(FlattenRedirectionsInput {
+            router_config: RouterConfig::default(),
+            rules,
+            max_hops,
+            project_domains: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_resolve_chain() {
+        let mut router = Router::<Rule>::default();
+        router.insert(rule("a", "/a", "/b"));
+        router.insert(rule("b", "/b", "/c"));
+
+        let chain = router.resolve_chain(&"/a".parse().unwrap(), 5);
+
+        assert_eq!(chain.redirection_count(), 2);
+        assert_eq!(chain.final_url, "/c");
+        assert_eq!(chain.final_status_code, 200);
+        assert_eq!(chain.error, None);
+
+        let chain = router.resolve_chain(&"/a".parse().unwrap(), 1);
+
+        assert_eq!(chain.final_url, "/b");
+        assert_eq!(chain.final_status_code, 301);
+        assert_eq!(chain.error, Some(RedirectionChainError::TooManyHops));
+
+        let chain = router.resolve_chain(&"/b".parse().unwrap(), 1);
+
+        assert_eq!(chain.redirection_count(), 1);
+        assert_eq!(chain.final_url, "/c");
+        assert_eq!(chain.final_status_code, 200);
+        assert_eq!(chain.error, None);
+    }
+
+    #[test]
+    fn test_flatten_chain() {
+        let output = flatten(vec![rule("a", "/a", "/b"), rule("b", "/b", "/c"), rule("c", "/c", "/d")]);
+
+        assert_eq!(output.rules.len(), 2);
+        assert_eq!(output.rules["a"].collapsed_target.as_deref(), Some("/d"));
+        assert_eq!(output.rules["a"].chain.redirection_count(), 2);
+        assert_eq!(output.rules["b"].collapsed_target.as_deref(), Some("/d"));
+    }
+
+    #[test]
+    fn test_flatten_chain_with_max_hops() {
+        let output = flatten_with_max_hops(vec![rule("a", "/a", "/b"), rule("b", "/b", "/c"), rule("c", "/c", "/d")], 1);
+
+        assert_eq!(output.rules["a"].collapsed_target, None);
+        assert_eq!(output.rules["a"].chain.error, Some(RedirectionChainError::TooManyHops));
+        assert_eq!(output.rules["b"].collapsed_target.as_deref(), Some("/d"));
+        assert_eq!(output.rules["b"].chain.error, None);
+    }
+
+    #[test]
+    fn test_flatten_loop() {
+        let output = flatten(vec![rule("a", "/a", "/b"), rule("b", "/b", "/a")]);
+
+        assert_eq!(output.rules["a"].collapsed_target, None);
+        assert_eq!(output.rules["a"].chain.error, Some(RedirectionChainError::Loop));
+    }
+}
@@ -98,7 +98,8 @@ impl ImpactOutput {
 
     pub fn create_result(impact_input: ImpactInput) -> ImpactOutput {
         let mut router = Router::<Rule>::from_config(impact_input.router_config.clone());
-        let mut trace_unique_router = Router::<Rule>::from_config(impact_input.router_config.clone());
+        // Share the config of the first router, so its geoip database is not opened again
+        let mut trace_unique_router = Router::<Rule>::from_arc_config(router.config.clone());
 
         for rule in impact_input.rules.iter() {
             // Even for a "add" action, we remove a potential previous version
@@ -6,6 +6,12 @@ pub enum IpConstraint {
     InRange(String),
     NotInRange(String),
     NotOneOf(Vec<String>),
+    InCountries(Vec<String>),
+    NotInCountries(Vec<String>),
+    InContinents(Vec<String>),
+    NotInContinents(Vec<String>),
+    InAsns(Vec<u32>),
+    NotInAsns(Vec<u32>),
     #[serde(untagged)]
     Other(serde_json::Value),
 }
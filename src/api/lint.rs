@@ -0,0 +1,500 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Example, Rule, Source};
+use crate::{
+    action::Action,
+    api::{redirection_loop::join_url, rules_message::RuleChangeSet},
+    http::Request,
+    marker::StaticOrDynamic,
+    regex_radix_tree::common_prefix_char_size,
+    router::{Route, Router},
+    router_config::RouterConfig,
+};
+
+// Input
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LintInput {
+    pub router_config: RouterConfig,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct LintProjectInput {
+    pub change_set: RuleChangeSet,
+}
+
+// Output
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintOutput {
+    pub rule_count: u32,
+    pub issue_count: u32,
+    /// Rules having at least one issue, by rule id
+    pub rules: HashMap<String, LintedRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LintedRule {
+    pub rule: Rule,
+    pub issues: Vec<LintIssue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum LintIssue {
+    /// Other rules have exactly the same source, only the rank decides which one is applied
+    DuplicateSource { rule_ids: Vec<String> },
+    /// The rule matches but is never applied, a rule with `stop` applied before it or a rule with `reset` applied
+    /// after it discards its action, or a catch-all rule with a higher priority always replaces its redirection
+    Shadowed { by_rule_ids: Vec<String> },
+    /// An example which must match the rule is not matched by its source
+    ExampleNotMatching { example: Example },
+    /// The rule redirects to an url which is matched by the rule itself
+    SelfRedirect { url: String, target: String },
+}
+
+// Implementation
+
+/// Rule without conditions other than its path, which is a prefix followed by a marker matching anything, and which
+/// always changes the status code, e.g. `/@anything` with an `.*` marker
+struct CatchAll {
+    route: Arc<Route<Rule>>,
+    /// Prefix of the path regex, as in the `RegexTreeMap` of the router
+    prefix: String,
+    allow_empty: bool,
+    ignore_case: bool,
+    conditions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LintOutput {
+    pub fn from_project(input: LintProjectInput, existing_router: Arc<Router<Rule>>) -> LintOutput {
+        let router = if input.change_set.is_empty() {
+            existing_router
+        } else {
+            Arc::new(input.change_set.update_existing_router(existing_router))
+        };
+
+        Self::create_result(&router)
+    }
+
+    pub fn create_result_without_project(input: LintInput) -> LintOutput {
+        let mut router = Router::<Rule>::from_config(input.router_config.clone());
+
+        for rule in input.rules.iter() {
+            router.insert(rule.clone());
+        }
+
+        router.cache(None);
+
+        Self::create_result(&router)
+    }
+
+    fn create_result(router: &Router<Rule>) -> LintOutput {
+        let mut results = LintOutput {
+            rule_count: router.len() as u32,
+            ..Default::default()
+        };
+
+        // Sources are compared once serialized, so rules with the same conditions written the same way are grouped
+        let mut sources = BTreeMap::new();
+
+        for (id, route) in router.routes() {
+            match serde_json::to_string(&route.handler().source) {
+                Ok(source) => sources.entry(source).or_insert_with(BTreeSet::new).insert(id.clone()),
+                Err(err) => {
+                    log::warn!("cannot serialize source of rule {id}: {err}");
+
+                    continue;
+                }
+            };
+        }
+
+        for rule_ids in sources.values().filter(|rule_ids| rule_ids.len() > 1) {
+            for id in rule_ids {
+                let Some(route) = router.get_route_by_id(id) else {
+                    continue;
+                };
+                let others = rule_ids.iter().filter(|other| *other != id).cloned().collect();
+
+                results.add_issue(route.handler(), LintIssue::DuplicateSource { rule_ids: others });
+            }
+        }
+
+        let catch_alls = router.routes().filter_map(|(_, route)| CatchAll::new(route)).collect::<Vec<_>>();
+
+        for (_, route) in router.routes() {
+            Self::lint_route(router, route, &catch_alls, &mut results);
+        }
+
+        results
+    }
+
+    fn lint_route(router: &Router<Rule>, route: &Arc<Route<Rule>>, catch_alls: &[CatchAll], results: &mut LintOutput) {
+        let rule = route.handler();
+        let mut samples = Vec::new();
+
+        for example in rule.examples.iter().flatten().filter(|example| example.must_match) {
+            let request = match Request::from_example(&router.config, example) {
+                Ok(request) => request,
+                // Invalid examples are already reported when testing examples
+                Err(_) => continue,
+            };
+
+            if !Self::is_matched(router, route, &request) {
+                results.add_issue(rule, LintIssue::ExampleNotMatching { example: example.clone() });
+
+                continue;
+            }
+
+            samples.push((example.url.clone(), request));
+        }
+
+        if let Some(url) = Self::static_source_url(route)
+            && let Ok(request) = Request::from_example(&router.config, &Self::example(url.clone(), &rule.source))
+            && Self::is_matched(router, route, &request)
+        {
+            samples.push((url, request));
+        }
+
+        let mut discarded_by = BTreeSet::new();
+
+        for (_, request) in &samples {
+            let routes = router.match_request(request);
+            let action = Action::from_routes_rule(routes.clone(), request, None);
+
+            if action.rule_ids.contains(route.id()) {
+                discarded_by.clear();
+
+                break;
+            }
+
+            for other in routes {
+                let other_rule = other.handler();
+
+                if action.rule_ids.contains(other.id()) && (other_rule.stop.unwrap_or(false) || other_rule.reset.unwrap_or(false)) {
+                    discarded_by.insert(other.id().to_string());
+                }
+            }
+        }
+
+        // The rule is still in the action when a catch-all replaces its redirection, so it is found from the prefixes
+        // of the paths instead of the samples, which also covers rules with markers and without examples
+        let mut shadowed_by = catch_alls
+            .iter()
+            .filter(|catch_all| catch_all.overrides(route))
+            .map(|catch_all| catch_all.route.id().to_string())
+            .collect::<BTreeSet<_>>();
+
+        shadowed_by.extend(discarded_by);
+
+        if !shadowed_by.is_empty() {
+            results.add_issue(
+                rule,
+                LintIssue::Shadowed {
+                    by_rule_ids: shadowed_by.into_iter().collect(),
+                },
+            );
+        }
+
+        if rule.status_code.is_none_or(|status_code| !(300..400).contains(&status_code)) {
+            return;
+        }
+
+        for (url, request) in &samples {
+            let target = match Action::get_target(route, request) {
+                Some(target) if !target.is_empty() => target,
+                _ => continue,
+            };
+
+            let target_url = join_url(url.as_str(), target.as_str());
+            let example = Self::example(target_url.clone(), &rule.source);
+
+            if let Ok(target_request) = Request::from_example(&router.config, &example)
+                && Self::is_matched(router, route, &target_request)
+            {
+                results.add_issue(
+                    rule,
+                    LintIssue::SelfRedirect {
+                        url: url.clone(),
+                        target: target_url,
+                    },
+                );
+
+                break;
+            }
+        }
+    }
+
+    fn is_matched(router: &Router<Rule>, route: &Route<Rule>, request: &Request) -> bool {
+        router.match_request(request).iter().any(|matched| matched.id() == route.id())
+    }
+
+    /// Url matched by the source when it has no markers
+    fn static_source_url(route: &Route<Rule>) -> Option<String> {
+        let source = &route.handler().source;
+
+        if !matches!(route.path_and_query(), StaticOrDynamic::Static(_)) {
+            return None;
+        }
+
+        let mut url = match (route.host(), &source.host) {
+            (None, _) => String::new(),
+            (Some(StaticOrDynamic::Static(_)), Some(host)) => format!("{}://{}", source.scheme.as_deref().unwrap_or("https"), host),
+            _ => return None,
+        };
+
+        url.push_str(source.path.as_str());
+
+        if let Some(query) = source.query.as_ref().filter(|query| !query.is_empty()) {
+            url.push('?');
+            url.push_str(query);
+        }
+
+        Some(url)
+    }
+
+    fn example(url: String, source: &Source) -> Example {
+        let method = match source.exclude_methods {
+            Some(true) => None,
+            _ => source.methods.as_ref().and_then(|methods| methods.first().cloned()),
+        };
+
+        Example {
+            url,
+            method,
+            headers: None,
+            datetime: None,
+            ip_address: None,
+            response_status_code: None,
+            must_match: true,
+            unit_ids_applied: None,
+        }
+    }
+
+    fn add_issue(&mut self, rule: &Rule, issue: LintIssue) {
+        self.issue_count += 1;
+
+        self.rules
+            .entry(rule.id.clone())
+            .or_insert_with(|| LintedRule {
+                rule: rule.clone(),
+                issues: Vec::new(),
+            })
+            .issues
+            .push(issue);
+    }
+}
+
+impl CatchAll {
+    fn new(route: &Arc<Route<Rule>>) -> Option<Self> {
+        let rule = route.handler();
+
+        let StaticOrDynamic::Dynamic(marker_string) = route.path_and_query() else {
+            return None;
+        };
+
+        let prefix = literal_prefix(marker_string.regex.as_str());
+        let allow_empty = match &marker_string.regex[prefix.len()..] {
+            "(?:.*)" => true,
+            "(?:.+)" => false,
+            _ => return None,
+        };
+
+        if rule.status_code.is_none() || rule.source.sampling.is_some() || rule.source.response_status_codes.is_some() {
+            return None;
+        }
+
+        Some(CatchAll {
+            route: route.clone(),
+            prefix,
+            allow_empty,
+            ignore_case: marker_string.ignore_case,
+            conditions: conditions(&rule.source)?,
+        })
+    }
+
+    /// Whether the catch-all matches every request matched by the route and replaces its status code and location,
+    /// it is applied after the route as it has a higher priority
+    fn overrides(&self, route: &Route<Rule>) -> bool {
+        let rule = route.handler();
+        let catch_all_rule = self.route.handler();
+
+        if self.route.id() == route.id() || self.route.priority() <= route.priority() {
+            return false;
+        }
+
+        if rule.status_code.is_none() || rule.stop.unwrap_or(false) {
+            return false;
+        }
+
+        let redirects = |rule: &Rule| rule.target.as_ref().is_some_and(|target| !target.is_empty());
+
+        if redirects(rule) && !redirects(catch_all_rule) {
+            return false;
+        }
+
+        let path_regex = match route.path_and_query() {
+            StaticOrDynamic::Static(path) => regex::escape(path),
+            StaticOrDynamic::Dynamic(marker_string) => marker_string.regex.clone(),
+        };
+        let (prefix, path_regex) = if self.ignore_case {
+            (self.prefix.to_lowercase(), path_regex.to_lowercase())
+        } else {
+            (self.prefix.clone(), path_regex)
+        };
+
+        let prefix_size = prefix.chars().count();
+        let common_size = common_prefix_char_size(prefix.as_str(), path_regex.as_str()) as usize;
+
+        if common_size < prefix_size || (!self.allow_empty && literal_prefix(path_regex.as_str()).chars().count() == prefix_size) {
+            return false;
+        }
+
+        let Some(route_conditions) = conditions(&rule.source) else {
+            return false;
+        };
+
+        self.conditions.values().all(|value| value.is_null()) || self.conditions == route_conditions
+    }
+}
+
+/// Conditions of the source, except the path and the query
+fn conditions(source: &Source) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let Ok(serde_json::Value::Object(mut conditions)) = serde_json::to_value(source) else {
+        return None;
+    };
+
+    conditions.remove("path");
+    conditions.remove("query");
+
+    Some(conditions)
+}
+
+/// Part of a path regex before its first marker
+fn literal_prefix(regex: &str) -> String {
+    let mut prefix = String::new();
+    let mut chars = regex.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => break,
+            '\\' => {
+                prefix.push(c);
+
+                if let Some(escaped) = chars.next() {
+                    prefix.push(escaped);
+                }
+            }
+            _ => prefix.push(c),
+        }
+    }
+
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(rules: &[&str]) -> LintOutput {
+        LintOutput::create_result_without_project(LintInput {
+            router_config: RouterConfig::default(),
+            rules: rules.iter().map(|rule| Rule::from_json(rule).unwrap()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_duplicate_source() {
+        let output = lint(&[
+            r#"{"id": "a", "rank": 1, "source": {"path": "/foo"}, "target": "/a", "status_code": 301}"#,
+            r#"{"id": "b", "rank": 2, "source": {"path": "/foo"}, "target": "/b", "status_code": 301}"#,
+        ]);
+
+        assert_eq!(
+            output.rules["a"].issues,
+            vec![LintIssue::DuplicateSource {
+                rule_ids: vec!["b".to_string()]
+            }]
+        );
+        assert_eq!(output.issue_count, 2);
+    }
+
+    #[test]
+    fn test_shadowed_by_catch_all() {
+        let output = lint(&[
+            r#"{"id": "catch-all", "rank": 10, "stop": true, "source": {"path": "/@anything"}, "markers": [{"name": "anything", "regex": ".*"}], "target": "/", "status_code": 301}"#,
+            r#"{"id": "foo", "rank": 1, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+        ]);
+
+        assert_eq!(
+            output.rules["foo"].issues,
+            vec![LintIssue::Shadowed {
+                by_rule_ids: vec!["catch-all".to_string()]
+            }]
+        );
+        assert!(!output.rules.contains_key("catch-all"));
+    }
+
+    #[test]
+    fn test_shadowed_by_catch_all_without_stop() {
+        let output = lint(&[
+            r#"{"id": "catch-all", "rank": 0, "source": {"path": "/@anything"}, "markers": [{"name": "anything", "regex": ".+"}], "target": "/", "status_code": 301}"#,
+            r#"{"id": "foo", "rank": 1, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+            r#"{"id": "blog", "rank": 1, "source": {"path": "/blog/@slug"}, "markers": [{"name": "slug", "regex": "[a-z]+"}], "target": "/news/@slug", "status_code": 302}"#,
+            r#"{"id": "root", "rank": 1, "source": {"path": "/"}, "target": "/home", "status_code": 301}"#,
+            r#"{"id": "host", "rank": 1, "source": {"host": "example.com", "path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+            r#"{"id": "applied-after", "rank": 0, "source": {"path": "/after"}, "target": "/bar", "status_code": 301}"#,
+        ]);
+
+        let shadowed = LintIssue::Shadowed {
+            by_rule_ids: vec!["catch-all".to_string()],
+        };
+
+        assert_eq!(output.rules["foo"].issues, vec![shadowed.clone()]);
+        assert_eq!(output.rules["blog"].issues, vec![shadowed.clone()]);
+        assert_eq!(output.rules["host"].issues, vec![shadowed]);
+        // The catch-all needs at least one character after the prefix, and a rule with a lower rank is applied after it
+        assert!(!output.rules.contains_key("root"));
+        assert!(!output.rules.contains_key("applied-after"));
+        assert!(!output.rules.contains_key("catch-all"));
+
+        let output = lint(&[
+            r#"{"id": "catch-all", "rank": 0, "source": {"host": "example.com", "path": "/blog/@anything"}, "markers": [{"name": "anything", "regex": ".*"}], "target": "/", "status_code": 301}"#,
+            r#"{"id": "same-host", "rank": 1, "source": {"host": "example.com", "path": "/blog/@slug"}, "markers": [{"name": "slug", "regex": "[a-z]+"}], "target": "/news/@slug", "status_code": 302}"#,
+            r#"{"id": "other-host", "rank": 1, "source": {"host": "example.org", "path": "/blog/@slug"}, "markers": [{"name": "slug", "regex": "[a-z]+"}], "target": "/news/@slug", "status_code": 302}"#,
+            r#"{"id": "other-prefix", "rank": 1, "source": {"host": "example.com", "path": "/news/@slug"}, "markers": [{"name": "slug", "regex": "[a-z]+"}], "target": "/", "status_code": 302}"#,
+        ]);
+
+        assert_eq!(
+            output.rules["same-host"].issues,
+            vec![LintIssue::Shadowed {
+                by_rule_ids: vec!["catch-all".to_string()]
+            }]
+        );
+        assert!(!output.rules.contains_key("other-host"));
+        assert!(!output.rules.contains_key("other-prefix"));
+    }
+
+    #[test]
+    fn test_example_not_matching_and_self_redirect() {
+        let output = lint(&[
+            r#"{"id": "a", "rank": 1, "source": {"path": "/a"}, "target": "/a", "status_code": 302, "examples": [{"url": "/b", "must_match": true}]}"#,
+        ]);
+
+        assert_eq!(output.issue_count, 2);
+        assert!(matches!(output.rules["a"].issues[0], LintIssue::ExampleNotMatching { .. }));
+        assert_eq!(
+            output.rules["a"].issues[1],
+            LintIssue::SelfRedirect {
+                url: "/a".to_string(),
+                target: "/a".to_string(),
+            }
+        );
+    }
+}
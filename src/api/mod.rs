@@ -1,18 +1,31 @@
+mod accept_language;
 mod body_filter;
+mod cookie;
 mod date_time;
 mod examples;
 #[cfg(feature = "router")]
 mod explain_request;
 #[cfg(not(target_arch = "wasm32"))]
 mod ffi;
+#[cfg(feature = "router")]
+mod flatten_redirections;
 mod header;
 mod header_filter;
 #[cfg(feature = "router")]
 mod impact;
 mod ip;
+#[cfg(feature = "router")]
+mod lint;
 mod log;
 mod marker;
+#[cfg(feature = "router")]
+mod next_activation;
 mod peer;
+mod peer_pool;
+mod query_param;
+mod rate_limit;
+#[cfg(feature = "router")]
+mod redirection_chain;
 #[cfg(feature = "router")]
 mod redirection_loop;
 #[cfg(feature = "router")]
@@ -20,37 +33,58 @@ mod rule;
 #[cfg(feature = "router")]
 mod rules_message;
 mod source;
+mod static_response;
 #[cfg(feature = "router")]
 mod test_examples;
 mod transformer;
 #[cfg(feature = "router")]
 mod unit_ids;
+#[cfg(feature = "router")]
+mod validation;
 mod variable;
 
+pub use accept_language::AcceptLanguage;
 pub use body_filter::{BodyFilter, HTMLBodyFilter, TextAction, TextBodyFilter};
+pub use cookie::Cookie;
 pub use date_time::DateTimeConstraint;
 pub use examples::Example;
 #[cfg(feature = "router")]
 pub use explain_request::{ExplainRequestInput, ExplainRequestOutput, ExplainRequestOutputError, ExplainRequestProjectInput};
+#[cfg(feature = "router")]
+pub use flatten_redirections::{
+    FlattenRedirectionsInput, FlattenRedirectionsOutput, FlattenRedirectionsProjectInput, FlattenedRedirection,
+};
 pub use header::Header;
 pub use header_filter::HeaderFilter;
 #[cfg(feature = "router")]
 pub use impact::{ImpactInput, ImpactOutput, ImpactProjectInput};
 pub use ip::IpConstraint;
+#[cfg(feature = "router")]
+pub use lint::{LintInput, LintIssue, LintOutput, LintProjectInput, LintedRule};
 pub use marker::Marker;
+#[cfg(feature = "router")]
+pub use next_activation::{NextActivationInput, NextActivationOutput, NextActivationProjectInput};
 pub use peer::Peer;
+pub use peer_pool::{PeerPool, PeerSelection, WeightedPeer};
+pub use query_param::QueryParam;
+pub use rate_limit::RateLimit;
 #[cfg(feature = "router")]
-pub use redirection_loop::RedirectionLoop;
+pub use redirection_chain::{RedirectionChain, RedirectionChainError};
+#[cfg(feature = "router")]
+pub use redirection_loop::{RedirectionHop, RedirectionLoop};
 #[cfg(feature = "router")]
 pub use rule::Rule;
 #[cfg(feature = "router")]
 pub use rules_message::{RuleChangeSet, RulesMessage};
 pub use source::Source;
+pub use static_response::StaticResponse;
 #[cfg(feature = "router")]
 pub use test_examples::{TestExamplesInput, TestExamplesOutput, TestExamplesProjectInput};
 pub use transformer::Transformer;
 #[cfg(feature = "router")]
 pub use unit_ids::{UnitIdsInput, UnitIdsOutput, UnitIdsProjectInput};
+#[cfg(feature = "router")]
+pub use validation::{DiagnosticSeverity, RuleDiagnostic};
 pub use variable::{Variable, VariableKind, VariableValue};
 
 pub use self::log::{LegacyLog, Log};
@@ -0,0 +1,68 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::Rule,
+    router::{IntoRoute, Route, Router},
+    router_config::RouterConfig,
+};
+
+// Input
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NextActivationInput {
+    pub router_config: RouterConfig,
+    pub rules: Vec<Rule>,
+    /// Activations are searched after this datetime, or after now when not set
+    pub after: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NextActivationProjectInput {
+    pub after: Option<DateTime<Utc>>,
+}
+
+// Output
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct NextActivationOutput {
+    /// Next activation of each rule having schedules, `None` when it never becomes active again
+    pub rules: HashMap<String, Option<DateTime<Utc>>>,
+}
+
+// Implementation
+
+impl NextActivationOutput {
+    pub fn create_result_from_project(input: NextActivationProjectInput, existing_router: Arc<Router<Rule>>) -> NextActivationOutput {
+        let after = input.after.unwrap_or_else(Utc::now);
+
+        Self::create_result(existing_router.routes().map(|(_, route)| route.as_ref()), &after)
+    }
+
+    pub fn create_result_without_project(input: NextActivationInput) -> NextActivationOutput {
+        let after = input.after.unwrap_or_else(Utc::now);
+        let routes = input
+            .rules
+            .into_iter()
+            .map(|rule| rule.into_route(&input.router_config))
+            .collect::<Vec<_>>();
+
+        Self::create_result(routes.iter(), &after)
+    }
+
+    fn create_result<'a>(routes: impl Iterator<Item = &'a Route<Rule>>, after: &DateTime<Utc>) -> NextActivationOutput {
+        let mut rules = HashMap::new();
+
+        for route in routes {
+            if route.schedules().is_none() {
+                continue;
+            }
+
+            rules.insert(route.id().to_string(), route.next_activation(after));
+        }
+
+        NextActivationOutput { rules }
+    }
+}
@@ -7,4 +7,8 @@ pub struct Peer {
     pub request_host: Option<String>,
     pub allow_invalid_certificates: bool,
     pub tls: bool,
+    /// Path and query forwarded to the peer instead of the request ones, markers and variables of the rule can be
+    /// used, e.g. `/wp/index.php?p=@slug`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub upstream_path: Option<String>,
 }
@@ -0,0 +1,146 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{api::Peer, stable_hash::StableHasher};
+
+/// Several peers sharing the traffic of a rule, e.g. to do a canary release between an old and a new backend
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerPool {
+    pub peers: Vec<WeightedPeer>,
+    #[serde(default)]
+    pub selection: PeerSelection,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WeightedPeer {
+    pub peer: Peer,
+    /// Share of the traffic sent to the peer relative to the other ones, a peer with a weight of 0 is never used
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PeerSelection {
+    #[default]
+    WeightedRandom,
+    /// Requests with the same key always use the same peer as long as the pool does not change, the key can use the
+    /// markers and variables of the rule, e.g. `@session` with a `request_cookie` variable
+    ConsistentHash { key: String },
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl PeerPool {
+    /// Order the peers in which they should be tried, the first one is the selected peer and the next ones are used
+    /// for failover
+    ///
+    /// The key is only used by the consistent hash selection, it must already be resolved with the rule variables.
+    /// Peers are ranked with a weighted rendezvous hashing, so changing the pool only moves the keys of the updated
+    /// peers.
+    pub fn ordered_peers(&self, key: Option<&str>) -> Vec<Peer> {
+        let mut scored = self
+            .peers
+            .iter()
+            .filter(|weighted| weighted.weight > 0)
+            .map(|weighted| {
+                let draw = match (&self.selection, key) {
+                    (PeerSelection::ConsistentHash { .. }, Some(key)) => unit_interval(stable_hash(key, weighted.peer.address.as_str())),
+                    _ => rand::random::<f64>(),
+                };
+
+                (weighted.weight as f64 / -draw.max(f64::MIN_POSITIVE).ln(), &weighted.peer)
+            })
+            .collect::<Vec<_>>();
+
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        scored.into_iter().map(|(_, peer)| peer.clone()).collect()
+    }
+}
+
+// FNV-1a hash finalized with the splitmix64 mixer, so close keys still give uniformly distributed values, it does not
+// depend on the platform so all proxies select the same peer
+fn stable_hash(key: &str, address: &str) -> u64 {
+    let mut hasher = StableHasher::default();
+    hasher.write(key.as_bytes());
+    hasher.write_u8(0);
+    hasher.write(address.as_bytes());
+
+    let mut hash = hasher.finish();
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    hash ^ (hash >> 31)
+}
+
+fn unit_interval(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(selection: PeerSelection, weights: &[(&str, u32)]) -> PeerPool {
+        PeerPool {
+            peers: weights
+                .iter()
+                .map(|(address, weight)| WeightedPeer {
+                    peer: Peer {
+                        address: address.to_string(),
+                        sni_host: None,
+                        request_host: None,
+                        allow_invalid_certificates: false,
+                        tls: false,
+                        upstream_path: None,
+                    },
+                    weight: *weight,
+                })
+                .collect(),
+            selection,
+        }
+    }
+
+    fn addresses(peers: Vec<Peer>) -> Vec<String> {
+        peers.into_iter().map(|peer| peer.address).collect()
+    }
+
+    #[test]
+    fn test_consistent_hash_is_deterministic() {
+        let pool = pool(
+            PeerSelection::ConsistentHash {
+                key: "@session".to_string(),
+            },
+            &[("old:80", 90), ("new:80", 10), ("drained:80", 0)],
+        );
+
+        let first = addresses(pool.ordered_peers(Some("session-a")));
+
+        assert_eq!(first.len(), 2);
+        assert!(!first.contains(&"drained:80".to_string()));
+
+        for _ in 0..10 {
+            assert_eq!(addresses(pool.ordered_peers(Some("session-a"))), first);
+        }
+    }
+
+    #[test]
+    fn test_selection_follows_weights() {
+        let pool = pool(
+            PeerSelection::ConsistentHash {
+                key: "@session".to_string(),
+            },
+            &[("old:80", 90), ("new:80", 10)],
+        );
+
+        let new_count = (0..1000)
+            .filter(|index| pool.ordered_peers(Some(format!("session-{index}").as_str()))[0].address == "new:80")
+            .count();
+
+        assert!((50..150).contains(&new_count), "new peer selected {new_count} times");
+    }
+}
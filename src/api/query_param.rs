@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryParam {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub value: Option<String>,
+}
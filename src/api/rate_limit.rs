@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Limit the number of requests matching the rule, requests over the limit are answered with `status_code` without
+/// calling the backend
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimit {
+    /// Requests with the same key share the same counter, markers and variables of the rule can be used, e.g. `@ip`
+    /// with a `request_remote_address` variable, all requests share the same counter when it is empty
+    #[serde(default)]
+    pub key: String,
+    /// Duration of the window in seconds
+    pub window: u64,
+    /// Number of requests allowed in each window
+    pub limit: u64,
+    #[serde(default = "default_status_code")]
+    pub status_code: u16,
+}
+
+fn default_status_code() -> u16 {
+    429
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{
+    Rule,
+    redirection_loop::{RedirectionHop, follow_redirection, join_url},
+};
+use crate::{
+    http::{Request, sanitize_url},
+    router::Router,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RedirectionChain {
+    /// Every url requested, starting with the original request which has a `0` status code
+    pub hops: Vec<RedirectionHop>,
+    pub final_url: String,
+    /// Status code of the final url, `200` when no rule changes it
+    pub final_status_code: u16,
+    pub error: Option<RedirectionChainError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectionChainError {
+    TooManyHops,
+    Loop,
+}
+
+impl RedirectionChain {
+    /// Number of redirections followed before reaching the final url
+    pub fn redirection_count(&self) -> usize {
+        self.hops.len() - 1
+    }
+
+    pub fn has_error(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+impl Router<Rule> {
+    /// Follow the redirections of the router from a request until a url which is not redirected
+    ///
+    /// At most `max_hops` redirections are followed, the chain has a `TooManyHops` error when the last url is
+    /// redirected again.
+    pub fn resolve_chain(&self, request: &Request, max_hops: u8) -> RedirectionChain {
+        self.resolve_chain_in_domains(request, max_hops, &[])
+    }
+
+    /// Same as `resolve_chain`, but stop following redirections when leaving the project domains, if any
+    pub fn resolve_chain_in_domains(&self, request: &Request, max_hops: u8, project_domains: &[String]) -> RedirectionChain {
+        let mut current_request = request.clone();
+        let mut current_url = request_url(request);
+        let mut current_method = request.method().to_string();
+
+        let mut hops = vec![RedirectionHop {
+            url: current_url.clone(),
+            status_code: 0,
+            method: current_method.clone(),
+        }];
+
+        loop {
+            let (status_code, location) = follow_redirection(self, &current_request, 200);
+
+            let location = match location {
+                Some(location) => location,
+                None => {
+                    return RedirectionChain {
+                        hops,
+                        final_url: current_url,
+                        final_status_code: status_code,
+                        error: None,
+                    };
+                }
+            };
+
+            // The last allowed hop redirects again
+            if hops.len() > max_hops as usize {
+                return RedirectionChain {
+                    hops,
+                    final_url: current_url,
+                    final_status_code: status_code,
+                    error: Some(RedirectionChainError::TooManyHops),
+                };
+            }
+
+            current_url = join_url(current_url.as_str(), location.as_str());
+
+            if [301, 302].contains(&status_code) {
+                current_method = String::from("GET");
+            }
+
+            let is_loop = hops.iter().any(|hop| hop.url == current_url && hop.method == current_method);
+
+            hops.push(RedirectionHop {
+                url: current_url.clone(),
+                status_code,
+                method: current_method.clone(),
+            });
+
+            let error = if is_loop { Some(RedirectionChainError::Loop) } else { None };
+
+            let next_request = match next_request(self, &current_request, current_url.as_str(), current_method.as_str()) {
+                Some(next_request) if error.is_none() && !is_outside_domains(current_url.as_str(), project_domains) => next_request,
+                // The final url is not requested, so we keep the status code of the last redirection
+                _ => {
+                    return RedirectionChain {
+                        hops,
+                        final_url: current_url,
+                        final_status_code: status_code,
+                        error,
+                    };
+                }
+            };
+
+            current_request = next_request;
+        }
+    }
+}
+
+fn request_url(request: &Request) -> String {
+    let path_and_query = match &request.path_and_query {
+        Some(path_and_query) => path_and_query.as_str(),
+        None => request.path_and_query_skipped.original.as_str(),
+    };
+
+    match request.host() {
+        Some(host) => format!("{}://{}{}", request.scheme().unwrap_or("http"), host, path_and_query),
+        None => path_and_query.to_string(),
+    }
+}
+
+fn is_outside_domains(url: &str, project_domains: &[String]) -> bool {
+    match Url::parse(url) {
+        Ok(url) => !project_domains.is_empty() && !project_domains.iter().any(|domain| Some(domain.as_str()) == url.host_str()),
+        // Relative urls stay on the same domain
+        Err(_) => false,
+    }
+}
+
+fn next_request(router: &Router<Rule>, request: &Request, url: &str, method: &str) -> Option<Request> {
+    let url = sanitize_url(url);
+    let uri = match url.parse::<http::Uri>() {
+        Ok(uri) => uri,
+        Err(err) => {
+            log::warn!("cannot create request from new target: {url} : {err}");
+
+            return None;
+        }
+    };
+
+    let mut next_request = Request::from_config(
+        &router.config,
+        uri.path_and_query()
+            .map(|path_and_query| path_and_query.as_str())
+            .unwrap_or("/")
+            .to_string(),
+        uri.authority()
+            .map(|authority| authority.to_string())
+            .or_else(|| request.host.clone()),
+        uri.scheme_str().map(|scheme| scheme.to_string()).or_else(|| request.scheme.clone()),
+        Some(method.to_string()),
+        request.remote_addr,
+        request.sampling_override,
+    );
+
+    next_request.headers = request.headers.clone();
+    next_request.created_at = request.created_at;
+    next_request.geo_location = next_request.geo_location.or_else(|| request.geo_location.clone());
+
+    Some(next_request)
+}
@@ -4,7 +4,7 @@ use url::Url;
 use super::{Example, Rule};
 use crate::{action::Action, http::Request, router::Router};
 
-const REDIRECTION_CODES: [u16; 4] = [301, 302, 307, 308];
+pub(super) const REDIRECTION_CODES: [u16; 4] = [301, 302, 307, 308];
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct RedirectionLoop {
@@ -65,37 +65,14 @@ impl RedirectionLoop {
                 }
             };
 
-            let routes = router.match_request(&request);
-            let mut action = Action::from_routes_rule(routes, &request, None);
-
-            let action_status_code = action.get_status_code(0, None);
-            let (final_status_code, backend_status_code) = if action_status_code != 0 {
-                (action_status_code, action_status_code)
-            } else {
-                // We call the backend and get a response code
-                let backend_status_code = new_example.response_status_code.unwrap_or(200);
-                let final_status_code = action.get_status_code(backend_status_code, None);
-                (final_status_code, backend_status_code)
-            };
-
-            if !REDIRECTION_CODES.contains(&final_status_code) {
-                break;
-            }
-
-            let headers = action.filter_headers(Vec::new(), backend_status_code, false, None);
+            let backend_status_code = new_example.response_status_code.unwrap_or(200);
 
-            let mut found = false;
-            for header in headers.iter() {
-                if header.name.to_lowercase() == "location" {
-                    current_url = join_url(current_url.as_str(), header.value.as_str());
-                    found = true;
-                    break;
-                }
-            }
+            let (final_status_code, location) = follow_redirection(router, &request, backend_status_code);
 
-            if !found {
-                break;
-            }
+            current_url = match location {
+                Some(location) => join_url(current_url.as_str(), location.as_str()),
+                None => break,
+            };
 
             if i > 1 {
                 error = Some(RedirectionError::AtLeastOneHop);
@@ -144,7 +121,36 @@ impl RedirectionLoop {
     }
 }
 
-fn join_url(base: &str, path: &str) -> String {
+/// Final status code of a request, and the location it is redirected to when it is a redirection
+pub(super) fn follow_redirection(router: &Router<Rule>, request: &Request, backend_status_code: u16) -> (u16, Option<String>) {
+    let routes = router.match_request(request);
+    let mut action = Action::from_routes_rule(routes, request, None);
+
+    let action_status_code = action.get_status_code(0, None);
+    let (final_status_code, backend_status_code) = if action_status_code != 0 {
+        (action_status_code, action_status_code)
+    } else {
+        // We call the backend and get a response code, which is kept when no rule changes it
+        match action.get_status_code(backend_status_code, None) {
+            0 => (backend_status_code, backend_status_code),
+            final_status_code => (final_status_code, backend_status_code),
+        }
+    };
+
+    if !REDIRECTION_CODES.contains(&final_status_code) {
+        return (final_status_code, None);
+    }
+
+    let location = action
+        .filter_headers(Vec::new(), backend_status_code, false, None)
+        .into_iter()
+        .find(|header| header.name.to_lowercase() == "location")
+        .map(|header| header.value);
+
+    (final_status_code, location)
+}
+
+pub(super) fn join_url(base: &str, path: &str) -> String {
     let base = match Url::parse(base) {
         Ok(url) => url,
         Err(_) => return path.to_string(),
@@ -1,4 +1,8 @@
-use std::{cmp::Ordering, collections::HashMap, net::IpAddr};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+    net::IpAddr,
+};
 
 use cidr::AnyIpCidr;
 use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
@@ -6,16 +10,37 @@ use serde::{Deserialize, Serialize};
 use serde_json::from_str as json_decode;
 
 use crate::{
-    api::{BodyFilter, DateTimeConstraint, Example, HeaderFilter, IpConstraint, Marker, Peer, Source, Variable, variable::VariableValue},
+    Error,
+    action::{ResponseHeaderCondition, ResponseHeaderConditionKind},
+    api::{
+        BodyFilter, DateTimeConstraint, Example, HeaderFilter, IpConstraint, Marker, Peer, PeerPool, PeerSelection, RateLimit,
+        RuleDiagnostic, Source, StaticResponse, Variable, validation::validate_rule, variable::VariableValue,
+    },
     http::Request,
     marker::{Marker as RouteMarker, MarkerString, StaticOrDynamic, Transform},
-    router::{IntoRoute, Route, RouteDateTime, RouteHeader, RouteHeaderKind, RouteIp, RouteTime, RouteWeekday},
+    router::{
+        IntoRoute, Route, RouteCookie, RouteDateTime, RouteHeader, RouteHeaderKind, RouteIp, RouteLanguage, RouteQueryParam, RouteSchedule,
+        RouteTime, RouteTimezone, RouteWeekday,
+    },
     router_config::RouterConfig,
 };
 
 const SIMPLE_ENCODE_SET: &AsciiSet = CONTROLS;
 const URL_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
 const QUERY_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>').add(b'+');
+// Variables are encoded as a whole component of the upstream path, so they cannot add a query or query params to it
+const UPSTREAM_PATH_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>').add(b'?');
+const UPSTREAM_QUERY_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'+')
+    .add(b'&')
+    .add(b'=');
+// Marker appended to the path of rules using query params conditions without a query, so they match whatever the query string is
+pub(crate) const ANY_QUERY_MARKER: &str = "rio_any_query_string";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Rule {
@@ -31,8 +56,19 @@ pub struct Rule {
     pub variables: Vec<Variable>,
     pub body_filters: Option<Vec<BodyFilter>>,
     pub header_filters: Option<Vec<HeaderFilter>>,
+    /// Filters applied on the headers of the request forwarded to the backend or the peer
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request_header_filters: Option<Vec<HeaderFilter>>,
     pub log_override: Option<bool>,
     pub peer_override: Option<Peer>,
+    /// Several peers to select from, it replaces `peer_override` when set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub peer_pool: Option<PeerPool>,
+    /// Response served without calling the backend, its status code replaces the one of the rule
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub respond_with: Option<StaticResponse>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub rate_limit: Option<RateLimit>,
     pub reset: Option<bool>,
     pub stop: Option<bool>,
     pub examples: Option<Vec<Example>>,
@@ -70,16 +106,13 @@ impl PartialEq for Rule {
 impl Eq for Rule {}
 
 impl Rule {
-    pub fn from_json(rule_str: &str) -> Option<Rule> {
-        let rule_result = json_decode(rule_str);
-
-        if rule_result.is_err() {
-            log::error!("Unable to create rule from string {}: {}", rule_str, rule_result.err().unwrap());
-
-            return None;
-        }
+    pub fn from_json(rule_str: &str) -> Result<Rule, Error> {
+        json_decode(rule_str).map_err(Error::RuleParse)
+    }
 
-        Some(rule_result.unwrap())
+    /// Check the rule for values that would be ignored or would fail when matching or applying it
+    pub fn validate(&self) -> Vec<RuleDiagnostic> {
+        validate_rule(self)
     }
 
     pub fn variables(&self, markers_captured: &HashMap<String, String>, request: &Request) -> Vec<(String, VariableValue)> {
@@ -104,15 +137,75 @@ impl Rule {
             }
         } else {
             for variable in &self.variables {
-                variables.push((variable.name.clone(), variable.get_value(&input, request)));
+                variables.push((
+                    variable.name.clone(),
+                    variable.get_value(&input, request, self.source.accept_language.as_ref()),
+                ));
             }
         }
 
-        variables.sort_by(|(key_a, _), (key_b, _)| key_b.len().cmp(&key_a.len()));
+        variables.sort_by_key(|(key, _)| Reverse(key.len()));
 
         variables
     }
 
+    /// Conditions on the backend response headers, unknown or incomplete conditions are ignored
+    pub fn response_header_conditions(&self) -> Vec<ResponseHeaderCondition> {
+        let mut conditions = Vec::new();
+
+        for header in self.source.response_headers.iter().flatten() {
+            let value = header.value.clone();
+            let kind = match (header.kind.as_str(), value) {
+                ("is_defined", _) => ResponseHeaderConditionKind::IsDefined,
+                ("is_not_defined", _) => ResponseHeaderConditionKind::IsNotDefined,
+                ("is_equals", Some(value)) => ResponseHeaderConditionKind::IsEquals(value),
+                ("is_not_equal_to", Some(value)) => ResponseHeaderConditionKind::IsNotEqualTo(value),
+                ("contains", Some(value)) => ResponseHeaderConditionKind::Contains(value),
+                ("does_not_contain", Some(value)) => ResponseHeaderConditionKind::DoesNotContain(value),
+                ("ends_with", Some(value)) => ResponseHeaderConditionKind::EndsWith(value),
+                ("starts_with", Some(value)) => ResponseHeaderConditionKind::StartsWith(value),
+                ("match_regex", Some(value)) => ResponseHeaderConditionKind::match_regex(value),
+                (kind, _) => {
+                    log::error!("unsupported response header constraint type {kind} on {}", header.name);
+
+                    continue;
+                }
+            };
+
+            conditions.push(ResponseHeaderCondition {
+                name: header.name.clone(),
+                kind,
+            });
+        }
+
+        conditions
+    }
+
+    /// Peers to forward the request to, in the order they should be tried, the first one is the selected peer
+    pub fn peers(&self, variables: &[(String, VariableValue)]) -> Vec<Peer> {
+        let mut peers = match (self.peer_pool.as_ref(), self.peer_override.as_ref()) {
+            (Some(peer_pool), _) => {
+                let key = match &peer_pool.selection {
+                    PeerSelection::ConsistentHash { key } => Some(StaticOrDynamic::replace(key.clone(), variables, true)),
+                    PeerSelection::WeightedRandom => None,
+                };
+
+                peer_pool.ordered_peers(key.as_deref())
+            }
+            (None, Some(peer)) => vec![peer.clone()],
+            (None, None) => Vec::new(),
+        };
+
+        for peer in &mut peers {
+            peer.upstream_path = peer
+                .upstream_path
+                .take()
+                .map(|upstream_path| replace_upstream_path(upstream_path, variables));
+        }
+
+        peers
+    }
+
     fn get_marker(&self, name: &str) -> Option<&Marker> {
         self.markers.iter().find(|m| m.name.as_str() == name)
     }
@@ -129,6 +222,20 @@ impl Rule {
         markers
     }
 
+    fn route_language(&self) -> Option<RouteLanguage> {
+        let accept_language = self.source.accept_language.as_ref()?;
+
+        if accept_language.supported.is_empty() {
+            return None;
+        }
+
+        Some(RouteLanguage {
+            supported: accept_language.supported.clone(),
+            matches: accept_language.matches.clone(),
+            default: accept_language.default.clone(),
+        })
+    }
+
     fn route_ips(&self) -> Option<Vec<RouteIp>> {
         match &self.source.ips {
             None => None,
@@ -162,6 +269,24 @@ impl Rule {
                                 route_ips.push(RouteIp::NotOneOf(ips));
                             }
                         }
+                        IpConstraint::InCountries(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::InCountries(list.iter().map(|c| c.to_uppercase()).collect()));
+                        }
+                        IpConstraint::NotInCountries(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::NotInCountries(list.iter().map(|c| c.to_uppercase()).collect()));
+                        }
+                        IpConstraint::InContinents(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::InContinents(list.iter().map(|c| c.to_uppercase()).collect()));
+                        }
+                        IpConstraint::NotInContinents(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::NotInContinents(list.iter().map(|c| c.to_uppercase()).collect()));
+                        }
+                        IpConstraint::InAsns(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::InAsns(list.clone()));
+                        }
+                        IpConstraint::NotInAsns(list) if !list.is_empty() => {
+                            route_ips.push(RouteIp::NotInAsns(list.clone()));
+                        }
                         _ => {
                             continue;
                         }
@@ -173,42 +298,67 @@ impl Rule {
         }
     }
 
-    fn route_datetimes(&self) -> Option<Vec<RouteDateTime>> {
+    /// Timezone of the rule, or the default one of the router when the rule has none or an invalid one
+    fn route_timezone(&self, default_timezone: Option<&String>) -> Option<RouteTimezone> {
+        if let Some(timezone) = self.source.timezone.as_ref()
+            && let Some(route_timezone) = RouteTimezone::parse(timezone.as_str())
+        {
+            return Some(route_timezone);
+        }
+
+        RouteTimezone::parse(default_timezone?.as_str())
+    }
+
+    fn route_datetimes(&self, timezone: Option<RouteTimezone>) -> Option<Vec<RouteDateTime>> {
         let mut route_datetimes = Vec::new();
 
         if let Some(source_datetimes) = self.source.datetime.as_ref() {
             for range in source_datetimes {
                 let DateTimeConstraint(source_start, source_end) = range;
-                route_datetimes.push(RouteDateTime::from_range(source_start, source_end));
+
+                route_datetimes.push(RouteDateTime::from_range(source_start, source_end, timezone));
             }
         }
 
         if route_datetimes.is_empty() { None } else { Some(route_datetimes) }
     }
 
-    fn route_times(&self) -> Option<Vec<RouteTime>> {
+    fn route_times(&self, timezone: Option<RouteTimezone>) -> Option<Vec<RouteTime>> {
         let mut route_times = Vec::new();
 
         if let Some(source_times) = self.source.time.as_ref() {
             for range in source_times {
                 let DateTimeConstraint(source_start, source_end) = range;
-                route_times.push(RouteTime::from_range(source_start, source_end));
+
+                route_times.push(RouteTime::from_range(source_start, source_end, timezone));
             }
         }
 
         if route_times.is_empty() { None } else { Some(route_times) }
     }
 
-    fn route_weekdays(&self) -> Option<RouteWeekday> {
+    fn route_schedules(&self, timezone: Option<RouteTimezone>) -> Option<Vec<RouteSchedule>> {
+        let route_schedules = self
+            .source
+            .schedules
+            .iter()
+            .flatten()
+            .filter_map(|expression| RouteSchedule::from_expression(expression, timezone))
+            .collect::<Vec<_>>();
+
+        if route_schedules.is_empty() { None } else { Some(route_schedules) }
+    }
+
+    fn route_weekdays(&self, timezone: Option<RouteTimezone>) -> Option<RouteWeekday> {
         if let Some(source_weekdays) = self.source.weekdays.as_ref() {
-            return RouteWeekday::from_weekdays(source_weekdays);
+            return RouteWeekday::from_weekdays(source_weekdays, timezone);
         }
 
         None
     }
 
     fn path_and_query(&self, ignore_case: bool, ignore_query_parameters: bool, ignore_query_param_order: bool) -> StaticOrDynamic {
-        let markers = self.markers();
+        let mut markers = self.markers();
 
         let query = match self.source.query.clone() {
             Some(source_query) if source_query.is_empty() => None,
@@ -228,6 +378,14 @@ impl Rule {
             let query_string_encoded = utf8_percent_encode(query_string.as_str(), QUERY_ENCODE_SET).to_string();
 
             path.push_str(format!("?{query_string_encoded}").as_str());
+        } else if self
+            .source
+            .query_params
+            .as_ref()
+            .is_some_and(|query_params| !query_params.is_empty())
+        {
+            path.push_str(format!("@{ANY_QUERY_MARKER}").as_str());
+            markers.push(RouteMarker::new(ANY_QUERY_MARKER.to_string(), r"(?:\?.*)?".to_string()));
         }
 
         StaticOrDynamic::new_with_markers(path.as_str(), markers, ignore_case)
@@ -246,58 +404,110 @@ impl Rule {
 
         if let Some(source_headers) = self.source.headers.as_ref() {
             for header in source_headers {
+                let Some(kind) = self.value_condition(header.kind.as_str(), header.value.as_ref(), ignore_case, "header") else {
+                    continue;
+                };
+
                 headers.push(RouteHeader {
                     name: header.name.clone(),
-                    kind: match header.kind.as_ref() {
-                        "is_defined" => RouteHeaderKind::IsDefined,
-                        "is_not_defined" => RouteHeaderKind::IsNotDefined,
-                        "is_equals" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::IsEquals(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "is_not_equal_to" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::IsNotEqualTo(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "contains" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::Contains(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "does_not_contain" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::DoesNotContain(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "ends_with" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::EndsWith(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "starts_with" => match &header.value {
-                            None => continue,
-                            Some(str) => RouteHeaderKind::StartsWith(if ignore_case { str.to_lowercase() } else { str.clone() }),
-                        },
-                        "match_regex" => match &header.value {
-                            None => continue,
-                            Some(str) => match MarkerString::new(str, self.markers(), ignore_case) {
-                                None => continue,
-                                Some(marker) => RouteHeaderKind::MatchRegex(marker),
-                            },
-                        },
-                        unknown => {
-                            log::error!("unsupported header constraint type {unknown}");
-
-                            continue;
-                        }
-                    },
+                    kind,
                 })
             }
         }
 
         headers
     }
+
+    fn query_params(&self, ignore_case: bool) -> Vec<RouteQueryParam> {
+        let mut query_params = Vec::new();
+
+        if let Some(source_query_params) = self.source.query_params.as_ref() {
+            for query_param in source_query_params {
+                let Some(kind) = self.value_condition(query_param.kind.as_str(), query_param.value.as_ref(), ignore_case, "query param")
+                else {
+                    continue;
+                };
+
+                query_params.push(RouteQueryParam {
+                    name: query_param.name.clone(),
+                    kind,
+                })
+            }
+        }
+
+        query_params
+    }
+
+    fn cookies(&self, ignore_case: bool) -> Vec<RouteCookie> {
+        let mut cookies = Vec::new();
+
+        if let Some(source_cookies) = self.source.cookies.as_ref() {
+            for cookie in source_cookies {
+                let Some(kind) = self.value_condition(cookie.kind.as_str(), cookie.value.as_ref(), ignore_case, "cookie") else {
+                    continue;
+                };
+
+                cookies.push(RouteCookie {
+                    name: cookie.name.clone(),
+                    kind,
+                })
+            }
+        }
+
+        cookies
+    }
+
+    fn value_condition(&self, kind: &str, value: Option<&String>, ignore_case: bool, constraint: &str) -> Option<RouteHeaderKind> {
+        let value_case = |str: &String| if ignore_case { str.to_lowercase() } else { str.clone() };
+
+        Some(match kind {
+            "is_defined" => RouteHeaderKind::IsDefined,
+            "is_not_defined" => RouteHeaderKind::IsNotDefined,
+            "is_equals" => RouteHeaderKind::IsEquals(value_case(value?)),
+            "is_not_equal_to" => RouteHeaderKind::IsNotEqualTo(value_case(value?)),
+            "contains" => RouteHeaderKind::Contains(value_case(value?)),
+            "does_not_contain" => RouteHeaderKind::DoesNotContain(value_case(value?)),
+            "ends_with" => RouteHeaderKind::EndsWith(value_case(value?)),
+            "starts_with" => RouteHeaderKind::StartsWith(value_case(value?)),
+            "match_regex" => RouteHeaderKind::MatchRegex(MarkerString::new(value?, self.markers(), ignore_case)?),
+            unknown => {
+                log::error!("unsupported {constraint} constraint type {unknown}");
+
+                return None;
+            }
+        })
+    }
+}
+
+fn replace_upstream_path(upstream_path: String, variables: &[(String, VariableValue)]) -> String {
+    let path_variables = encode_variables(variables, UPSTREAM_PATH_ENCODE_SET);
+
+    match upstream_path.split_once('?') {
+        None => StaticOrDynamic::replace(upstream_path, &path_variables, true),
+        Some((path, query)) => format!(
+            "{}?{}",
+            StaticOrDynamic::replace(path.to_string(), &path_variables, true),
+            StaticOrDynamic::replace(query.to_string(), &encode_variables(variables, UPSTREAM_QUERY_ENCODE_SET), true),
+        ),
+    }
+}
+
+fn encode_variables(variables: &[(String, VariableValue)], encode_set: &'static AsciiSet) -> Vec<(String, VariableValue)> {
+    variables
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.clone(),
+                value.map_value(|value| utf8_percent_encode(value, encode_set).to_string()),
+            )
+        })
+        .collect()
 }
 
 impl IntoRoute<Rule> for Rule {
     fn into_route(self, config: &RouterConfig) -> Route<Rule> {
+        let timezone = self.route_timezone(config.default_timezone.as_ref());
+
         Route::new(
             self.source.methods.clone(),
             self.source.exclude_methods,
@@ -309,10 +519,14 @@ impl IntoRoute<Rule> for Rule {
                 config.ignore_query_param_order,
             ),
             self.headers(config.ignore_header_case),
+            self.query_params(config.ignore_path_and_query_case),
+            self.cookies(config.ignore_header_case),
+            self.route_language(),
             self.route_ips(),
-            self.route_datetimes(),
-            self.route_times(),
-            self.route_weekdays(),
+            self.route_datetimes(timezone),
+            self.route_times(timezone),
+            self.route_weekdays(timezone),
+            self.route_schedules(timezone),
             self.id.clone(),
             0 - self.rank as i64,
             self,
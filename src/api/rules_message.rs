@@ -2,7 +2,10 @@ use std::{collections::HashSet, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{api::Rule, router::Router};
+use crate::{
+    api::{Rule, RuleDiagnostic},
+    router::Router,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RulesMessage {
@@ -10,6 +13,12 @@ pub struct RulesMessage {
     pub rules: Vec<Rule>,
 }
 
+impl RulesMessage {
+    pub fn validate(&self) -> Vec<RuleDiagnostic> {
+        self.rules.iter().flat_map(|rule| rule.validate()).collect()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct RuleChangeSet {
     pub added: Vec<Rule>,
@@ -18,8 +27,12 @@ pub struct RuleChangeSet {
 }
 
 impl RuleChangeSet {
+    /// Create a new router from an existing one with this change set applied
+    ///
+    /// The new router shares its matchers with the existing one, which stays untouched and can still be used by
+    /// requests in flight, only the matchers of the changed rules are copied.
     pub fn update_existing_router(self, existing_router: Arc<Router<Rule>>) -> Router<Rule> {
-        let mut new_router = existing_router.as_ref().clone();
+        let mut new_router = Arc::unwrap_or_clone(existing_router);
 
         new_router.apply_change_set(self.added, self.updated, self.deleted);
 
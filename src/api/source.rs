@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::api::{DateTimeConstraint, Header, IpConstraint};
+use crate::api::{AcceptLanguage, Cookie, DateTimeConstraint, Header, IpConstraint, QueryParam};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Source {
@@ -14,11 +14,26 @@ pub struct Source {
     pub path: String,
     pub query: Option<String>,
     pub headers: Option<Vec<Header>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub query_params: Option<Vec<QueryParam>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cookies: Option<Vec<Cookie>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub accept_language: Option<AcceptLanguage>,
     pub methods: Option<Vec<String>>,
     pub exclude_methods: Option<bool>,
     pub response_status_codes: Option<Vec<u16>>,
     pub exclude_response_status_codes: Option<bool>,
+    /// Conditions on the backend response headers, the filters and the status code of the rule only apply when they all match
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub response_headers: Option<Vec<Header>>,
     pub sampling: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub weekdays: Option<Vec<String>>,
+    /// Cron like expressions, the source only matches when one of them is active, see `RouteSchedule`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub schedules: Option<Vec<String>>,
+    /// IANA timezone in which the datetime, time, weekdays and schedules conditions are evaluated, e.g. `Europe/Paris`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<String>,
 }
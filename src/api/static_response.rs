@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Response served directly by the proxy without calling the backend, e.g. a 410 gone page, a `robots.txt` or a
+/// maintenance page
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaticResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    /// Markers and variables of the rule can be used in the body
+    #[serde(default)]
+    pub body: String,
+}
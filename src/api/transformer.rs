@@ -1,8 +1,12 @@
 use std::{collections::HashMap, str::FromStr};
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
-use crate::marker::{Camelize, Dasherize, Lowercase, Replace, Slice, Transform, Underscorize, Uppercase};
+use crate::marker::{
+    Camelize, Dasherize, DefaultValue, Lowercase, RegexReplace, Replace, ShortHash, Slice, Slugify, Transform, Trim, Underscorize,
+    Uppercase, UrlDecode, UrlEncode,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Transformer {
@@ -13,38 +17,142 @@ pub struct Transformer {
 
 impl Transformer {
     pub fn to_transform(&self) -> Option<Box<dyn Transform>> {
-        match self.kind.as_ref() {
-            None => None,
-            Some(kind) => match kind.as_str() {
-                "camelize" => Some(Box::<Camelize>::default()),
-                "dasherize" => Some(Box::<Dasherize>::default()),
-                "lowercase" => Some(Box::<Lowercase>::default()),
-                "replace" => match self.options.as_ref() {
-                    None => None,
-                    Some(options) => {
-                        if !options.contains_key("something") || !options.contains_key("with") {
-                            return None;
-                        }
-
-                        Some(Box::new(Replace::new(
-                            options.get("something").unwrap().clone(),
-                            options.get("with").unwrap().clone(),
-                        )))
-                    }
+        match self.try_to_transform() {
+            Ok(transform) => Some(transform),
+            Err(err) => {
+                log::error!("cannot create transformer, it will be ignored: {err}");
+
+                None
+            }
+        }
+    }
+
+    pub fn try_to_transform(&self) -> Result<Box<dyn Transform>, String> {
+        let kind = match self.kind.as_ref() {
+            None => return Err("transformer type is missing".to_string()),
+            Some(kind) => kind,
+        };
+
+        match kind.as_str() {
+            "camelize" => Ok(Box::<Camelize>::default()),
+            "dasherize" => Ok(Box::<Dasherize>::default()),
+            "default" => match self.option("value") {
+                Some(value) => Ok(Box::new(DefaultValue::new(value.clone()))),
+                None => Err("default transformer requires a \"value\" option".to_string()),
+            },
+            "hash" => match self.option("length") {
+                None => Ok(Box::<ShortHash>::default()),
+                Some(length) => match usize::from_str(length) {
+                    Ok(length) if (1..=ShortHash::MAX_LENGTH).contains(&length) => Ok(Box::new(ShortHash::new(length))),
+                    _ => Err(format!(
+                        "hash transformer \"length\" option must be between 1 and {}",
+                        ShortHash::MAX_LENGTH
+                    )),
                 },
-                "slice" => match self.options.as_ref() {
-                    None => None,
-                    Some(options) => {
-                        let from = options.get("from").map(|f| usize::from_str(f)).and_then(|r| r.ok()).unwrap_or(0);
-                        let to = options.get("to").map(|f| usize::from_str(f)).and_then(|r| r.ok());
-
-                        Some(Box::new(Slice::new(from, to)))
-                    }
+            },
+            "lowercase" => Ok(Box::<Lowercase>::default()),
+            "regex_replace" => match (self.option("pattern"), self.option("with")) {
+                (Some(pattern), Some(with)) => match Regex::new(pattern) {
+                    Ok(regex) => Ok(Box::new(RegexReplace::new(regex, with.clone()))),
+                    Err(err) => Err(format!("regex_replace transformer has an invalid pattern: {err}")),
                 },
-                "underscorize" => Some(Box::<Underscorize>::default()),
-                "uppercase" => Some(Box::<Uppercase>::default()),
-                _ => None,
+                _ => Err("regex_replace transformer requires \"pattern\" and \"with\" options".to_string()),
+            },
+            "replace" => match (self.option("something"), self.option("with")) {
+                (Some(something), Some(with)) => Ok(Box::new(Replace::new(something.clone(), with.clone()))),
+                _ => Err("replace transformer requires \"something\" and \"with\" options".to_string()),
             },
+            "slice" => match self.options.as_ref() {
+                None => Err("slice transformer requires options".to_string()),
+                Some(options) => {
+                    let from = options.get("from").map(|f| usize::from_str(f)).and_then(|r| r.ok()).unwrap_or(0);
+                    let to = options.get("to").map(|f| usize::from_str(f)).and_then(|r| r.ok());
+
+                    Ok(Box::new(Slice::new(from, to)))
+                }
+            },
+            "slugify" => match self.option("separator") {
+                Some(separator) => Ok(Box::new(Slugify::new(separator.clone()))),
+                None => Ok(Box::<Slugify>::default()),
+            },
+            "trim" => Ok(Box::new(Trim::new(self.option("characters").cloned()))),
+            "underscorize" => Ok(Box::<Underscorize>::default()),
+            "uppercase" => Ok(Box::<Uppercase>::default()),
+            "urldecode" => Ok(Box::<UrlDecode>::default()),
+            "urlencode" => Ok(Box::<UrlEncode>::default()),
+            unknown => Err(format!("unknown transformer type \"{unknown}\"")),
         }
     }
+
+    fn option(&self, name: &str) -> Option<&String> {
+        self.options.as_ref()?.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(kind: &str, options: &[(&str, &str)], value: &str) -> Result<String, String> {
+        let transformer = Transformer {
+            kind: Some(kind.to_string()),
+            options: Some(options.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()),
+        };
+
+        transformer
+            .try_to_transform()
+            .map(|transform| transform.transform(value.to_string()))
+    }
+
+    #[test]
+    fn test_url_encode_decode() {
+        assert_eq!(transform("urlencode", &[], "café au lait/1").unwrap(), "caf%C3%A9%20au%20lait%2F1");
+        assert_eq!(transform("urldecode", &[], "caf%C3%A9%20au%20lait%2F1").unwrap(), "café au lait/1");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            transform("slugify", &[], "  Crème Brûlée: 100% Délice! ").unwrap(),
+            "creme-brulee-100-delice"
+        );
+        assert_eq!(transform("slugify", &[("separator", "_")], "Hello World").unwrap(), "hello_world");
+    }
+
+    #[test]
+    fn test_trim_and_default() {
+        assert_eq!(transform("trim", &[], "  foo ").unwrap(), "foo");
+        assert_eq!(transform("trim", &[("characters", "/-")], "/foo-bar/").unwrap(), "foo-bar");
+        assert_eq!(transform("default", &[("value", "home")], "").unwrap(), "home");
+        assert_eq!(transform("default", &[("value", "home")], "about").unwrap(), "about");
+        assert!(transform("default", &[], "").is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(transform("hash", &[], "").unwrap(), "cbf29ce4");
+        assert_eq!(transform("hash", &[("length", "16")], "a").unwrap(), "af63dc4c8601ec8c");
+        assert!(transform("hash", &[("length", "17")], "a").is_err());
+    }
+
+    #[test]
+    fn test_regex_replace() {
+        assert_eq!(
+            transform("regex_replace", &[("pattern", r"(\d+)-(\d+)"), ("with", "$2-$1")], "page 1-2").unwrap(),
+            "page 2-1"
+        );
+        assert!(transform("regex_replace", &[("pattern", "("), ("with", "")], "").is_err());
+    }
+
+    #[test]
+    fn test_slice_chars() {
+        assert_eq!(transform("slice", &[("from", "1"), ("to", "3")], "éàü").unwrap(), "àü");
+        assert_eq!(transform("slice", &[("from", "3"), ("to", "1")], "abcd").unwrap(), "");
+        assert_eq!(transform("slice", &[("from", "10")], "abcd").unwrap(), "");
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        assert_eq!(transform("foo", &[], "").unwrap_err(), "unknown transformer type \"foo\"");
+    }
 }
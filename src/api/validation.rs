@@ -0,0 +1,702 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use cidr::AnyIpCidr;
+use lol_html::Selector;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{BodyFilter, DateTimeConstraint, IpConstraint, Peer, Rule, TextAction, Transformer, VariableKind, rule::ANY_QUERY_MARKER},
+    filter::{HtmlBodyVisitor, create_header_action},
+    marker::{Marker as RouteMarker, MarkerString},
+    router::RouteSchedule,
+};
+
+const CONTINENT_CODES: [&str; 7] = ["AF", "AN", "AS", "EU", "NA", "OC", "SA"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// The rule, or part of it, will not be applied
+    Error,
+    /// The rule will be applied but may not behave as expected
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RuleDiagnostic {
+    pub rule_id: String,
+    /// Path of the faulty value into the rule, e.g. `markers[0].transformers[1]`
+    pub path: String,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+struct RuleValidator<'a> {
+    rule: &'a Rule,
+    diagnostics: Vec<RuleDiagnostic>,
+}
+
+pub(crate) fn validate_rule(rule: &Rule) -> Vec<RuleDiagnostic> {
+    let mut validator = RuleValidator {
+        rule,
+        diagnostics: Vec::new(),
+    };
+
+    validator.validate_markers();
+    validator.validate_variables();
+    validator.validate_headers();
+    validator.validate_query_params();
+    validator.validate_cookies();
+    validator.validate_response_headers();
+    validator.validate_accept_language();
+    validator.validate_ips();
+    validator.validate_datetimes();
+    validator.validate_header_filters();
+    validator.validate_request_header_filters();
+    validator.validate_body_filters();
+    validator.validate_peer_override();
+    validator.validate_respond_with();
+    validator.validate_rate_limit();
+
+    validator.diagnostics
+}
+
+impl RuleValidator<'_> {
+    fn error(&mut self, path: String, message: String) {
+        self.push(path, DiagnosticSeverity::Error, message);
+    }
+
+    fn warning(&mut self, path: String, message: String) {
+        self.push(path, DiagnosticSeverity::Warning, message);
+    }
+
+    fn push(&mut self, path: String, severity: DiagnosticSeverity, message: String) {
+        self.diagnostics.push(RuleDiagnostic {
+            rule_id: self.rule.id.clone(),
+            path,
+            severity,
+            message,
+        });
+    }
+
+    fn validate_markers(&mut self) {
+        for (index, marker) in self.rule.markers.iter().enumerate() {
+            if let Err(err) = Regex::new(format!("(?P<{}>)", marker.name).as_str()) {
+                self.error(
+                    format!("markers[{index}].name"),
+                    format!("invalid marker name \"{}\": {err}", marker.name),
+                );
+            } else if marker.name == ANY_QUERY_MARKER {
+                self.error(
+                    format!("markers[{index}].name"),
+                    format!("marker name \"{}\" is reserved", marker.name),
+                );
+            }
+
+            if let Err(err) = Regex::new(marker.regex.as_str()) {
+                self.error(
+                    format!("markers[{index}].regex"),
+                    format!("invalid regex \"{}\": {err}", marker.regex),
+                );
+            }
+
+            self.validate_transformers(format!("markers[{index}]").as_str(), &marker.transformers);
+        }
+    }
+
+    fn validate_transformers(&mut self, path: &str, transformers: &[Transformer]) {
+        for (index, transformer) in transformers.iter().enumerate() {
+            if let Err(message) = transformer.try_to_transform() {
+                self.error(format!("{path}.transformers[{index}]"), message);
+            }
+        }
+    }
+
+    fn validate_variables(&mut self) {
+        for (index, variable) in self.rule.variables.iter().enumerate() {
+            match &variable.kind {
+                VariableKind::Marker(name) if !self.rule.markers.iter().any(|marker| &marker.name == name) => {
+                    self.warning(
+                        format!("variables[{index}].type"),
+                        format!("marker \"{name}\" is not defined, variable will be empty"),
+                    );
+                }
+                VariableKind::HtmlBody { selector, .. } => {
+                    if let Err(err) = selector.parse::<Selector>() {
+                        self.error(
+                            format!("variables[{index}].type.selector"),
+                            format!("invalid css selector \"{selector}\": {err}"),
+                        );
+                    }
+                }
+                VariableKind::PreferredLanguage { supported, .. } if supported.is_empty() && self.rule.source.accept_language.is_none() => {
+                    self.warning(
+                        format!("variables[{index}].type.supported"),
+                        "no supported languages, variable will only use its default".to_string(),
+                    );
+                }
+                VariableKind::Other(value) => {
+                    self.error(format!("variables[{index}].type"), format!("unknown variable type {value}"));
+                }
+                _ => (),
+            }
+
+            self.validate_transformers(format!("variables[{index}]").as_str(), &variable.transformers);
+        }
+    }
+
+    fn validate_headers(&mut self) {
+        for (index, header) in self.rule.source.headers.iter().flatten().enumerate() {
+            self.validate_value_condition(
+                format!("source.headers[{index}]").as_str(),
+                header.kind.as_str(),
+                header.value.as_ref(),
+                "header",
+            );
+        }
+    }
+
+    fn validate_query_params(&mut self) {
+        for (index, query_param) in self.rule.source.query_params.iter().flatten().enumerate() {
+            self.validate_value_condition(
+                format!("source.query_params[{index}]").as_str(),
+                query_param.kind.as_str(),
+                query_param.value.as_ref(),
+                "query param",
+            );
+        }
+    }
+
+    fn validate_accept_language(&mut self) {
+        let Some(accept_language) = self.rule.source.accept_language.as_ref() else {
+            return;
+        };
+
+        if accept_language.supported.is_empty() {
+            self.error(
+                "source.accept_language.supported".to_string(),
+                "supported languages cannot be empty, condition will be ignored".to_string(),
+            );
+
+            return;
+        }
+
+        for (index, language) in accept_language.matches.iter().enumerate() {
+            let is_supported = accept_language.supported.iter().any(|s| s.eq_ignore_ascii_case(language))
+                || accept_language.default.as_ref().is_some_and(|d| d.eq_ignore_ascii_case(language));
+
+            if !is_supported {
+                self.warning(
+                    format!("source.accept_language.matches[{index}]"),
+                    format!("language \"{language}\" is not supported, it will never be negotiated"),
+                );
+            }
+        }
+    }
+
+    fn validate_cookies(&mut self) {
+        for (index, cookie) in self.rule.source.cookies.iter().flatten().enumerate() {
+            self.validate_value_condition(
+                format!("source.cookies[{index}]").as_str(),
+                cookie.kind.as_str(),
+                cookie.value.as_ref(),
+                "cookie",
+            );
+        }
+    }
+
+    fn validate_response_headers(&mut self) {
+        for (index, header) in self.rule.source.response_headers.iter().flatten().enumerate() {
+            let path = format!("source.response_headers[{index}]");
+
+            // Markers cannot be used on the response, so regexes are validated as is
+            match (header.kind.as_str(), header.value.as_ref()) {
+                ("match_regex", Some(value)) => {
+                    if let Err(err) = Regex::new(value) {
+                        self.error(format!("{path}.value"), format!("invalid regex \"{value}\": {err}"));
+                    }
+                }
+                (kind, value) => self.validate_value_condition(path.as_str(), kind, value, "response header"),
+            }
+        }
+    }
+
+    fn validate_value_condition(&mut self, path: &str, kind: &str, value: Option<&String>, constraint: &str) {
+        match (kind, value) {
+            ("is_defined" | "is_not_defined", _) => (),
+            ("is_equals" | "is_not_equal_to" | "contains" | "does_not_contain" | "ends_with" | "starts_with" | "match_regex", None) => {
+                self.error(
+                    format!("{path}.value"),
+                    format!("{constraint} condition \"{kind}\" requires a value"),
+                );
+            }
+            ("match_regex", Some(value)) => {
+                let markers = self
+                    .rule
+                    .markers
+                    .iter()
+                    .map(|marker| RouteMarker::new(marker.name.clone(), marker.regex.clone()))
+                    .collect();
+
+                match MarkerString::new(value, markers, false) {
+                    None => {
+                        self.warning(
+                            format!("{path}.value"),
+                            format!("\"{value}\" does not use any marker, condition is ignored"),
+                        );
+                    }
+                    Some(marker_string) => {
+                        if let Err(err) = Regex::new(marker_string.regex.as_str()) {
+                            self.error(format!("{path}.value"), format!("invalid regex \"{value}\": {err}"));
+                        }
+                    }
+                }
+            }
+            ("is_equals" | "is_not_equal_to" | "contains" | "does_not_contain" | "ends_with" | "starts_with", Some(_)) => (),
+            (unknown, _) => {
+                self.error(format!("{path}.type"), format!("unknown {constraint} condition \"{unknown}\""));
+            }
+        }
+    }
+
+    fn validate_ips(&mut self) {
+        let Some(ips) = self.rule.source.ips.as_ref() else {
+            return;
+        };
+
+        for (index, ip) in ips.iter().enumerate() {
+            let path = format!("source.ips[{index}]");
+
+            match ip {
+                IpConstraint::InRange(range) | IpConstraint::NotInRange(range) => {
+                    if let Err(err) = range.parse::<AnyIpCidr>() {
+                        self.error(path, format!("invalid cidr \"{range}\": {err}"));
+                    }
+                }
+                IpConstraint::NotOneOf(list) => {
+                    for (ip_index, ip_str) in list.iter().enumerate() {
+                        if let Err(err) = ip_str.parse::<IpAddr>() {
+                            self.error(format!("{path}[{ip_index}]"), format!("invalid ip \"{ip_str}\": {err}"));
+                        }
+                    }
+                }
+                IpConstraint::InCountries(list) | IpConstraint::NotInCountries(list) => {
+                    if list.is_empty() {
+                        self.error(path.clone(), "country list cannot be empty".to_string());
+                    }
+
+                    for (country_index, country) in list.iter().enumerate() {
+                        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                            self.error(format!("{path}[{country_index}]"), format!("invalid country code \"{country}\""));
+                        }
+                    }
+                }
+                IpConstraint::InContinents(list) | IpConstraint::NotInContinents(list) => {
+                    if list.is_empty() {
+                        self.error(path.clone(), "continent list cannot be empty".to_string());
+                    }
+
+                    for (continent_index, continent) in list.iter().enumerate() {
+                        if !CONTINENT_CODES.contains(&continent.to_uppercase().as_str()) {
+                            self.error(
+                                format!("{path}[{continent_index}]"),
+                                format!("invalid continent code \"{continent}\""),
+                            );
+                        }
+                    }
+                }
+                IpConstraint::InAsns(list) | IpConstraint::NotInAsns(list) => {
+                    if list.is_empty() {
+                        self.error(path, "asn list cannot be empty".to_string());
+                    }
+                }
+                IpConstraint::Other(value) => {
+                    self.error(path, format!("unknown ip constraint {value}"));
+                }
+            }
+        }
+    }
+
+    fn validate_datetimes(&mut self) {
+        let source = &self.rule.source;
+
+        if let Some(timezone) = source.timezone.as_ref()
+            && let Err(err) = timezone.parse::<Tz>()
+        {
+            self.error(
+                "source.timezone".to_string(),
+                format!("invalid timezone \"{timezone}\", the default timezone is used: {err}"),
+            );
+        }
+
+        for (index, DateTimeConstraint(start, end)) in source.datetime.iter().flatten().enumerate() {
+            for (position, value) in [(0, start), (1, end)] {
+                let Some(datetime) = value else {
+                    continue;
+                };
+
+                if datetime.parse::<DateTime<Utc>>().is_ok() {
+                    continue;
+                }
+
+                match datetime.parse::<NaiveDateTime>() {
+                    Ok(_) if source.timezone.is_none() => self.warning(
+                        format!("source.datetime[{index}][{position}]"),
+                        format!("datetime \"{datetime}\" has no offset, it will be read in the default timezone of the router or as utc"),
+                    ),
+                    Ok(_) => (),
+                    Err(err) => self.error(
+                        format!("source.datetime[{index}][{position}]"),
+                        format!("invalid datetime \"{datetime}\": {err}"),
+                    ),
+                }
+            }
+        }
+
+        for (index, DateTimeConstraint(start, end)) in source.time.iter().flatten().enumerate() {
+            for (position, value) in [(0, start), (1, end)] {
+                if let Some(time) = value
+                    && let Err(err) = time.parse::<NaiveTime>()
+                {
+                    self.error(
+                        format!("source.time[{index}][{position}]"),
+                        format!("invalid time \"{time}\": {err}"),
+                    );
+                }
+            }
+        }
+
+        for (index, expression) in source.schedules.iter().flatten().enumerate() {
+            if let Err(err) = RouteSchedule::parse(expression, None) {
+                self.error(
+                    format!("source.schedules[{index}]"),
+                    format!("invalid schedule \"{expression}\": {err}"),
+                );
+            }
+        }
+
+        for (index, weekday) in source.weekdays.iter().flatten().enumerate() {
+            if let Err(err) = weekday.parse::<Weekday>() {
+                self.error(format!("source.weekdays[{index}]"), format!("invalid weekday \"{weekday}\": {err}"));
+            }
+        }
+    }
+
+    fn validate_header_filters(&mut self) {
+        for (index, filter) in self.rule.header_filters.iter().flatten().enumerate() {
+            if create_header_action(filter).is_none() {
+                self.error(
+                    format!("header_filters[{index}].action"),
+                    format!("unknown header action \"{}\"", filter.action),
+                );
+            }
+        }
+    }
+
+    fn validate_request_header_filters(&mut self) {
+        for (index, filter) in self.rule.request_header_filters.iter().flatten().enumerate() {
+            if create_header_action(filter).is_none() {
+                self.error(
+                    format!("request_header_filters[{index}].action"),
+                    format!("unknown header action \"{}\"", filter.action),
+                );
+            }
+        }
+    }
+
+    fn validate_body_filters(&mut self) {
+        for (index, filter) in self.rule.body_filters.iter().flatten().enumerate() {
+            let path = format!("body_filters[{index}]");
+
+            match filter {
+                BodyFilter::HTML(html_filter) => {
+                    if !HtmlBodyVisitor::is_supported_action(html_filter.action.as_str()) {
+                        self.error(format!("{path}.action"), format!("unknown html action \"{}\"", html_filter.action));
+                    }
+
+                    if HtmlBodyVisitor::is_attribute_action(html_filter.action.as_str()) {
+                        match html_filter.attribute.as_deref() {
+                            None | Some("") => self.error(
+                                format!("{path}.attribute"),
+                                format!("html action \"{}\" requires an attribute", html_filter.action),
+                            ),
+                            Some(attribute) if !HtmlBodyVisitor::is_valid_attribute_name(attribute) => self.error(
+                                format!("{path}.attribute"),
+                                format!("invalid attribute name \"{attribute}\", elements will be left untouched"),
+                            ),
+                            _ => (),
+                        }
+                    }
+
+                    if html_filter.element_tree.is_empty() {
+                        self.error(format!("{path}.element_tree"), "element tree is empty".to_string());
+                    } else {
+                        let element_tree = html_filter.element_tree.join(" > ");
+
+                        if let Err(err) = element_tree.parse::<Selector>() {
+                            self.error(
+                                format!("{path}.element_tree"),
+                                format!("invalid css selector \"{element_tree}\": {err}"),
+                            );
+                        }
+                    }
+
+                    if let Some(css_selector) = html_filter.css_selector.as_ref()
+                        && !css_selector.is_empty()
+                        && let Err(err) = css_selector.parse::<Selector>()
+                    {
+                        self.error(
+                            format!("{path}.css_selector"),
+                            format!("invalid css selector \"{css_selector}\": {err}"),
+                        );
+                    }
+                }
+                BodyFilter::Text(text_filter) => match (&text_filter.action, text_filter.pattern.as_deref()) {
+                    (TextAction::Other(value), _) => {
+                        self.error(format!("{path}.action"), format!("unknown text action {value}"));
+                    }
+                    (TextAction::ReplaceRegex | TextAction::ReplaceString, None | Some("")) => {
+                        self.error(format!("{path}.pattern"), "pattern is required for this text action".to_string());
+                    }
+                    (TextAction::ReplaceRegex, Some(pattern)) => {
+                        if let Err(err) = Regex::new(pattern) {
+                            self.error(format!("{path}.pattern"), format!("invalid regex \"{pattern}\": {err}"));
+                        }
+                    }
+                    _ => (),
+                },
+                BodyFilter::Other(_) => {
+                    self.error(path, "unknown body filter".to_string());
+                }
+            }
+        }
+    }
+
+    fn validate_peer_override(&mut self) {
+        if let Some(peer) = self.rule.peer_override.as_ref() {
+            if self.rule.peer_pool.is_some() {
+                self.warning("peer_override".to_string(), "peer is ignored as a peer pool is set".to_string());
+            }
+
+            self.validate_upstream_path("peer_override.upstream_path".to_string(), peer);
+        }
+
+        let Some(peer_pool) = self.rule.peer_pool.as_ref() else {
+            return;
+        };
+
+        if peer_pool.peers.iter().all(|weighted| weighted.weight == 0) {
+            self.error(
+                "peer_pool.peers".to_string(),
+                "peer pool must contain at least one peer with a positive weight".to_string(),
+            );
+        }
+
+        for (index, weighted) in peer_pool.peers.iter().enumerate() {
+            self.validate_upstream_path(format!("peer_pool.peers[{index}].peer.upstream_path"), &weighted.peer);
+        }
+    }
+
+    fn validate_upstream_path(&mut self, path: String, peer: &Peer) {
+        let Some(upstream_path) = peer.upstream_path.as_ref() else {
+            return;
+        };
+
+        if !upstream_path.starts_with('/') && !upstream_path.starts_with('@') {
+            self.error(path, format!("upstream path \"{upstream_path}\" must start with a /"));
+        }
+    }
+
+    fn validate_respond_with(&mut self) {
+        let Some(respond_with) = self.rule.respond_with.as_ref() else {
+            return;
+        };
+
+        if !(100..=599).contains(&respond_with.status_code) {
+            self.error(
+                "respond_with.status_code".to_string(),
+                format!("invalid status code {}", respond_with.status_code),
+            );
+        }
+
+        if self.rule.status_code.is_some() {
+            self.warning(
+                "status_code".to_string(),
+                "status code is replaced by the one of the static response".to_string(),
+            );
+        }
+
+        if self.rule.source.response_status_codes.is_some() || self.rule.source.response_headers.is_some() {
+            self.error(
+                "respond_with".to_string(),
+                "static response cannot depend on the backend response, it is ignored".to_string(),
+            );
+        }
+    }
+
+    fn validate_rate_limit(&mut self) {
+        let Some(rate_limit) = self.rule.rate_limit.as_ref() else {
+            return;
+        };
+
+        if rate_limit.window == 0 {
+            self.error("rate_limit.window".to_string(), "window must be at least 1 second".to_string());
+        }
+
+        if !(100..=599).contains(&rate_limit.status_code) {
+            self.error(
+                "rate_limit.status_code".to_string(),
+                format!("invalid status code {}", rate_limit.status_code),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics(rule_str: &str) -> Vec<(String, DiagnosticSeverity)> {
+        let rule = Rule::from_json(rule_str).unwrap();
+
+        rule.validate()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.path, diagnostic.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_rule() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/@slug","ips":[{"in_range":"10.0.0.0/8"}],"weekdays":["mon"]},"markers":[{"name":"slug","regex":"[a-z]+","transformers":[{"type":"uppercase","options":null}]}],"header_filters":[{"action":"add","header":"X-Foo","value":"bar","id":null,"target_hash":null}],"body_filters":null,"target":"/@slug","status_code":301}"#,
+        );
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rule() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/@slug","ips":[{"in_range":"10.0.0.0/64"},{"unknown":"foo"}],"datetime":[["yesterday",null]],"schedules":["* * 32 * *"],"timezone":"Europe/Nowhere","headers":[{"type":"match_regex","name":"X-Foo","value":"foo"}],"query_params":[{"type":"is_equals","name":"page","value":null}]},"markers":[{"name":"slug","regex":"[a-z+","transformers":[{"type":"replace","options":{"something":"a"}},{"type":"foo","options":null}]}],"header_filters":[{"action":"append","header":"X-Foo","value":"bar","id":null,"target_hash":null}],"body_filters":[{"action":"insert","element_tree":["html","body"],"css_selector":"div[","value":"","inner_value":null,"id":null,"target_hash":null},{"action":"foo_text","content":"","id":null,"target_hash":null},{"action":"replace_regex_text","pattern":"(a","content":"","id":null,"target_hash":null},{"action":"set_attribute","element_tree":["html","head","link"],"css_selector":null,"value":"nofollow","inner_value":null,"id":null,"target_hash":null}],"target":"/@slug","status_code":301}"#,
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                ("markers[0].regex".to_string(), DiagnosticSeverity::Error),
+                ("markers[0].transformers[0]".to_string(), DiagnosticSeverity::Error),
+                ("markers[0].transformers[1]".to_string(), DiagnosticSeverity::Error),
+                ("source.headers[0].value".to_string(), DiagnosticSeverity::Warning),
+                ("source.query_params[0].value".to_string(), DiagnosticSeverity::Error),
+                ("source.ips[0]".to_string(), DiagnosticSeverity::Error),
+                ("source.ips[1]".to_string(), DiagnosticSeverity::Error),
+                ("source.timezone".to_string(), DiagnosticSeverity::Error),
+                ("source.datetime[0][0]".to_string(), DiagnosticSeverity::Error),
+                ("source.schedules[0]".to_string(), DiagnosticSeverity::Error),
+                ("header_filters[0].action".to_string(), DiagnosticSeverity::Error),
+                ("body_filters[0].action".to_string(), DiagnosticSeverity::Error),
+                ("body_filters[0].css_selector".to_string(), DiagnosticSeverity::Error),
+                ("body_filters[1].action".to_string(), DiagnosticSeverity::Error),
+                ("body_filters[2].pattern".to_string(), DiagnosticSeverity::Error),
+                ("body_filters[3].attribute".to_string(), DiagnosticSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_response_headers() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/foo","response_headers":[{"type":"match_regex","name":"Content-Type","value":"text/(html"},{"type":"is_equals","name":"X-Env","value":null},{"type":"starts_with","name":"Cache-Control","value":"max-age"}]},"header_filters":[{"action":"add","header":"X-Foo","value":"bar","id":null,"target_hash":null}],"status_code":410}"#,
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                ("source.response_headers[0].value".to_string(), DiagnosticSeverity::Error),
+                ("source.response_headers[1].value".to_string(), DiagnosticSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reserved_marker_name() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/@rio_any_query_string","query_params":[{"type":"is_defined","name":"page","value":null}]},"markers":[{"name":"rio_any_query_string","regex":"[a-z]+","transformers":[]}],"status_code":410}"#,
+        );
+
+        assert_eq!(result, vec![("markers[0].name".to_string(), DiagnosticSeverity::Error)]);
+    }
+
+    #[test]
+    fn test_invalid_attribute_name() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/foo"},"body_filters":[{"action":"set_attribute","element_tree":["html","head","link"],"css_selector":null,"attribute":"data id","value":"1","inner_value":null,"id":null,"target_hash":null},{"action":"set_attribute","element_tree":["html","head","link"],"css_selector":null,"attribute":"data-id","value":"1","inner_value":null,"id":null,"target_hash":null}]}"#,
+        );
+
+        assert_eq!(result, vec![("body_filters[0].attribute".to_string(), DiagnosticSeverity::Error)]);
+    }
+
+    #[test]
+    fn test_invalid_request_header_filters() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/foo"},"request_header_filters":[{"action":"remove","header":"Cookie","value":"","id":null,"target_hash":null},{"action":"strip","header":"Cookie","value":"","id":null,"target_hash":null}]}"#,
+        );
+
+        assert_eq!(
+            result,
+            vec![("request_header_filters[1].action".to_string(), DiagnosticSeverity::Error)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_peer_upstream_path() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/blog/@slug"},"markers":[{"name":"slug","regex":"[a-z-]+","transformers":[]}],"peer_override":{"address":"127.0.0.1:8080","sni_host":null,"request_host":null,"allow_invalid_certificates":false,"tls":false,"upstream_path":"wp/index.php?p=@slug"}}"#,
+        );
+
+        assert_eq!(result, vec![("peer_override.upstream_path".to_string(), DiagnosticSeverity::Error)]);
+    }
+
+    #[test]
+    fn test_invalid_peer_pool() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/foo"},"peer_override":{"address":"127.0.0.1:8080","sni_host":null,"request_host":null,"allow_invalid_certificates":false,"tls":false},"peer_pool":{"peers":[{"peer":{"address":"127.0.0.1:8081","sni_host":null,"request_host":null,"allow_invalid_certificates":false,"tls":false},"weight":0},{"peer":{"address":"127.0.0.1:8082","sni_host":null,"request_host":null,"allow_invalid_certificates":false,"tls":false},"weight":0}]}}"#,
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                ("peer_override".to_string(), DiagnosticSeverity::Warning),
+                ("peer_pool.peers".to_string(), DiagnosticSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_respond_with() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/foo","response_status_codes":[404]},"respond_with":{"status_code":1410,"content_type":"text/plain","body":"gone"},"status_code":301}"#,
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                ("respond_with.status_code".to_string(), DiagnosticSeverity::Error),
+                ("status_code".to_string(), DiagnosticSeverity::Warning),
+                ("respond_with".to_string(), DiagnosticSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rate_limit() {
+        let result = diagnostics(
+            r#"{"id":"a","rank":0,"source":{"path":"/api/@slug"},"markers":[{"name":"slug","regex":"[a-z]+","transformers":[]}],"rate_limit":{"key":"@slug","window":0,"limit":10}}"#,
+        );
+
+        assert_eq!(result, vec![("rate_limit.window".to_string(), DiagnosticSeverity::Error)]);
+    }
+}
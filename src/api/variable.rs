@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{api::Transformer, http::Request};
+use crate::{
+    api::{AcceptLanguage, Transformer},
+    http::Request,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -12,12 +15,23 @@ pub enum VariableKind {
         name: String,
         default: Option<String>,
     },
+    RequestCookie {
+        name: String,
+        default: Option<String>,
+    },
     RequestHost,
     RequestMethod,
     RequestPath,
     RequestRemoteAddress,
+    RequestCountry,
     RequestScheme,
     RequestTime,
+    /// Language negotiated from the `Accept-Language` header, the source languages are used when none are set
+    PreferredLanguage {
+        #[serde(default)]
+        supported: Vec<String>,
+        default: Option<String>,
+    },
     HtmlBody {
         selector: String,
         default: Option<String>,
@@ -30,9 +44,9 @@ pub enum VariableKind {
 pub struct Variable {
     pub name: String,
     #[serde(rename = "type")]
-    kind: VariableKind,
+    pub(crate) kind: VariableKind,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    transformers: Vec<Transformer>,
+    pub(crate) transformers: Vec<Transformer>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -46,19 +60,42 @@ pub enum VariableValue {
 }
 
 impl Variable {
-    pub fn get_value(&self, markers_captured: &HashMap<String, String>, request: &Request) -> VariableValue {
+    pub fn get_value(
+        &self,
+        markers_captured: &HashMap<String, String>,
+        request: &Request,
+        accept_language: Option<&AcceptLanguage>,
+    ) -> VariableValue {
         let mut value = match &self.kind {
             VariableKind::RequestHeader { name, default } => Some(
                 request
                     .header_value(name.as_str())
                     .unwrap_or_else(|| default.clone().unwrap_or_default()),
             ),
+            VariableKind::RequestCookie { name, default } => Some(
+                request
+                    .cookie_values(name.as_str(), false)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| default.clone().unwrap_or_default()),
+            ),
             VariableKind::RequestHost => request.host.clone(),
             VariableKind::RequestMethod => request.method.clone(),
             VariableKind::RequestPath => Some(request.path_and_query_skipped.original.clone()),
             VariableKind::RequestRemoteAddress => request.remote_addr.map(|addr| addr.to_string()),
+            VariableKind::RequestCountry => request.geo_location.as_ref().and_then(|location| location.country.clone()),
             VariableKind::RequestScheme => request.scheme.clone(),
             VariableKind::RequestTime => request.created_at.map(|d| d.to_rfc2822()),
+            VariableKind::PreferredLanguage { supported, default } => {
+                let (supported, source_default) = match accept_language {
+                    Some(accept_language) if supported.is_empty() => (&accept_language.supported, accept_language.default.as_ref()),
+                    _ => (supported, None),
+                };
+
+                request
+                    .preferred_language(supported)
+                    .or_else(|| default.as_ref().or(source_default).cloned())
+            }
             VariableKind::Marker(marker_name) => markers_captured.get(marker_name.as_str()).cloned(),
             VariableKind::HtmlBody { selector, default } => {
                 return VariableValue::HtmlFilter {
@@ -85,6 +122,22 @@ impl Variable {
 }
 
 impl VariableValue {
+    /// Transform the value, or the default value of an html filter, e.g. to escape it for the place where it is substituted
+    pub fn map_value(&self, map: impl Fn(&str) -> String) -> VariableValue {
+        match self {
+            VariableValue::Value(value) => VariableValue::Value(map(value)),
+            VariableValue::HtmlFilter {
+                selector,
+                default,
+                transformers,
+            } => VariableValue::HtmlFilter {
+                selector: selector.clone(),
+                default: default.as_deref().map(map),
+                transformers: transformers.clone(),
+            },
+        }
+    }
+
     pub fn to_static(&self, mut new_value: String) -> VariableValue {
         match self {
             VariableValue::Value(_) => VariableValue::Value(new_value),
@@ -107,6 +107,8 @@ struct Rule {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     header_filters: Vec<HeaderFilter>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    request_header_filters: Vec<HeaderFilter>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     body_filters: Vec<BodyFilter>,
     rank: Option<u16>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -133,6 +135,12 @@ struct Source {
     #[serde(skip_serializing_if = "Option::is_none")]
     headers: Option<Vec<SourceHeader>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    query_params: Option<Vec<SourceHeader>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cookies: Option<Vec<SourceHeader>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accept_language: Option<SourceAcceptLanguage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     methods: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     exclude_methods: Option<bool>,
@@ -145,13 +153,28 @@ struct Source {
     #[serde(skip_serializing_if = "Option::is_none")]
     weekdays: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    schedules: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     response_status_codes: Option<Vec<u16>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     exclude_response_status_codes: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    response_headers: Option<Vec<SourceHeader>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sampling: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SourceAcceptLanguage {
+    supported: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct SourceHeader {
     name: String,
@@ -173,11 +196,15 @@ struct BodyFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     value: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     element_tree: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     css_selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attribute: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -199,14 +226,30 @@ struct Transformer {
 #[serde(rename_all = "snake_case")]
 pub enum VariableKind {
     Marker(String),
-    RequestHeader { name: String, default: Option<String> },
+    RequestHeader {
+        name: String,
+        default: Option<String>,
+    },
+    RequestCookie {
+        name: String,
+        default: Option<String>,
+    },
     RequestHost,
     RequestMethod,
     RequestPath,
     RequestRemoteAddress,
+    RequestCountry,
     RequestScheme,
     RequestTime,
-    HtmlBody { selector: String, default: Option<String> },
+    PreferredLanguage {
+        #[serde(default)]
+        supported: Vec<String>,
+        default: Option<String>,
+    },
+    HtmlBody {
+        selector: String,
+        default: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -225,6 +268,12 @@ pub enum IpConstraint {
     InRange(String),
     NotInRange(String),
     NotOneOf(Vec<String>),
+    InCountries(Vec<String>),
+    NotInCountries(Vec<String>),
+    InContinents(Vec<String>),
+    NotInContinents(Vec<String>),
+    InAsns(Vec<u32>),
+    NotInAsns(Vec<u32>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -237,6 +286,8 @@ pub struct Peer {
     pub request_host: String,
     pub allow_invalid_certificates: bool,
     pub tls: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_path: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -382,14 +433,12 @@ fn read_router_tests(path: &str) -> HashMap<String, RuleSet> {
                         rule_sets.extend(read_router_tests(file.path().to_str().unwrap()))
                     } else if file_type.is_file() {
                         match file.path().extension() {
-                            None => (),
-                            Some(ext) => {
-                                if ext == "yml" {
-                                    let (key, rule_set) = build_router_test_file(file).expect("");
+                            Some(ext) if ext == "yml" => {
+                                let (key, rule_set) = build_router_test_file(file).expect("");
 
-                                    rule_sets.insert(key, rule_set);
-                                }
+                                rule_sets.insert(key, rule_set);
                             }
+                            _ => (),
                         }
                     }
                 }
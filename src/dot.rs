@@ -1,5 +1,16 @@
+use std::sync::Arc;
+
 use dot_graph::Graph;
 
 pub trait DotBuilder {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String>;
 }
+
+impl<T> DotBuilder for Arc<T>
+where
+    T: DotBuilder,
+{
+    fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
+        self.as_ref().graph(id, graph)
+    }
+}
@@ -0,0 +1,45 @@
+use std::{cell::RefCell, ffi::CString, os::raw::c_char, ptr::null};
+
+use super::{Error, ErrorCode};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(ErrorCode, CString)>> = const { RefCell::new(None) };
+}
+
+/// Log an error and keep it as the last error of the current thread, so it can be read through the ffi
+pub(crate) fn set_last_error(error: Error) {
+    log::error!("{error}");
+
+    let message = CString::new(error.to_string().replace('\0', "")).unwrap_or_default();
+
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some((error.code(), message)));
+}
+
+/// Returns the code of the last error which happened in a function of this library on the current thread
+///
+/// A function returning null or false can be followed by this call to know why it failed, errors are not
+/// cleared on success, use `redirectionio_error_clear` before the call to check
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_error_last_code() -> ErrorCode {
+    LAST_ERROR.with(|last_error| match last_error.borrow().as_ref() {
+        None => ErrorCode::None,
+        Some((code, _)) => *code,
+    })
+}
+
+/// Returns the message of the last error which happened on the current thread, or null if there is none
+///
+/// The message is owned by the library, it must not be freed and is only valid until the next error
+/// or call to `redirectionio_error_clear` on the same thread
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_error_last_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| match last_error.borrow().as_ref() {
+        None => null(),
+        Some((_, message)) => message.as_ptr(),
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_error_clear() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
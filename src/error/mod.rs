@@ -0,0 +1,205 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod ffi;
+
+use std::{fmt::Display, net::AddrParseError};
+
+use crate::filter::FilterBodyError;
+#[cfg(feature = "router")]
+use crate::router::SnapshotError;
+
+/// This error describes the failures that can be returned by the public apis of this crate
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A rule could not be deserialized
+    RuleParse(serde_json::Error),
+    /// An input, like a router config or a rules message, could not be deserialized
+    Decode {
+        input: &'static str,
+        source: serde_json::Error,
+    },
+    /// A value could not be serialized
+    Encode(serde_json::Error),
+    /// A request could not be built from an url, a method or headers
+    InvalidRequest(http::Error),
+    InvalidIpAddress {
+        value: String,
+        source: AddrParseError,
+    },
+    /// A date, a time or a date time could not be parsed
+    InvalidDateTime {
+        value: String,
+        source: chrono::ParseError,
+    },
+    Regex(regex::Error),
+    Filter(FilterBodyError),
+    #[cfg(feature = "router")]
+    Snapshot(SnapshotError),
+}
+
+/// Code of an error, as exposed through the ffi
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    None = 0,
+    RuleParse = 1,
+    Decode = 2,
+    Encode = 3,
+    InvalidRequest = 4,
+    InvalidIpAddress = 5,
+    InvalidDateTime = 6,
+    Regex = 7,
+    Filter = 8,
+    Snapshot = 9,
+}
+
+impl Error {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::RuleParse(_) => ErrorCode::RuleParse,
+            Self::Decode { .. } => ErrorCode::Decode,
+            Self::Encode(_) => ErrorCode::Encode,
+            Self::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            Self::InvalidIpAddress { .. } => ErrorCode::InvalidIpAddress,
+            Self::InvalidDateTime { .. } => ErrorCode::InvalidDateTime,
+            Self::Regex(_) => ErrorCode::Regex,
+            Self::Filter(_) => ErrorCode::Filter,
+            #[cfg(feature = "router")]
+            Self::Snapshot(_) => ErrorCode::Snapshot,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RuleParse(source) => write!(f, "cannot parse rule: {source}"),
+            Self::Decode { input, source } => write!(f, "cannot deserialize {input}: {source}"),
+            Self::Encode(source) => write!(f, "cannot serialize: {source}"),
+            Self::InvalidRequest(source) => write!(f, "cannot create request: {source}"),
+            Self::InvalidIpAddress { value, source } => write!(f, "cannot parse ip address {value}: {source}"),
+            Self::InvalidDateTime { value, source } => write!(f, "cannot parse datetime {value}: {source}"),
+            Self::Regex(source) => write!(f, "cannot create regex: {source}"),
+            Self::Filter(source) => write!(f, "cannot filter body: {source}"),
+            #[cfg(feature = "router")]
+            Self::Snapshot(source) => write!(f, "invalid router snapshot: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::RuleParse(source) | Self::Decode { source, .. } | Self::Encode(source) => Some(source),
+            Self::InvalidRequest(source) => Some(source),
+            Self::InvalidIpAddress { source, .. } => Some(source),
+            Self::InvalidDateTime { source, .. } => Some(source),
+            Self::Regex(source) => Some(source),
+            Self::Filter(source) => Some(source),
+            #[cfg(feature = "router")]
+            Self::Snapshot(source) => Some(source),
+        }
+    }
+}
+
+impl From<http::Error> for Error {
+    fn from(error: http::Error) -> Self {
+        Self::InvalidRequest(error)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(error: regex::Error) -> Self {
+        Self::Regex(error)
+    }
+}
+
+impl From<FilterBodyError> for Error {
+    fn from(error: FilterBodyError) -> Self {
+        Self::Filter(error)
+    }
+}
+
+#[cfg(feature = "router")]
+impl From<SnapshotError> for Error {
+    fn from(error: SnapshotError) -> Self {
+        Self::Snapshot(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "router")]
+    use crate::{
+        RouterConfig,
+        api::{Example, Rule},
+        http::Request,
+    };
+
+    #[cfg(feature = "router")]
+    #[test]
+    fn test_public_api_errors() {
+        assert_eq!(Rule::from_json("{\"id\": 1}").unwrap_err().code(), ErrorCode::RuleParse);
+
+        let example = Example {
+            url: "/foo".to_string(),
+            method: None,
+            headers: None,
+            datetime: None,
+            ip_address: Some("not an ip".to_string()),
+            response_status_code: None,
+            must_match: true,
+            unit_ids_applied: None,
+        };
+
+        assert_eq!(
+            Request::from_example(&RouterConfig::default(), &example).unwrap_err().code(),
+            ErrorCode::InvalidIpAddress
+        );
+
+        let mut request: Request = "/foo".parse().unwrap();
+
+        assert_eq!(
+            request.set_created_at(Some("yesterday".to_string())).unwrap_err().code(),
+            ErrorCode::InvalidDateTime
+        );
+    }
+
+    #[test]
+    fn test_regex_and_filter_errors() {
+        assert_eq!(
+            crate::regex::LazyRegex::new_leaf("(", false).try_create_regex().unwrap_err().code(),
+            ErrorCode::Regex
+        );
+        assert_eq!(
+            Error::from(FilterBodyError::UnsupportedEncoding("compress".to_string())).code(),
+            ErrorCode::Filter
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_ffi_last_error() {
+        use std::ffi::CStr;
+
+        use super::ffi::{redirectionio_error_clear, redirectionio_error_last_code, redirectionio_error_last_message, set_last_error};
+
+        redirectionio_error_clear();
+
+        assert_eq!(redirectionio_error_last_code(), ErrorCode::None);
+        assert!(redirectionio_error_last_message().is_null());
+
+        set_last_error(Error::Decode {
+            input: "rules message",
+            source: serde_json::from_str::<u8>("{").unwrap_err(),
+        });
+
+        assert_eq!(redirectionio_error_last_code(), ErrorCode::Decode);
+
+        // Safety: the message is a valid c string until the next error on this thread
+        let message = unsafe { CStr::from_ptr(redirectionio_error_last_message()) };
+
+        assert!(message.to_str().unwrap().starts_with("cannot deserialize rules message"));
+    }
+}
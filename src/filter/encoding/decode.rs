@@ -5,6 +5,7 @@ use std::{
 
 use brotli::DecompressorWriter;
 use flate2::write::{GzDecoder, ZlibDecoder};
+use zstd::stream::write::Decoder as ZstdDecoder;
 
 use crate::filter::{encoding::SupportedEncoding, error::Result};
 
@@ -12,6 +13,7 @@ pub enum DecodeFilterBody {
     Gzip(GzDecoder<Vec<u8>>),
     Brotli(Box<DecompressorWriter<Vec<u8>>>),
     Deflate(ZlibDecoder<Vec<u8>>),
+    Zstd(Box<ZstdDecoder<'static, Vec<u8>>>),
 }
 
 impl Debug for DecodeFilterBody {
@@ -21,12 +23,13 @@ impl Debug for DecodeFilterBody {
 }
 
 impl DecodeFilterBody {
-    pub fn new(encoding: SupportedEncoding) -> Self {
-        match encoding {
+    pub fn new(encoding: SupportedEncoding) -> Result<Self> {
+        Ok(match encoding {
             SupportedEncoding::Brotli => Self::Brotli(Box::new(DecompressorWriter::new(Vec::new(), 4096))),
             SupportedEncoding::Gzip => Self::Gzip(GzDecoder::new(Vec::new())),
             SupportedEncoding::Deflate => Self::Deflate(ZlibDecoder::new(Vec::new())),
-        }
+            SupportedEncoding::Zstd => Self::Zstd(Box::new(ZstdDecoder::new(Vec::new())?)),
+        })
     }
 
     pub fn filter(&mut self, data: Vec<u8>) -> Result<Vec<u8>> {
@@ -68,6 +71,19 @@ impl DecodeFilterBody {
                 let mut buffer = Vec::new();
                 std::mem::swap(&mut buffer, decoder.get_mut());
 
+                Ok(buffer)
+            }
+            Self::Zstd(decoder) => {
+                decoder.write_all(data.as_slice())?;
+                decoder.flush()?;
+
+                if decoder.get_ref().is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let mut buffer = Vec::new();
+                std::mem::swap(&mut buffer, decoder.get_mut());
+
                 Ok(buffer)
             }
         }
@@ -87,6 +103,10 @@ impl DecodeFilterBody {
                 Ok(buffer) => Ok(buffer),
                 Err(buffer) => Ok(buffer),
             },
+            Self::Zstd(mut d) => {
+                d.flush()?;
+                Ok(d.into_inner())
+            }
         }
     }
 }
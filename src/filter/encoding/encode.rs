@@ -5,6 +5,7 @@ use std::{
 
 use brotli::CompressorWriter;
 use flate2::write::{GzEncoder, ZlibEncoder};
+use zstd::stream::write::Encoder as ZstdEncoder;
 
 use crate::filter::{encoding::SupportedEncoding, error::Result};
 
@@ -12,6 +13,7 @@ pub enum EncodeFilterBody {
     Gzip(GzEncoder<Vec<u8>>),
     Brotli(Box<CompressorWriter<Vec<u8>>>),
     Deflate(ZlibEncoder<Vec<u8>>),
+    Zstd(Box<ZstdEncoder<'static, Vec<u8>>>),
 }
 
 impl Debug for EncodeFilterBody {
@@ -21,12 +23,13 @@ impl Debug for EncodeFilterBody {
 }
 
 impl EncodeFilterBody {
-    pub fn new(encoding: SupportedEncoding) -> Self {
-        match encoding {
+    pub fn new(encoding: SupportedEncoding) -> Result<Self> {
+        Ok(match encoding {
             SupportedEncoding::Brotli => Self::Brotli(Box::new(CompressorWriter::new(Vec::new(), 4096, 11, 22))),
             SupportedEncoding::Gzip => Self::Gzip(GzEncoder::new(Vec::new(), flate2::Compression::default())),
             SupportedEncoding::Deflate => Self::Deflate(ZlibEncoder::new(Vec::new(), flate2::Compression::default())),
-        }
+            SupportedEncoding::Zstd => Self::Zstd(Box::new(ZstdEncoder::new(Vec::new(), zstd::DEFAULT_COMPRESSION_LEVEL)?)),
+        })
     }
 
     pub fn filter(&mut self, data: Vec<u8>) -> Result<Vec<u8>> {
@@ -68,6 +71,19 @@ impl EncodeFilterBody {
                 let mut buffer = Vec::new();
                 std::mem::swap(&mut buffer, encoder.get_mut());
 
+                Ok(buffer)
+            }
+            Self::Zstd(encoder) => {
+                encoder.write_all(data.as_slice())?;
+                encoder.flush()?;
+
+                if encoder.get_ref().is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let mut buffer = Vec::new();
+                std::mem::swap(&mut buffer, encoder.get_mut());
+
                 Ok(buffer)
             }
         }
@@ -84,6 +100,7 @@ impl EncodeFilterBody {
                 Ok(encoder.finish()?)
             }
             Self::Brotli(encoder) => Ok(encoder.into_inner()),
+            Self::Zstd(encoder) => Ok(encoder.finish()?),
         }
     }
 }
@@ -6,6 +6,7 @@ pub enum SupportedEncoding {
     Brotli,
     Gzip,
     Deflate,
+    Zstd,
 }
 
 impl SupportedEncoding {
@@ -14,25 +15,48 @@ impl SupportedEncoding {
         set.insert("br".to_string());
         set.insert("gzip".to_string());
         set.insert("deflate".to_string());
+        set.insert("zstd".to_string());
         set
     }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "br" => Some(SupportedEncoding::Brotli),
+            "gzip" | "x-gzip" => Some(SupportedEncoding::Gzip),
+            "deflate" => Some(SupportedEncoding::Deflate),
+            "zstd" => Some(SupportedEncoding::Zstd),
+            _ => None,
+        }
+    }
 }
 
 use std::collections::HashSet;
 
+use crate::filter::error::{FilterBodyError, Result};
+
 pub use decode::DecodeFilterBody;
 pub use encode::EncodeFilterBody;
 
-pub fn get_encoding_filters(encoding: &str) -> Option<(DecodeFilterBody, EncodeFilterBody)> {
-    let supported_encoding = match encoding {
-        "br" => SupportedEncoding::Brotli,
-        "gzip" => SupportedEncoding::Gzip,
-        "deflate" => SupportedEncoding::Deflate,
-        _ => return None,
-    };
-
-    Some((
-        DecodeFilterBody::new(supported_encoding.clone()),
-        EncodeFilterBody::new(supported_encoding),
-    ))
+/// Create filters for a content-encoding header value, which may list several encodings in the order they were
+/// applied (e.g. `gzip, br`)
+///
+/// Decoders are returned in decoding order (last applied encoding first) and encoders in encoding order, so they
+/// can be respectively put at the start and the end of the filter chain
+pub fn get_encoding_filters(encoding: &str) -> Result<(Vec<DecodeFilterBody>, Vec<EncodeFilterBody>)> {
+    let mut decoders = Vec::new();
+    let mut encoders = Vec::new();
+
+    for name in encoding
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "identity")
+    {
+        let supported_encoding =
+            SupportedEncoding::from_name(name).ok_or_else(|| FilterBodyError::UnsupportedEncoding(name.to_string()))?;
+
+        decoders.insert(0, DecodeFilterBody::new(supported_encoding.clone())?);
+        encoders.push(EncodeFilterBody::new(supported_encoding)?);
+    }
+
+    Ok((decoders, encoders))
 }
@@ -6,17 +6,31 @@ use std::result;
 pub enum FilterBodyError {
     /// Error while reading or writing to the buffer
     IoError(std::io::Error),
+    /// The body uses a content-encoding which cannot be decoded, so it cannot be filtered
+    UnsupportedEncoding(String),
+    /// The html of the body could not be rewritten
+    Html(lol_html::errors::RewritingError),
 }
 
 impl std::fmt::Display for FilterBodyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::IoError(source) => write!(f, "{source}"),
+            Self::UnsupportedEncoding(encoding) => write!(f, "unsupported content-encoding {encoding}"),
+            Self::Html(source) => write!(f, "cannot rewrite html: {source}"),
         }
     }
 }
 
-impl std::error::Error for FilterBodyError {}
+impl std::error::Error for FilterBodyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::IoError(source) => Some(source),
+            Self::UnsupportedEncoding(_) => None,
+            Self::Html(source) => Some(source),
+        }
+    }
+}
 
 impl From<std::io::Error> for FilterBodyError {
     fn from(error: std::io::Error) -> Self {
@@ -24,4 +38,10 @@ impl From<std::io::Error> for FilterBodyError {
     }
 }
 
+impl From<lol_html::errors::RewritingError> for FilterBodyError {
+    fn from(error: lol_html::errors::RewritingError) -> Self {
+        Self::Html(error)
+    }
+}
+
 pub type Result<T> = result::Result<T, FilterBodyError>;
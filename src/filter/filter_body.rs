@@ -1,5 +1,7 @@
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
+use regex::bytes::Regex;
+
 #[cfg(feature = "compress")]
 use crate::filter::encoding::{DecodeFilterBody, EncodeFilterBody, get_encoding_filters};
 use crate::{
@@ -8,7 +10,7 @@ use crate::{
     filter::{
         HtmlFilterBodyAction,
         buffer_filter_body::BufferFilterBody,
-        error::Result,
+        error::{FilterBodyError, Result},
         html_body_action::{
             HtmlBodyVisitor,
             body_capture::{BodyCapture, CaptureRegistry},
@@ -22,6 +24,7 @@ use crate::{
 pub struct FilterBodyAction {
     chain: Vec<FilterBodyActionItem>,
     in_error: bool,
+    last_error: Option<FilterBodyError>,
 }
 
 #[derive(Debug)]
@@ -36,12 +39,29 @@ pub enum FilterBodyActionItem {
 }
 
 impl FilterBodyAction {
+    /// Create the filters of the body, filtering is disabled when the body cannot be decoded
     pub fn new(
         filters: Vec<BodyFilter>,
         headers: &[Header],
         unit_trace: Option<Rc<RefCell<UnitTrace>>>,
         variables: Vec<(String, VariableValue)>,
     ) -> Self {
+        match Self::try_new(filters, headers, unit_trace, variables) {
+            Ok(filter_body) => filter_body,
+            Err(err) => {
+                log::error!("{err}, filtering will be disabled for this request");
+
+                Self::from_chain(Vec::new())
+            }
+        }
+    }
+
+    pub fn try_new(
+        filters: Vec<BodyFilter>,
+        headers: &[Header],
+        unit_trace: Option<Rc<RefCell<UnitTrace>>>,
+        variables: Vec<(String, VariableValue)>,
+    ) -> Result<Self> {
         let mut chain = Vec::new();
         let mut content_type = None;
         #[cfg(feature = "compress")]
@@ -81,35 +101,27 @@ impl FilterBodyAction {
             );
         }
 
-        #[cfg(not(feature = "compress"))]
+        #[cfg(feature = "compress")]
+        if !chain.is_empty()
+            && let Some(encoding) = content_encoding
         {
-            return Self { chain, in_error: false };
-        }
+            let (decoders, encoders) = get_encoding_filters(encoding.as_str())?;
 
-        #[cfg(feature = "compress")]
-        if chain.is_empty() {
-            return Self { chain, in_error: false };
+            chain.splice(
+                0..0,
+                decoders.into_iter().map(|decode| FilterBodyActionItem::Decode(Box::new(decode))),
+            );
+            chain.extend(encoders.into_iter().map(|encode| FilterBodyActionItem::Encode(Box::new(encode))));
         }
 
-        #[cfg(feature = "compress")]
-        match content_encoding {
-            Some(encoding) => match get_encoding_filters(encoding.as_str()) {
-                Some((decode, encode)) => {
-                    chain.insert(0, FilterBodyActionItem::Decode(Box::new(decode)));
-                    chain.push(FilterBodyActionItem::Encode(Box::new(encode)));
-
-                    Self { chain, in_error: false }
-                }
-                None => {
-                    log::error!("redirectionio does not support content-encoding {encoding}, filtering will be disable for this request");
+        Ok(Self::from_chain(chain))
+    }
 
-                    Self {
-                        chain: Vec::new(),
-                        in_error: false,
-                    }
-                }
-            },
-            None => Self { chain, in_error: false },
+    fn from_chain(chain: Vec<FilterBodyActionItem>) -> Self {
+        Self {
+            chain,
+            in_error: false,
+            last_error: None,
         }
     }
 
@@ -117,6 +129,7 @@ impl FilterBodyAction {
         self.chain.is_empty()
     }
 
+    /// Filter a chunk of the body, after an error the chunks are returned unmodified, see `take_error`
     pub fn filter(&mut self, data: Vec<u8>, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Vec<u8> {
         if self.in_error {
             return data;
@@ -125,14 +138,20 @@ impl FilterBodyAction {
         match self.do_filter(data.clone(), unit_trace) {
             Ok(filtered) => filtered,
             Err(err) => {
-                log::error!("error while filtering: {err:?}");
+                log::error!("error while filtering: {err}");
                 self.in_error = true;
+                self.last_error = Some(err);
 
                 data
             }
         }
     }
 
+    /// Error which stopped the filtering of the body, if it was not already taken
+    pub fn take_error(&mut self) -> Option<FilterBodyError> {
+        self.last_error.take()
+    }
+
     fn do_filter(&mut self, mut data: Vec<u8>, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Result<Vec<u8>> {
         for item in &mut self.chain {
             data = item.filter(data, unit_trace.clone())?;
@@ -146,11 +165,7 @@ impl FilterBodyAction {
     }
 
     pub fn end(self, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Vec<u8> {
-        if self.in_error {
-            return Vec::new();
-        }
-
-        match self.do_end(unit_trace) {
+        match self.try_end(unit_trace) {
             Ok(end) => end,
             Err(err) => {
                 log::error!("error while ending filtering: {err}");
@@ -160,6 +175,15 @@ impl FilterBodyAction {
         }
     }
 
+    /// Flush the end of the body, nothing is returned when the filtering already failed
+    pub fn try_end(self, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Result<Vec<u8>> {
+        if self.in_error {
+            return Ok(Vec::new());
+        }
+
+        self.do_end(unit_trace)
+    }
+
     fn do_end(self, unit_trace: Option<Rc<RefCell<UnitTrace>>>) -> Result<Vec<u8>> {
         let mut data = None;
 
@@ -215,6 +239,28 @@ impl FilterBodyActionItem {
                     TextAction::Append => TextFilterAction::Append,
                     TextAction::Prepend => TextFilterAction::Prepend,
                     TextAction::Replace => TextFilterAction::Replace,
+                    TextAction::ReplaceRegex | TextAction::ReplaceString => {
+                        let Some(pattern) = text_body_filter.pattern.as_deref().filter(|pattern| !pattern.is_empty()) else {
+                            log::error!("text body filter {:?} requires a pattern", text_body_filter.action);
+                            return None;
+                        };
+
+                        let is_regex = matches!(text_body_filter.action, TextAction::ReplaceRegex);
+                        let regex = if is_regex {
+                            Regex::new(pattern)
+                        } else {
+                            Regex::new(regex::escape(pattern).as_str())
+                        };
+
+                        match regex {
+                            Ok(regex) if is_regex => TextFilterAction::ReplaceRegex(regex),
+                            Ok(regex) => TextFilterAction::ReplaceString(regex),
+                            Err(err) => {
+                                log::error!("cannot create regex for text body filter: {err}");
+                                return None;
+                            }
+                        }
+                    }
                     _ => {
                         log::warn!(
                             "unsupported text body filter action: {:?}, you may need to update your agent or module",
@@ -247,7 +293,7 @@ impl FilterBodyActionItem {
     pub fn end(self) -> Result<Vec<u8>> {
         Ok(match self {
             FilterBodyActionItem::Buffer(buffer) => buffer.end(),
-            FilterBodyActionItem::Html(html_body_filter) => html_body_filter.end(),
+            FilterBodyActionItem::Html(html_body_filter) => html_body_filter.end()?,
             FilterBodyActionItem::Text(text_body_filter) => text_body_filter.end(),
             #[cfg(feature = "compress")]
             FilterBodyActionItem::Decode(decode_body_filter) => decode_body_filter.end()?,
@@ -267,7 +313,7 @@ mod tests {
     };
 
     use super::*;
-    use crate::api::HTMLBodyFilter;
+    use crate::api::{HTMLBodyFilter, TextBodyFilter};
 
     #[test]
     pub fn test_filter_gzip() {
@@ -296,6 +342,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &headers,
             None,
@@ -351,6 +398,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &headers,
             None,
@@ -379,6 +427,86 @@ mod tests {
         );
     }
 
+    fn prepend_filter_in_chunks(content_encoding: &str, compressed_input: &[u8]) -> Vec<u8> {
+        let headers = vec![
+            Header {
+                name: "Content-Encoding".to_string(),
+                value: content_encoding.to_string(),
+            },
+            Header {
+                name: "Content-Type".to_string(),
+                value: "text/html;charset=".to_string(),
+            },
+        ];
+
+        let mut filter = FilterBodyAction::new(
+            vec![BodyFilter::HTML(HTMLBodyFilter {
+                action: "prepend_child".to_string(),
+                element_tree: vec!["html".to_string(), "body".to_string()],
+                css_selector: Some("".to_string()),
+                value: "<p>This is as test</p>".to_string(),
+                id: Some("test".to_string()),
+                target_hash: Some("target_hash".to_string()),
+                inner_value: None,
+                attribute: None,
+            })],
+            &headers,
+            None,
+            Vec::new(),
+        );
+
+        let mut filtered = Vec::new();
+
+        for chunk in compressed_input.chunks(10) {
+            filtered.extend(filter.filter(chunk.to_vec(), None));
+        }
+
+        filtered.extend(filter.end(None));
+
+        filtered
+    }
+
+    #[test]
+    pub fn test_filter_zstd() {
+        let decompressed_input = "<html><head></head><body class=\"page\"><div>Yolo</div></body></html>".to_string();
+        let compressed_input = zstd::encode_all(decompressed_input.as_bytes(), 0).unwrap();
+
+        let filtered = prepend_filter_in_chunks("zstd", &compressed_input);
+        let decompressed_output = zstd::decode_all(filtered.as_slice()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(decompressed_output).unwrap(),
+            "<html><head></head><body class=\"page\"><p>This is as test</p><div>Yolo</div></body></html>".to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_filter_stacked_encodings() {
+        let decompressed_input = "<html><head></head><body class=\"page\"><div>Yolo</div></body></html>".to_string();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(decompressed_input.as_bytes()).unwrap();
+        let gzipped_input = encoder.finish().unwrap();
+        let compressed_input = zstd::encode_all(gzipped_input.as_slice(), 0).unwrap();
+
+        let filtered = prepend_filter_in_chunks("gzip, zstd", &compressed_input);
+        let gzipped_output = zstd::decode_all(filtered.as_slice()).unwrap();
+        let mut decoder = GzDecoder::new(Vec::new());
+        decoder.write_all(&gzipped_output).unwrap();
+        let decompressed_output = decoder.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(decompressed_output).unwrap(),
+            "<html><head></head><body class=\"page\"><p>This is as test</p><div>Yolo</div></body></html>".to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_filter_unsupported_stacked_encoding() {
+        let filtered = prepend_filter_in_chunks("gzip, compress", b"<html><body></body></html>");
+
+        assert_eq!(filtered, b"<html><body></body></html>".to_vec());
+    }
+
     #[test]
     pub fn test_filter_brotli() {
         let decompressed_input = "<html><head><h2>This is stupide data to ensure compression before</H2></head><body class=\"page\"><div>Yolo</div></body></html>".to_string();
@@ -406,6 +534,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &headers,
             None,
@@ -468,6 +597,7 @@ mod tests {
                     id: Some("test".to_string()),
                     target_hash: Some("target_hash".to_string()),
                     inner_value: None,
+                    attribute: None,
                 }),
                 BodyFilter::HTML(HTMLBodyFilter {
                     action: "replace".to_string(),
@@ -477,6 +607,7 @@ mod tests {
                     id: Some("test".to_string()),
                     target_hash: Some("target_hash".to_string()),
                     inner_value: None,
+                    attribute: None,
                 }),
             ],
             &[],
@@ -508,6 +639,7 @@ mod tests {
                     id: Some("test".to_string()),
                     target_hash: Some("target_hash".to_string()),
                     inner_value: None,
+                    attribute: None,
                 }),
                 BodyFilter::HTML(HTMLBodyFilter {
                     action: "replace".to_string(),
@@ -517,6 +649,7 @@ mod tests {
                     id: Some("test".to_string()),
                     target_hash: Some("target_hash".to_string()),
                     inner_value: None,
+                    attribute: None,
                 }),
             ],
             &[],
@@ -544,6 +677,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &[],
             None,
@@ -575,6 +709,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &[],
             None,
@@ -601,6 +736,7 @@ mod tests {
                 id: Some("test".to_string()),
                 target_hash: Some("target_hash".to_string()),
                 inner_value: None,
+                attribute: None,
             })],
             &[],
             None,
@@ -623,4 +759,255 @@ mod tests {
             String::from_utf8(filtered).unwrap()
         );
     }
+
+    fn filter_text_in_chunks(action: TextAction, pattern: &str, content: &str, input: &str, chunk_size: usize) -> String {
+        let mut filter = FilterBodyAction::new(
+            vec![BodyFilter::Text(TextBodyFilter {
+                action,
+                content: content.to_string(),
+                pattern: Some(pattern.to_string()),
+                id: Some("test".to_string()),
+                target_hash: None,
+            })],
+            &[Header {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            }],
+            None,
+            Vec::new(),
+        );
+
+        let mut filtered = Vec::new();
+
+        for chunk in input.as_bytes().chunks(chunk_size) {
+            filtered.extend(filter.filter(chunk.to_vec(), None));
+        }
+
+        filtered.extend(filter.end(None));
+
+        String::from_utf8(filtered).unwrap()
+    }
+
+    #[test]
+    pub fn test_replace_string_across_chunks() {
+        let input = r#"{"links":["https://old.example.com/a","https://old.example.com/b"]}"#;
+
+        for chunk_size in [1, 3, 7, input.len()] {
+            assert_eq!(
+                filter_text_in_chunks(TextAction::ReplaceString, "old.example.com", "new.example.com", input, chunk_size),
+                r#"{"links":["https://new.example.com/a","https://new.example.com/b"]}"#
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_replace_string_does_not_expand() {
+        assert_eq!(
+            filter_text_in_chunks(TextAction::ReplaceString, "a.b", "$0", "a.b axb", 2),
+            "$0 axb"
+        );
+    }
+
+    #[test]
+    pub fn test_replace_regex_with_captures() {
+        let input = "body { background: url(/images/foo.png); } .a { background: url(/images/bar.png); }";
+
+        for chunk_size in [1, 5, input.len()] {
+            assert_eq!(
+                filter_text_in_chunks(
+                    TextAction::ReplaceRegex,
+                    r"/images/(\w+)\.png",
+                    "/static/${1}.webp",
+                    input,
+                    chunk_size
+                ),
+                "body { background: url(/static/foo.webp); } .a { background: url(/static/bar.webp); }"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_replace_regex_large_body() {
+        let input = "foo ".repeat(10_000);
+
+        let filtered = filter_text_in_chunks(TextAction::ReplaceRegex, "fo+", "bar", input.as_str(), 1000);
+
+        assert_eq!(filtered, "bar ".repeat(10_000));
+    }
+
+    #[test]
+    pub fn test_replace_regex_split_across_two_chunks() {
+        let input = "see /images/foo.png";
+
+        assert_eq!(
+            filter_text_in_chunks(TextAction::ReplaceRegex, r"/images/(\w+)\.png", "/static/$1.webp", input, 12),
+            "see /static/foo.webp"
+        );
+    }
+
+    #[test]
+    pub fn test_replace_regex_anchors_at_chunk_boundaries() {
+        let input = "foo xfoo ".repeat(1_000);
+
+        for chunk_size in [1, 7, 1000, 4096] {
+            let filtered = filter_text_in_chunks(TextAction::ReplaceRegex, r"^foo|\bfoo", "bar", input.as_str(), chunk_size);
+
+            assert_eq!(filtered, "bar xfoo ".repeat(1_000));
+
+            let filtered = filter_text_in_chunks(TextAction::ReplaceRegex, r"\Afoo", "bar", input.as_str(), chunk_size);
+
+            assert_eq!(filtered, format!("bar xfoo {}", "foo xfoo ".repeat(999)));
+        }
+    }
+
+    fn filter_html(action: &str, css_selector: &str, attribute: Option<&str>, value: &str, input: &str) -> String {
+        let mut filter = FilterBodyAction::new(
+            vec![BodyFilter::HTML(HTMLBodyFilter {
+                action: action.to_string(),
+                element_tree: vec!["html".to_string(), "head".to_string()],
+                css_selector: Some(css_selector.to_string()),
+                value: value.to_string(),
+                id: Some("test".to_string()),
+                target_hash: None,
+                inner_value: None,
+                attribute: attribute.map(|attribute| attribute.to_string()),
+            })],
+            &[],
+            None,
+            Vec::new(),
+        );
+
+        let mut filtered = filter.filter(input.to_string().into_bytes(), None);
+        filtered.extend(filter.end(None));
+
+        String::from_utf8(filtered).unwrap()
+    }
+
+    #[test]
+    pub fn test_remove() {
+        assert_eq!(
+            filter_html(
+                "remove",
+                r#"script[src*="tracker"]"#,
+                None,
+                "",
+                r#"<html><head><script src="/tracker.js"></script><script src="/app.js"></script></head></html>"#
+            ),
+            r#"<html><head><script src="/app.js"></script></head></html>"#
+        );
+    }
+
+    #[test]
+    pub fn test_set_attribute() {
+        assert_eq!(
+            filter_html(
+                "set_attribute",
+                r#"link[rel="canonical"]"#,
+                Some("href"),
+                "https://example.com/new",
+                r#"<html><head><link rel="canonical" href="https://example.com/old"></head></html>"#
+            ),
+            r#"<html><head><link rel="canonical" href="https://example.com/new"></head></html>"#
+        );
+    }
+
+    #[test]
+    pub fn test_set_invalid_attribute_with_trace() {
+        let input = r#"<html><head><link rel="canonical" href="/old"></head></html>"#;
+
+        for (attribute, expected, traced) in [
+            ("data id", input, false),
+            (
+                "data-id",
+                r#"<html><head><link rel="canonical" href="/old" data-id="1"></head></html>"#,
+                true,
+            ),
+        ] {
+            let unit_trace = Rc::new(RefCell::new(UnitTrace::default()));
+            let mut filter = FilterBodyAction::new(
+                vec![BodyFilter::HTML(HTMLBodyFilter {
+                    action: "set_attribute".to_string(),
+                    element_tree: vec!["html".to_string(), "head".to_string()],
+                    css_selector: Some("link".to_string()),
+                    value: "1".to_string(),
+                    id: Some("test".to_string()),
+                    target_hash: None,
+                    inner_value: None,
+                    attribute: Some(attribute.to_string()),
+                })],
+                &[],
+                Some(unit_trace.clone()),
+                Vec::new(),
+            );
+
+            let mut filtered = filter.filter(input.to_string().into_bytes(), Some(unit_trace.clone()));
+            filtered.extend(filter.end(Some(unit_trace.clone())));
+
+            assert_eq!(String::from_utf8(filtered).unwrap(), expected);
+            assert_eq!(unit_trace.borrow().get_unit_ids_applied().contains("test"), traced);
+        }
+    }
+
+    #[test]
+    pub fn test_remove_attribute() {
+        assert_eq!(
+            filter_html(
+                "remove_attribute",
+                r#"meta[name="robots"]"#,
+                Some("content"),
+                "",
+                r#"<html><head><meta name="robots" content="noindex"></head></html>"#
+            ),
+            r#"<html><head><meta name="robots"></head></html>"#
+        );
+    }
+
+    #[test]
+    pub fn test_wrap() {
+        assert_eq!(
+            filter_html(
+                "wrap",
+                "title",
+                None,
+                "<noscript></noscript>",
+                "<html><head><title>Test</title></head></html>"
+            ),
+            "<html><head><noscript><title>Test</title></noscript></head></html>"
+        );
+    }
+
+    #[test]
+    pub fn test_insert_before_and_after() {
+        let input = "<html><head><title>Test</title></head></html>";
+
+        assert_eq!(
+            filter_html("insert_before", "title", None, "<meta charset=\"utf-8\">", input),
+            "<html><head><meta charset=\"utf-8\"><title>Test</title></head></html>"
+        );
+        assert_eq!(
+            filter_html("insert_after", "title", None, "<meta charset=\"utf-8\">", input),
+            "<html><head><title>Test</title><meta charset=\"utf-8\"></head></html>"
+        );
+    }
+
+    #[test]
+    pub fn test_unsupported_encoding() {
+        let filters = vec![BodyFilter::Text(TextBodyFilter {
+            action: TextAction::Append,
+            content: "appended".to_string(),
+            pattern: None,
+            id: Some("test".to_string()),
+            target_hash: None,
+        })];
+        let headers = vec![Header {
+            name: "Content-Encoding".to_string(),
+            value: "gzip, compress".to_string(),
+        }];
+
+        assert!(matches!(
+            FilterBodyAction::try_new(filters.clone(), &headers, None, Vec::new()),
+            Err(FilterBodyError::UnsupportedEncoding(encoding)) if encoding == "compress"
+        ));
+        assert!(FilterBodyAction::new(filters, &headers, None, Vec::new()).is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    cmp::Reverse,
     collections::HashMap,
     ops::Deref,
     sync::{Arc, RwLock},
@@ -31,7 +32,7 @@ impl CaptureRegistry {
             .collect::<Vec<(String, VariableValue)>>();
 
         // sort by length descending to replace longer keys first
-        variables.sort_by(|(key_a, _), (key_b, _)| key_b.len().cmp(&key_a.len()));
+        variables.sort_by_key(|(key, _)| Reverse(key.len()));
 
         StaticOrDynamic::replace(value, &variables, true)
     }
@@ -0,0 +1,75 @@
+use std::{cell::RefCell, rc::Rc};
+
+use lol_html::{ElementHandler, send::IntoHandler};
+
+use crate::action::UnitTrace;
+
+#[derive(Debug)]
+pub struct BodyRemoveAttribute {
+    element_tree: Vec<String>,
+    css_selector: Option<String>,
+    attribute: String,
+    id: Option<String>,
+    target_hash: Option<String>,
+    unit_trace: Option<Rc<RefCell<UnitTrace>>>,
+}
+
+impl<'h> IntoHandler<ElementHandler<'h>> for BodyRemoveAttribute {
+    fn into_handler(self) -> ElementHandler<'h> {
+        Box::new(move |element| {
+            element.remove_attribute(self.attribute.as_str());
+
+            if let (Some(unit_trace), Some(id)) = (self.unit_trace.clone(), &self.id) {
+                if let Some(target_hash) = &self.target_hash {
+                    unit_trace
+                        .borrow_mut()
+                        .override_unit_id_with_target(target_hash.as_str(), id.as_str());
+                } else {
+                    unit_trace.borrow_mut().add_unit_id(id.clone());
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl BodyRemoveAttribute {
+    pub fn new(
+        element_tree: Vec<String>,
+        css_selector: Option<String>,
+        attribute: String,
+        id: Option<String>,
+        target_hash: Option<String>,
+        unit_trace: Option<Rc<RefCell<UnitTrace>>>,
+    ) -> BodyRemoveAttribute {
+        BodyRemoveAttribute {
+            element_tree,
+            css_selector,
+            attribute,
+            id,
+            target_hash,
+            unit_trace,
+        }
+    }
+}
+
+impl BodyRemoveAttribute {
+    pub fn css_selector(&self) -> String {
+        let mut element_tree = self.element_tree.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+
+        if let Some(css_selector) = &self.css_selector
+            && !css_selector.is_empty()
+        {
+            if let Some(last) = element_tree.last()
+                && css_selector.starts_with(last)
+            {
+                element_tree.remove(element_tree.len() - 1);
+            }
+
+            return format!("{} > {}", element_tree.join(" > "), css_selector);
+        }
+
+        element_tree.join(" > ")
+    }
+}
@@ -1,11 +1,17 @@
 pub mod body_append;
 pub mod body_capture;
+pub mod body_insert_after;
+pub mod body_insert_before;
 pub mod body_prepend;
+pub mod body_remove;
+pub mod body_remove_attribute;
 pub mod body_replace;
+pub mod body_set_attribute;
+pub mod body_wrap;
 
 use std::{borrow::Cow, cell::RefCell, fmt::Debug, rc::Rc, sync::Arc};
 
-use lol_html::{ElementContentHandlers, Settings};
+use lol_html::{ElementContentHandlers, ElementHandler, Settings, send::IntoHandler};
 
 use crate::{
     action::UnitTrace,
@@ -13,8 +19,14 @@ use crate::{
     filter::html_body_action::{
         body_append::BodyAppend,
         body_capture::{BodyCapture, CaptureRegistry},
+        body_insert_after::BodyInsertAfter,
+        body_insert_before::BodyInsertBefore,
         body_prepend::BodyPrepend,
+        body_remove::BodyRemove,
+        body_remove_attribute::BodyRemoveAttribute,
         body_replace::BodyReplace,
+        body_set_attribute::BodySetAttribute,
+        body_wrap::BodyWrap,
     },
 };
 
@@ -23,10 +35,44 @@ pub enum HtmlBodyVisitor {
     Append(BodyAppend),
     Prepend(BodyPrepend),
     Replace(BodyReplace),
+    Remove(BodyRemove),
+    SetAttribute(BodySetAttribute),
+    RemoveAttribute(BodyRemoveAttribute),
+    Wrap(BodyWrap),
+    InsertBefore(BodyInsertBefore),
+    InsertAfter(BodyInsertAfter),
     Capture(BodyCapture),
 }
 
 impl HtmlBodyVisitor {
+    pub fn is_supported_action(action: &str) -> bool {
+        matches!(
+            action,
+            "append_child"
+                | "prepend_child"
+                | "replace"
+                | "remove"
+                | "set_attribute"
+                | "remove_attribute"
+                | "wrap"
+                | "insert_before"
+                | "insert_after"
+        )
+    }
+
+    /// Whether the action needs the `attribute` field of the filter
+    pub fn is_attribute_action(action: &str) -> bool {
+        matches!(action, "set_attribute" | "remove_attribute")
+    }
+
+    /// Whether the attribute name can be set on an element, it follows the rules of the html rewriter
+    pub fn is_valid_attribute_name(name: &str) -> bool {
+        !name.is_empty()
+            && !name
+                .bytes()
+                .any(|byte| matches!(byte, b' ' | b'\n' | b'\r' | b'\t' | b'\x0C' | b'/' | b'>' | b'='))
+    }
+
     pub fn new(
         filter: HTMLBodyFilter,
         unit_trace: Option<Rc<RefCell<UnitTrace>>>,
@@ -67,6 +113,62 @@ impl HtmlBodyVisitor {
                 unit_trace,
                 variables.clone(),
             ))),
+            "remove" => Some(HtmlBodyVisitor::Remove(BodyRemove::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+            ))),
+            "set_attribute" => Some(HtmlBodyVisitor::SetAttribute(BodySetAttribute::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.attribute.filter(|attribute| !attribute.is_empty())?,
+                filter.value.clone(),
+                filter.inner_value.unwrap_or(filter.value),
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+                variables.clone(),
+            ))),
+            "remove_attribute" => Some(HtmlBodyVisitor::RemoveAttribute(BodyRemoveAttribute::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.attribute.filter(|attribute| !attribute.is_empty())?,
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+            ))),
+            "wrap" => Some(HtmlBodyVisitor::Wrap(BodyWrap::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.value.clone(),
+                filter.inner_value.unwrap_or(filter.value),
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+                variables.clone(),
+            ))),
+            "insert_before" => Some(HtmlBodyVisitor::InsertBefore(BodyInsertBefore::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.value.clone(),
+                filter.inner_value.unwrap_or(filter.value),
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+                variables.clone(),
+            ))),
+            "insert_after" => Some(HtmlBodyVisitor::InsertAfter(BodyInsertAfter::new(
+                filter.element_tree,
+                filter.css_selector,
+                filter.value.clone(),
+                filter.inner_value.unwrap_or(filter.value),
+                filter.id,
+                filter.target_hash,
+                unit_trace,
+                variables.clone(),
+            ))),
             _ => None,
         }
     }
@@ -92,9 +194,32 @@ impl HtmlBodyVisitor {
                     .element_content_handlers
                     .push((Cow::Owned(selector), ElementContentHandlers::default().element(replace)));
             }
+            HtmlBodyVisitor::Remove(remove) => Self::push_element_handler(settings, remove.css_selector(), remove),
+            HtmlBodyVisitor::SetAttribute(set_attribute) => {
+                Self::push_element_handler(settings, set_attribute.css_selector(), set_attribute)
+            }
+            HtmlBodyVisitor::RemoveAttribute(remove_attribute) => {
+                Self::push_element_handler(settings, remove_attribute.css_selector(), remove_attribute)
+            }
+            HtmlBodyVisitor::Wrap(wrap) => Self::push_element_handler(settings, wrap.css_selector(), wrap),
+            HtmlBodyVisitor::InsertBefore(insert_before) => {
+                Self::push_element_handler(settings, insert_before.css_selector(), insert_before)
+            }
+            HtmlBodyVisitor::InsertAfter(insert_after) => Self::push_element_handler(settings, insert_after.css_selector(), insert_after),
             HtmlBodyVisitor::Capture(capture) => {
                 capture.into_handlers(settings);
             }
         }
     }
+
+    fn push_element_handler<'h>(settings: &mut Settings<'h, '_>, css_selector: String, handler: impl IntoHandler<ElementHandler<'h>>) {
+        let Ok(selector) = css_selector.parse() else {
+            log::error!("Failed to parse CSS selector: {css_selector}");
+            return;
+        };
+
+        settings
+            .element_content_handlers
+            .push((Cow::Owned(selector), ElementContentHandlers::default().element(handler)));
+    }
 }
@@ -32,12 +32,7 @@ impl HtmlFilterBodyAction {
     }
 
     pub fn filter(&mut self, input: Vec<u8>) -> Result<Vec<u8>> {
-        match self.rewriter.write(&input) {
-            Ok(_) => {}
-            Err(err) => {
-                tracing::error!("Error while parsing html: {}", err);
-            }
-        }
+        self.rewriter.write(&input)?;
 
         let output = RefCell::new(vec![]);
         self.output.swap(&output);
@@ -45,14 +40,9 @@ impl HtmlFilterBodyAction {
         Ok(output.take())
     }
 
-    pub fn end(self) -> Vec<u8> {
-        match self.rewriter.end() {
-            Ok(_) => {}
-            Err(err) => {
-                tracing::error!("Error while ending html rewriter: {}", err);
-            }
-        }
+    pub fn end(self) -> Result<Vec<u8>> {
+        self.rewriter.end()?;
 
-        self.output.take()
+        Ok(self.output.take())
     }
 }
@@ -13,6 +13,11 @@ mod text_filter_body;
 pub use buffer::Buffer;
 #[cfg(feature = "compress")]
 pub use encoding::SupportedEncoding;
+pub use error::FilterBodyError;
 pub use filter_body::FilterBodyAction;
 pub use filter_header::FilterHeaderAction;
+#[cfg(feature = "router")]
+pub(crate) use header_action::create_header_action;
+#[cfg(feature = "router")]
+pub(crate) use html_body_action::HtmlBodyVisitor;
 pub use html_filter_body::HtmlFilterBodyAction;
@@ -1,13 +1,26 @@
 use std::{cell::RefCell, rc::Rc};
 
+use regex::bytes::Regex;
+
 use crate::action::UnitTrace;
 
+/// Number of bytes kept between two chunks when replacing a pattern, a match cannot be longer than this
+/// to be found across chunk boundaries
+const MAX_MATCH_LENGTH: usize = 4096;
+
+/// Number of already flushed bytes kept before the pending ones, so `^`, `$` and `\b` see the character preceding
+/// a chunk, a character is at most 4 bytes
+const CONTEXT_LENGTH: usize = 4;
+
 #[derive(Debug)]
 pub struct TextFilterBodyAction {
     id: Option<String>,
     action: TextFilterAction,
     content: Vec<u8>,
     executed: bool,
+    // Last flushed bytes, only used as context when matching the pending ones
+    context: Vec<u8>,
+    pending: Vec<u8>,
 }
 
 #[derive(Debug)]
@@ -15,6 +28,10 @@ pub enum TextFilterAction {
     Append,
     Prepend,
     Replace,
+    /// Replace every match of the regex, `$1` or `${name}` in the content are expanded with the captured groups
+    ReplaceRegex(Regex),
+    /// Replace every occurrence of the string, the regex is the escaped string
+    ReplaceString(Regex),
 }
 
 impl TextFilterBodyAction {
@@ -24,6 +41,8 @@ impl TextFilterBodyAction {
             action,
             content: content.into_bytes(),
             executed: false,
+            context: Vec::new(),
+            pending: Vec::new(),
         }
     }
 
@@ -75,10 +94,92 @@ impl TextFilterBodyAction {
                     content
                 }
             }
+            TextFilterAction::ReplaceRegex(_) | TextFilterAction::ReplaceString(_) => {
+                if let Some(trace) = unit_trace
+                    && let Some(id) = self.id.clone()
+                {
+                    // We always use "body" as target since it's not
+                    // possible to change the value in the UI
+                    trace.borrow_mut().add_unit_id_with_target("text", id.as_str());
+                }
+
+                self.replace(data, false)
+            }
         }
     }
 
-    pub fn end(self) -> Vec<u8> {
-        if self.executed { Vec::new() } else { self.content.clone() }
+    pub fn end(mut self) -> Vec<u8> {
+        match self.action {
+            TextFilterAction::ReplaceRegex(_) | TextFilterAction::ReplaceString(_) => self.replace(Vec::new(), true),
+            _ => {
+                if self.executed {
+                    Vec::new()
+                } else {
+                    self.content.clone()
+                }
+            }
+        }
+    }
+
+    fn replace(&mut self, data: Vec<u8>, is_end: bool) -> Vec<u8> {
+        let (regex, expand) = match &self.action {
+            TextFilterAction::ReplaceRegex(regex) => (regex, true),
+            TextFilterAction::ReplaceString(regex) => (regex, false),
+            _ => return data,
+        };
+
+        // The buffer starts with the context, which is never replaced nor flushed again
+        let start = self.context.len();
+        let mut buffer = std::mem::take(&mut self.context);
+        buffer.extend(std::mem::take(&mut self.pending));
+        buffer.extend(data);
+
+        // Keep the end of the buffer for the next chunk, as a match may start there and continue in the next chunk
+        let cut = if is_end {
+            buffer.len()
+        } else {
+            start.max(buffer.len().saturating_sub(MAX_MATCH_LENGTH))
+        };
+
+        let mut output = Vec::with_capacity(buffer.len() - start);
+        let mut last = start;
+        let mut position = start;
+
+        while position <= buffer.len() {
+            let Some(captures) = regex.captures_at(&buffer, position) else {
+                break;
+            };
+            let Some(matched) = captures.get(0) else {
+                break;
+            };
+
+            if matched.start() >= cut {
+                break;
+            }
+
+            // Like the iterator of the regex, an empty match right after the previous match is skipped
+            if matched.is_empty() && matched.start() == last && last > start {
+                position = matched.end() + 1;
+                continue;
+            }
+
+            output.extend_from_slice(&buffer[last..matched.start()]);
+
+            if expand {
+                captures.expand(&self.content, &mut output);
+            } else {
+                output.extend_from_slice(&self.content);
+            }
+
+            last = matched.end();
+            position = if matched.is_empty() { matched.end() + 1 } else { matched.end() };
+        }
+
+        let flushed = last.max(cut);
+        output.extend_from_slice(&buffer[last..flushed]);
+        self.pending = buffer.split_off(flushed);
+        self.context = buffer.split_off(buffer.len().saturating_sub(CONTEXT_LENGTH));
+
+        output
     }
 }
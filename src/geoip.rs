@@ -0,0 +1,120 @@
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+    net::IpAddr,
+    sync::Arc,
+};
+
+use maxminddb::{MaxMindDBError, Reader};
+use serde::{Deserialize, Serialize};
+
+/// A MaxMind format (`.mmdb`) database, it may be a country, city or asn database
+pub struct GeoIpDatabase {
+    reader: Reader<Vec<u8>>,
+}
+
+/// Database shared by the routers created with the same config
+///
+/// It is loaded from the path of the config, so it is ignored when comparing, hashing or printing the config
+#[derive(Clone, Default)]
+pub struct SharedGeoIpDatabase(Option<Arc<GeoIpDatabase>>);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct GeoIpLocation {
+    /// ISO 3166-1 alpha-2 country code, e.g. `FR`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub country: Option<String>,
+    /// Continent code, e.g. `EU`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub continent: Option<String>,
+    /// Autonomous system number
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub asn: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct GeoIpRecord {
+    country: Option<GeoIpCountryRecord>,
+    continent: Option<GeoIpContinentRecord>,
+    autonomous_system_number: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct GeoIpCountryRecord {
+    iso_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GeoIpContinentRecord {
+    code: Option<String>,
+}
+
+impl Debug for GeoIpDatabase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeoIpDatabase")
+            .field("database_type", &self.reader.metadata.database_type)
+            .finish()
+    }
+}
+
+impl SharedGeoIpDatabase {
+    pub fn new(database: GeoIpDatabase) -> Self {
+        Self(Some(Arc::new(database)))
+    }
+
+    pub fn get(&self) -> Option<&GeoIpDatabase> {
+        self.0.as_deref()
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl PartialEq for SharedGeoIpDatabase {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Hash for SharedGeoIpDatabase {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl Debug for SharedGeoIpDatabase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedGeoIpDatabase")
+    }
+}
+
+impl GeoIpDatabase {
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, MaxMindDBError> {
+        Ok(Self {
+            reader: Reader::from_source(bytes)?,
+        })
+    }
+
+    pub fn open(path: &str) -> Result<Self, MaxMindDBError> {
+        Ok(Self {
+            reader: Reader::open_readfile(path)?,
+        })
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<GeoIpLocation> {
+        let record: GeoIpRecord = match self.reader.lookup(ip) {
+            Ok(record) => record,
+            Err(MaxMindDBError::AddressNotFoundError(_)) => return None,
+            Err(err) => {
+                log::error!("cannot lookup {ip} in geoip database: {err}");
+
+                return None;
+            }
+        };
+
+        Some(GeoIpLocation {
+            country: record.country.and_then(|country| country.iso_code),
+            continent: record.continent.and_then(|continent| continent.code),
+            asn: record.autonomous_system_number,
+        })
+    }
+}
@@ -0,0 +1,146 @@
+struct LanguageRange<'a> {
+    range: &'a str,
+    quality: f32,
+}
+
+// How a language range applies to a language tag, a higher value is a more relevant match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RangeMatch {
+    Wildcard,
+    // The range is more specific than the tag, e.g. `fr-CH` for `fr`
+    Truncated(usize),
+    // The range is equal to or a prefix of the tag, e.g. `fr` for `fr-CH`
+    Direct(usize),
+}
+
+/// Pick the best language of `supported` for an `Accept-Language` header value
+///
+/// Each supported language gets the quality of the most specific range matching it, a range matches the
+/// languages it is a prefix of (`en` matches `en-GB`) and, with a lower priority, the languages that are a
+/// prefix of it (`en-GB` matches `en`). Ties are resolved by the order of the ranges in the header, then by
+/// the order of the supported languages.
+pub fn negotiate_language<'a>(accept_language: &str, supported: &'a [String]) -> Option<&'a str> {
+    let ranges = parse_accept_language(accept_language);
+    let mut best: Option<(f32, usize, &'a str)> = None;
+
+    for language in supported {
+        let Some((quality, position)) = language_quality(&ranges, language) else {
+            continue;
+        };
+
+        if quality <= 0.0 {
+            continue;
+        }
+
+        let is_better = match best {
+            None => true,
+            Some((best_quality, best_position, _)) => quality > best_quality || (quality == best_quality && position < best_position),
+        };
+
+        if is_better {
+            best = Some((quality, position, language.as_str()));
+        }
+    }
+
+    best.map(|(_, _, language)| language)
+}
+
+fn parse_accept_language(accept_language: &str) -> Vec<LanguageRange<'_>> {
+    let mut ranges = Vec::new();
+
+    for item in accept_language.split(',') {
+        let mut parts = item.split(';');
+        let range = parts.next().unwrap_or_default().trim();
+
+        if range.is_empty() {
+            continue;
+        }
+
+        let mut quality = 1.0;
+
+        for param in parts {
+            if let Some((name, value)) = param.split_once('=')
+                && name.trim().eq_ignore_ascii_case("q")
+            {
+                quality = value.trim().parse::<f32>().unwrap_or(0.0).clamp(0.0, 1.0);
+            }
+        }
+
+        ranges.push(LanguageRange { range, quality });
+    }
+
+    ranges
+}
+
+fn language_quality(ranges: &[LanguageRange], language: &str) -> Option<(f32, usize)> {
+    ranges
+        .iter()
+        .enumerate()
+        .filter_map(|(position, range)| Some((range_match(range.range, language)?, position, range.quality)))
+        .max_by(|(a, a_position, _), (b, b_position, _)| a.cmp(b).then(b_position.cmp(a_position)))
+        .map(|(_, position, quality)| (quality, position))
+}
+
+fn range_match(range: &str, language: &str) -> Option<RangeMatch> {
+    if range == "*" {
+        return Some(RangeMatch::Wildcard);
+    }
+
+    if is_prefix_tag(range, language) {
+        return Some(RangeMatch::Direct(range.len()));
+    }
+
+    if is_prefix_tag(language, range) {
+        return Some(RangeMatch::Truncated(language.len()));
+    }
+
+    None
+}
+
+fn is_prefix_tag(prefix: &str, tag: &str) -> bool {
+    if prefix.len() > tag.len() || !tag.is_char_boundary(prefix.len()) {
+        return false;
+    }
+
+    let (head, tail) = tag.split_at(prefix.len());
+
+    head.eq_ignore_ascii_case(prefix) && (tail.is_empty() || tail.starts_with('-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supported(languages: &[&str]) -> Vec<String> {
+        languages.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_negotiate_quality() {
+        let languages = supported(&["en", "fr", "de"]);
+
+        assert_eq!(negotiate_language("fr;q=0.8, de;q=0.9", &languages), Some("de"));
+        assert_eq!(negotiate_language("fr, de", &languages), Some("fr"));
+        assert_eq!(negotiate_language("es", &languages), None);
+        assert_eq!(negotiate_language("", &languages), None);
+    }
+
+    #[test]
+    fn test_negotiate_ranges() {
+        let languages = supported(&["en-US", "fr", "pt-BR"]);
+
+        assert_eq!(negotiate_language("fr-CH, en;q=0.5", &languages), Some("fr"));
+        assert_eq!(negotiate_language("en;q=0.9, pt-br;q=0.5", &languages), Some("en-US"));
+        assert_eq!(negotiate_language("pt", &languages), Some("pt-BR"));
+        assert_eq!(negotiate_language("frx", &languages), None);
+    }
+
+    #[test]
+    fn test_negotiate_wildcard_and_refused() {
+        let languages = supported(&["en", "fr"]);
+
+        assert_eq!(negotiate_language("*", &languages), Some("en"));
+        assert_eq!(negotiate_language("en;q=0, *;q=0.1", &languages), Some("fr"));
+        assert_eq!(negotiate_language("fr;q=0, en;q=0", &languages), None);
+    }
+}
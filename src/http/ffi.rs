@@ -4,6 +4,8 @@ use serde_json::{from_str as json_decode, to_string as json_encode};
 use trusted_proxies::{Config, Trusted};
 
 use crate::{
+    Error,
+    error::ffi::set_last_error,
     ffi_helpers::{c_char_to_str, string_to_c_char},
     http::{Addr, Header, PathAndQueryWithSkipped, Request},
     router_config::RouterConfig,
@@ -69,8 +71,8 @@ pub extern "C" fn redirectionio_request_json_deserialize(str: *mut c_char) -> *c
     };
 
     let request = match json_decode(request_str) {
-        Err(err) => {
-            log::error!("cannot deserialize request {err} for string {request_str}");
+        Err(source) => {
+            set_last_error(Error::Decode { input: "request", source });
 
             return null();
         }
@@ -222,8 +224,8 @@ pub extern "C" fn redirectionio_request_from_str(_url: *const c_char) -> *const
     let url = c_char_to_str(_url).unwrap_or("/");
 
     match url.parse::<Request>() {
-        Err(err) => {
-            log::error!("cannot create request for url {url}: {err}");
+        Err(error) => {
+            set_last_error(error);
 
             null()
         }
@@ -1,3 +1,4 @@
+mod accept_language;
 mod addr;
 mod header;
 mod query;
@@ -6,6 +7,7 @@ mod request;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod ffi;
 
+pub use accept_language::negotiate_language;
 pub use addr::Addr;
 pub use header::Header;
 pub use query::{PathAndQueryWithSkipped, sanitize_url};
@@ -1,16 +1,16 @@
 use std::{collections::BTreeMap, net::IpAddr, str::FromStr};
 
 use chrono::{DateTime, Utc};
-#[cfg(feature = "router")]
-use http::Error;
 use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 use serde::{Deserialize, Serialize};
 use trusted_proxies::RequestInformation;
 use url::form_urlencoded::parse as parse_query;
 
-use super::{header::Header, query::PathAndQueryWithSkipped};
+use super::{accept_language::negotiate_language, header::Header, query::PathAndQueryWithSkipped};
+use crate::Error;
 #[cfg(feature = "router")]
 use crate::api::Example;
+use crate::geoip::GeoIpLocation;
 #[cfg(feature = "router")]
 use crate::http::sanitize_url;
 #[cfg(feature = "router")]
@@ -31,10 +31,13 @@ pub struct Request {
     pub remote_addr: Option<IpAddr>,
     pub created_at: Option<DateTime<Utc>>,
     pub sampling_override: Option<bool>,
+    /// Location of the remote address, resolved when the router config has a geoip database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_location: Option<GeoIpLocation>,
 }
 
 impl FromStr for Request {
-    type Err = http::Error;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let http_request = http::Request::<()>::builder().uri(s).method("GET").body(())?;
@@ -75,6 +78,7 @@ impl Request {
             remote_addr,
             created_at: Some(Utc::now()),
             sampling_override,
+            geo_location: None,
         }
     }
 
@@ -94,6 +98,7 @@ impl Request {
             host: host.map(|s| if config.ignore_host_case { s.to_lowercase() } else { s }),
             scheme,
             method,
+            geo_location: Self::lookup_geo_location(config, remote_addr),
             remote_addr,
             headers: Vec::new(),
             created_at: Some(Utc::now()),
@@ -128,11 +133,15 @@ impl Request {
         }
 
         if let Some(ip) = &example.ip_address {
-            request.remote_addr = Some(IpAddr::from_str(ip).unwrap());
+            request.remote_addr = Some(IpAddr::from_str(ip).map_err(|source| Error::InvalidIpAddress {
+                value: ip.to_string(),
+                source,
+            })?);
+            request.geo_location = Self::lookup_geo_location(router_config, request.remote_addr);
         }
 
         if let Some(datetime) = &example.datetime {
-            request.set_created_at(Some(datetime.to_string()));
+            request.set_created_at(Some(datetime.to_string()))?;
         }
 
         Ok(request)
@@ -178,9 +187,15 @@ impl Request {
             remote_addr: request.remote_addr,
             created_at: request.created_at,
             sampling_override: request.sampling_override,
+            geo_location: Self::lookup_geo_location(config, request.remote_addr).or_else(|| request.geo_location.clone()),
         }
     }
 
+    #[cfg(feature = "router")]
+    fn lookup_geo_location(config: &RouterConfig, remote_addr: Option<IpAddr>) -> Option<GeoIpLocation> {
+        config.geoip_database()?.lookup(remote_addr?)
+    }
+
     pub fn add_header(&mut self, name: String, value: String, ignore_case: bool) {
         self.headers.push(Header {
             name,
@@ -188,16 +203,17 @@ impl Request {
         });
     }
 
-    pub fn set_created_at(&mut self, created_at: Option<String>) {
-        match created_at {
-            None => (),
-            Some(created_at) => match created_at.parse::<DateTime<Utc>>() {
+    pub fn set_created_at(&mut self, created_at: Option<String>) -> Result<(), Error> {
+        if let Some(created_at) = created_at {
+            match created_at.parse::<DateTime<Utc>>() {
                 Ok(dt) => self.created_at = Some(dt),
-                Err(err) => {
-                    log::error!("cannot parse datetime {created_at}: {err}");
+                Err(source) => {
+                    return Err(Error::InvalidDateTime { value: created_at, source });
                 }
-            },
-        };
+            }
+        }
+
+        Ok(())
     }
 
     pub fn method(&self) -> &str {
@@ -256,6 +272,56 @@ impl Request {
         if values.is_empty() { None } else { Some(values.join(",")) }
     }
 
+    /// Best language of `supported` for the `Accept-Language` headers of this request
+    pub fn preferred_language(&self, supported: &[String]) -> Option<String> {
+        let accept_language = self.header_value("Accept-Language")?;
+
+        negotiate_language(accept_language.as_str(), supported).map(str::to_string)
+    }
+
+    /// Decoded values of a query parameter, taken from the original url so parameters skipped for matching
+    /// (like marketing ones) are still available
+    pub fn query_param_values(&self, name: &str, ignore_case: bool) -> Vec<String> {
+        let Some((_, query)) = self.path_and_query_skipped.original.split_once('?') else {
+            return Vec::new();
+        };
+
+        parse_query(query.as_bytes())
+            .filter(|(key, _)| {
+                if ignore_case {
+                    key.to_lowercase() == name.to_lowercase()
+                } else {
+                    key == name
+                }
+            })
+            .map(|(_, value)| if ignore_case { value.to_lowercase() } else { value.into_owned() })
+            .collect()
+    }
+
+    /// Values of a cookie sent in the `Cookie` headers, cookie names are case sensitive
+    pub fn cookie_values(&self, name: &str, ignore_case: bool) -> Vec<String> {
+        let mut values = Vec::new();
+
+        for header in self.header_values("cookie") {
+            for pair in header.split(';') {
+                let Some((cookie_name, value)) = pair.trim().split_once('=') else {
+                    continue;
+                };
+
+                if cookie_name.trim() != name {
+                    continue;
+                }
+
+                let value = value.trim();
+                let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+
+                values.push(if ignore_case { value.to_lowercase() } else { value.to_string() });
+            }
+        }
+
+        values
+    }
+
     pub fn path_and_query(&self) -> String {
         match &self.path_and_query_skipped.path_and_query_matching {
             None => self.path_and_query_skipped.path_and_query.clone(),
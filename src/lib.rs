@@ -3,6 +3,9 @@ This crate provides a library for matching, handling and logging http requests w
 rule format.
 */
 
+// Shared matchers nest deeply, proving the router is `Send` and `Sync` needs more than the default limit
+#![recursion_limit = "256"]
+
 pub mod action;
 pub mod api;
 pub mod filter;
@@ -17,12 +20,19 @@ pub mod router;
 mod callback_log;
 #[cfg(feature = "dot")]
 mod dot;
+mod error;
 #[cfg(not(target_arch = "wasm32"))]
 mod ffi_helpers;
+mod geoip;
+mod rate_limit;
 mod regex;
 mod router_config;
+mod stable_hash;
 #[cfg(feature = "wasmbind")]
 #[cfg(target_arch = "wasm32")]
 mod wasm_api;
 
+pub use error::{Error, ErrorCode};
+pub use geoip::{GeoIpDatabase, GeoIpLocation, SharedGeoIpDatabase};
+pub use rate_limit::{CounterStore, MemoryCounterStore, RateLimiter};
 pub use router_config::RouterConfig;
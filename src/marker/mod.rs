@@ -1,12 +1,16 @@
 mod transformer;
 
 use std::{
+    cmp::Reverse,
     collections::HashMap,
     sync::{Arc, RwLock},
 };
 
 use serde::{Deserialize, Serialize};
-pub use transformer::{Camelize, Dasherize, Lowercase, Replace, Slice, Transform, Underscorize, Uppercase};
+pub use transformer::{
+    Camelize, Dasherize, DefaultValue, Lowercase, RegexReplace, Replace, ShortHash, Slice, Slugify, Transform, Trim, Underscorize,
+    Uppercase, UrlDecode, UrlEncode,
+};
 
 use crate::{api::VariableValue, regex::LazyRegex};
 
@@ -16,13 +20,14 @@ pub struct Marker {
     regex: String,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StaticOrDynamic {
     Static(String),
     Dynamic(MarkerString),
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(from = "MarkerStringDefinition")]
 pub struct MarkerString {
     pub regex: String,
     pub capture: String,
@@ -32,6 +37,30 @@ pub struct MarkerString {
     regex_capture: Arc<RwLock<LazyRegex>>,
 }
 
+// The capture regex is not serialized, it is rebuilt from the capture pattern
+#[derive(Deserialize)]
+struct MarkerStringDefinition {
+    regex: String,
+    capture: String,
+    ignore_case: bool,
+    markers: HashMap<String, String>,
+}
+
+impl From<MarkerStringDefinition> for MarkerString {
+    fn from(definition: MarkerStringDefinition) -> Self {
+        MarkerString {
+            regex_capture: Arc::new(RwLock::new(LazyRegex::new_leaf(
+                definition.capture.as_str(),
+                definition.ignore_case,
+            ))),
+            regex: definition.regex,
+            capture: definition.capture,
+            ignore_case: definition.ignore_case,
+            markers: definition.markers,
+        }
+    }
+}
+
 impl Marker {
     pub fn new(name: String, regex: String) -> Marker {
         Marker { name, regex }
@@ -50,7 +79,7 @@ impl MarkerString {
         let mut marker_map = HashMap::new();
 
         // Sort markers by length
-        markers.sort_by(|a, b| b.name.len().cmp(&a.name.len()));
+        markers.sort_by_key(|marker| Reverse(marker.name.len()));
 
         // Foreach marker replace
         for marker in &markers {
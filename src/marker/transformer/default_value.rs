@@ -0,0 +1,17 @@
+use crate::marker::Transform;
+
+pub struct DefaultValue {
+    value: String,
+}
+
+impl Transform for DefaultValue {
+    fn transform(&self, str: String) -> String {
+        if str.is_empty() { self.value.clone() } else { str }
+    }
+}
+
+impl DefaultValue {
+    pub fn new(value: String) -> DefaultValue {
+        DefaultValue { value }
+    }
+}
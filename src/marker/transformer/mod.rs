@@ -1,18 +1,32 @@
 mod camelize;
 mod dasherize;
+mod default_value;
 mod lowercase;
+mod regex_replace;
 mod replace;
+mod short_hash;
 mod slice;
+mod slugify;
+mod trim;
 mod underscorize;
 mod uppercase;
+mod url_decode;
+mod url_encode;
 
 pub use camelize::Camelize;
 pub use dasherize::Dasherize;
+pub use default_value::DefaultValue;
 pub use lowercase::Lowercase;
+pub use regex_replace::RegexReplace;
 pub use replace::Replace;
+pub use short_hash::ShortHash;
 pub use slice::Slice;
+pub use slugify::Slugify;
+pub use trim::Trim;
 pub use underscorize::Underscorize;
 pub use uppercase::Uppercase;
+pub use url_decode::UrlDecode;
+pub use url_encode::UrlEncode;
 
 pub trait Transform {
     fn transform(&self, str: String) -> String;
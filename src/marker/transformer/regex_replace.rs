@@ -0,0 +1,21 @@
+use regex::Regex;
+
+use crate::marker::Transform;
+
+pub struct RegexReplace {
+    pattern: Regex,
+    with: String,
+}
+
+impl Transform for RegexReplace {
+    // `with` may reference capture groups of the pattern, like `$1` or `${name}`
+    fn transform(&self, str: String) -> String {
+        self.pattern.replace_all(str.as_str(), self.with.as_str()).to_string()
+    }
+}
+
+impl RegexReplace {
+    pub fn new(pattern: Regex, with: String) -> RegexReplace {
+        RegexReplace { pattern, with }
+    }
+}
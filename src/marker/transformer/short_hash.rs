@@ -0,0 +1,42 @@
+use crate::marker::Transform;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Hexadecimal FNV-1a hash of the value, it does not depend on the platform or the rust version so it can be
+/// used in urls
+pub struct ShortHash {
+    length: usize,
+}
+
+impl Transform for ShortHash {
+    fn transform(&self, str: String) -> String {
+        let mut hash = FNV_OFFSET_BASIS;
+
+        for byte in str.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+
+        let mut hex = format!("{hash:016x}");
+        hex.truncate(self.length);
+
+        hex
+    }
+}
+
+impl ShortHash {
+    pub const MAX_LENGTH: usize = 16;
+
+    pub fn new(length: usize) -> ShortHash {
+        ShortHash {
+            length: length.clamp(1, Self::MAX_LENGTH),
+        }
+    }
+}
+
+impl Default for ShortHash {
+    fn default() -> Self {
+        ShortHash::new(8)
+    }
+}
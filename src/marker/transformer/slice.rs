@@ -6,19 +6,15 @@ pub struct Slice {
 }
 
 impl Transform for Slice {
+    // Indexes are in chars, not bytes, so multi-byte characters are never split
     fn transform(&self, str: String) -> String {
-        let from = self.from;
-        let mut to = self.to.unwrap_or(str.len());
+        let to = self.to.unwrap_or(usize::MAX);
 
-        if from > str.len() {
+        if self.from >= to {
             return "".to_string();
         }
 
-        if to > str.len() {
-            to = str.len();
-        }
-
-        str[from..to].to_string()
+        str.chars().skip(self.from).take(to - self.from).collect()
     }
 }
 
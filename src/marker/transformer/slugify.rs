@@ -0,0 +1,30 @@
+use deunicode::deunicode;
+
+use crate::marker::Transform;
+
+pub struct Slugify {
+    separator: String,
+}
+
+impl Transform for Slugify {
+    fn transform(&self, str: String) -> String {
+        deunicode(str.as_str())
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(self.separator.as_str())
+    }
+}
+
+impl Slugify {
+    pub fn new(separator: String) -> Slugify {
+        Slugify { separator }
+    }
+}
+
+impl Default for Slugify {
+    fn default() -> Self {
+        Slugify::new("-".to_string())
+    }
+}
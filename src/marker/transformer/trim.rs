@@ -0,0 +1,23 @@
+use crate::marker::Transform;
+
+#[derive(Default)]
+pub struct Trim {
+    characters: Option<Vec<char>>,
+}
+
+impl Transform for Trim {
+    fn transform(&self, str: String) -> String {
+        match &self.characters {
+            None => str.trim().to_string(),
+            Some(characters) => str.trim_matches(characters.as_slice()).to_string(),
+        }
+    }
+}
+
+impl Trim {
+    pub fn new(characters: Option<String>) -> Trim {
+        Trim {
+            characters: characters.map(|characters| characters.chars().collect()),
+        }
+    }
+}
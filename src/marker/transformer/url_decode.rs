@@ -0,0 +1,12 @@
+use percent_encoding::percent_decode_str;
+
+use crate::marker::Transform;
+
+#[derive(Default)]
+pub struct UrlDecode;
+
+impl Transform for UrlDecode {
+    fn transform(&self, str: String) -> String {
+        percent_decode_str(str.as_str()).decode_utf8_lossy().to_string()
+    }
+}
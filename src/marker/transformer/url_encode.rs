@@ -0,0 +1,15 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::marker::Transform;
+
+// Unreserved characters of RFC 3986 are kept as is
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+#[derive(Default)]
+pub struct UrlEncode;
+
+impl Transform for UrlEncode {
+    fn transform(&self, str: String) -> String {
+        utf8_percent_encode(str.as_str(), URL_ENCODE_SET).to_string()
+    }
+}
@@ -0,0 +1,17 @@
+use crate::rate_limit::RateLimiter;
+
+/// Create a rate limiter keeping its counters in memory, it can be shared between threads
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_rate_limiter_create() -> *const RateLimiter {
+    Box::into_raw(Box::<RateLimiter>::default())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_rate_limiter_drop(_rate_limiter: *mut RateLimiter) {
+    if _rate_limiter.is_null() {
+        return;
+    }
+
+    // SAFETY: _rate_limiter is a valid pointer to a RateLimiter
+    drop(unsafe { Box::from_raw(_rate_limiter) });
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::rate_limit::CounterStore;
+
+const DEFAULT_CAPACITY: usize = 100_000;
+
+/// Counters kept in the memory of the process, each instance of a proxy has its own counters
+///
+/// The number of counters is bounded by the capacity of the store: once full, the counter whose window ends first is
+/// evicted to make room for a new key, so many distinct keys cannot prevent other requests from being counted
+#[derive(Debug)]
+pub struct MemoryCounterStore {
+    capacity: usize,
+    counters: Mutex<MemoryCounters>,
+}
+
+#[derive(Debug)]
+struct MemoryCounters {
+    counters: HashMap<String, MemoryCounter>,
+    // No counter expires before this timestamp, so counters of past windows are dropped at most once per window
+    next_sweep: i64,
+}
+
+#[derive(Debug)]
+struct MemoryCounter {
+    // Timestamp at which the window of the counter ends
+    window_end: i64,
+    count: u64,
+}
+
+impl Default for MemoryCounterStore {
+    fn default() -> Self {
+        MemoryCounterStore::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoryCounterStore {
+    /// Create a store keeping at most `capacity` counters
+    pub fn with_capacity(capacity: usize) -> Self {
+        MemoryCounterStore {
+            capacity,
+            counters: Mutex::new(MemoryCounters {
+                counters: HashMap::new(),
+                next_sweep: i64::MAX,
+            }),
+        }
+    }
+}
+
+impl MemoryCounters {
+    fn sweep(&mut self, timestamp: i64) {
+        self.counters.retain(|_, counter| counter.window_end > timestamp);
+        self.next_sweep = self.counters.values().map(|counter| counter.window_end).min().unwrap_or(i64::MAX);
+    }
+
+    // Remove the counter whose window ends first, it is the one that would have been dropped next
+    fn evict(&mut self) {
+        let evicted = self
+            .counters
+            .iter()
+            .min_by_key(|(_, counter)| counter.window_end)
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = evicted {
+            self.counters.remove(&key);
+        }
+    }
+}
+
+impl CounterStore for MemoryCounterStore {
+    fn increment(&self, key: &str, window: Duration, now: DateTime<Utc>) -> u64 {
+        let window_seconds = window.as_secs().max(1) as i64;
+        let timestamp = now.timestamp();
+        let window_end = (timestamp.div_euclid(window_seconds) + 1) * window_seconds;
+
+        let mut counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if timestamp >= counters.next_sweep {
+            counters.sweep(timestamp);
+        }
+
+        if !counters.counters.contains_key(key) && counters.counters.len() >= self.capacity {
+            if self.capacity == 0 {
+                return 0;
+            }
+
+            counters.evict();
+        }
+
+        counters.next_sweep = counters.next_sweep.min(window_end);
+
+        let counter = counters
+            .counters
+            .entry(key.to_string())
+            .or_insert(MemoryCounter { window_end, count: 0 });
+
+        if counter.window_end != window_end {
+            counter.window_end = window_end;
+            counter.count = 0;
+        }
+
+        counter.count += 1;
+        counter.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    impl MemoryCounterStore {
+        fn store_len(&self) -> usize {
+            self.counters.lock().unwrap().counters.len()
+        }
+    }
+
+    #[test]
+    fn test_counter_resets_on_new_window() {
+        let store = MemoryCounterStore::default();
+        let window = Duration::from_secs(60);
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+
+        assert_eq!(store.increment("a", window, start), 1);
+        assert_eq!(store.increment("a", window, start + chrono::Duration::seconds(59)), 2);
+        assert_eq!(store.increment("b", window, start), 1);
+        assert_eq!(store.increment("a", window, start + chrono::Duration::seconds(60)), 1);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let store = MemoryCounterStore::with_capacity(2);
+        let window = Duration::from_secs(60);
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+
+        assert_eq!(store.increment("a", window, start), 1);
+        assert_eq!(store.increment("b", window, start), 1);
+        assert_eq!(store.increment("a", window, start), 2);
+
+        // Counters of the past window are dropped, which makes room for new keys
+        assert_eq!(store.increment("c", window, start + chrono::Duration::seconds(60)), 1);
+        assert_eq!(store.increment("a", window, start + chrono::Duration::seconds(60)), 1);
+        assert_eq!(store.store_len(), 2);
+    }
+
+    #[test]
+    fn test_full_store_evicts_counter_ending_first() {
+        let store = MemoryCounterStore::with_capacity(2);
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+
+        assert_eq!(store.increment("hour", Duration::from_secs(3600), start), 1);
+        assert_eq!(store.increment("minute", Duration::from_secs(60), start), 1);
+
+        // A new key is still counted, the counter of the minute window is evicted as it ends first
+        assert_eq!(store.increment("new", Duration::from_secs(3600), start), 1);
+        assert_eq!(store.increment("new", Duration::from_secs(3600), start), 2);
+        assert_eq!(store.increment("hour", Duration::from_secs(3600), start), 2);
+        assert_eq!(store.increment("minute", Duration::from_secs(60), start), 1);
+        assert_eq!(store.store_len(), 2);
+    }
+
+    #[test]
+    fn test_sweep_once_per_window() {
+        let store = MemoryCounterStore::default();
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap();
+
+        store.increment("a", Duration::from_secs(60), start);
+        store.increment("b", Duration::from_secs(3600), start);
+
+        assert_eq!(store.counters.lock().unwrap().next_sweep, start.timestamp() + 60);
+
+        store.increment("b", Duration::from_secs(3600), start + chrono::Duration::seconds(60));
+
+        let counters = store.counters.lock().unwrap();
+
+        assert_eq!(counters.counters.len(), 1);
+        assert_eq!(counters.next_sweep, start.timestamp() + 3600);
+    }
+}
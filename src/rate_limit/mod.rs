@@ -0,0 +1,43 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod ffi;
+mod memory_store;
+
+use std::{fmt::Debug, time::Duration};
+
+use chrono::{DateTime, Utc};
+pub use memory_store::MemoryCounterStore;
+
+/// Storage of the request counters used by the rate limits, proxies may implement it to share the counters between
+/// several instances
+pub trait CounterStore: Debug + Send + Sync {
+    /// Increment the counter of the key for the window containing `now` and return its new value, the counter starts
+    /// again from 0 on each new window
+    fn increment(&self, key: &str, window: Duration, now: DateTime<Utc>) -> u64;
+}
+
+/// Count the requests matching rules with a rate limit, see `Action::check_rate_limits`
+#[derive(Debug)]
+pub struct RateLimiter {
+    store: Box<dyn CounterStore>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(Box::<MemoryCounterStore>::default())
+    }
+}
+
+impl RateLimiter {
+    pub fn new(store: Box<dyn CounterStore>) -> Self {
+        RateLimiter { store }
+    }
+
+    /// Count a request for the key and return whether it is over the limit of the current window
+    pub fn hit(&self, key: &str, window: Duration, limit: u64) -> bool {
+        self.hit_at(key, window, limit, Utc::now())
+    }
+
+    pub fn hit_at(&self, key: &str, window: Duration, limit: u64, now: DateTime<Utc>) -> bool {
+        self.store.increment(key, window, now) > limit
+    }
+}
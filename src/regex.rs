@@ -1,13 +1,20 @@
-use std::{fmt::Display, hash::Hash, sync::Arc};
+use std::{
+    fmt::Display,
+    hash::Hash,
+    sync::{Arc, OnceLock},
+};
 
 use regex::{Regex, RegexBuilder};
 use serde::Serialize;
 
+use crate::Error;
+
 #[derive(Debug, Clone)]
 pub struct LazyRegex {
     pub(crate) original: String,
     pub(crate) regex: String,
-    pub(crate) compiled: Option<Arc<Regex>>,
+    /// Compiled once and shared by every clone made after, so caching does not need a mutable access
+    pub(crate) compiled: OnceLock<Arc<Regex>>,
     pub(crate) ignore_case: bool,
 }
 
@@ -54,12 +61,11 @@ impl Display for LazyRegex {
 }
 
 impl LazyRegex {
-    #[cfg(feature = "router")]
     pub fn new(regex: String, ignore_case: bool) -> LazyRegex {
         LazyRegex {
             regex: regex.clone(),
             original: regex,
-            compiled: None,
+            compiled: OnceLock::new(),
             ignore_case,
         }
     }
@@ -73,7 +79,7 @@ impl LazyRegex {
                 ["^", regex.as_str()].join("")
             },
             original: regex,
-            compiled: None,
+            compiled: OnceLock::new(),
             ignore_case,
         }
     }
@@ -82,14 +88,14 @@ impl LazyRegex {
         LazyRegex {
             regex: ["^", regex, "$"].join(""),
             original: regex.to_string(),
-            compiled: None,
+            compiled: OnceLock::new(),
             ignore_case,
         }
     }
 
     #[cfg(feature = "router")]
     pub fn is_match(&self, value: &str) -> bool {
-        match &self.compiled {
+        match self.compiled.get() {
             Some(regex) => regex.is_match(value),
             None => {
                 if self.original.is_empty() {
@@ -105,17 +111,23 @@ impl LazyRegex {
     }
 
     pub fn regex(&self) -> Option<Arc<Regex>> {
-        match &self.compiled {
+        match self.compiled.get() {
             Some(regex) => Some(regex.clone()),
             None => self.create_regex(),
         }
     }
 
+    pub fn try_create_regex(&self) -> Result<Arc<Regex>, Error> {
+        Ok(Arc::new(
+            RegexBuilder::new(self.regex.as_str()).case_insensitive(self.ignore_case).build()?,
+        ))
+    }
+
     pub fn create_regex(&self) -> Option<Arc<Regex>> {
-        match RegexBuilder::new(self.regex.as_str()).case_insensitive(self.ignore_case).build() {
-            Ok(regex) => Some(Arc::new(regex)),
-            Err(e) => {
-                tracing::error!("cannot create regex: {:?}", e);
+        match self.try_create_regex() {
+            Ok(regex) => Some(regex),
+            Err(err) => {
+                log::error!("{err}");
 
                 None
             }
@@ -123,7 +135,11 @@ impl LazyRegex {
     }
 
     pub fn compile(&self) -> Self {
-        let compiled = self.create_regex();
+        let compiled = OnceLock::new();
+
+        if let Some(regex) = self.create_regex() {
+            let _ = compiled.set(regex);
+        }
 
         LazyRegex {
             regex: self.regex.clone(),
@@ -132,4 +148,24 @@ impl LazyRegex {
             ignore_case: self.ignore_case,
         }
     }
+
+    #[cfg(feature = "router")]
+    pub fn is_compiled(&self) -> bool {
+        self.compiled.get().is_some()
+    }
+
+    #[cfg(feature = "router")]
+    /// Compile the regex in place if not already done
+    ///
+    /// Return true when the regex has been compiled by this call
+    pub fn cache(&self) -> bool {
+        if self.is_compiled() {
+            return false;
+        }
+
+        match self.create_regex() {
+            Some(regex) => self.compiled.set(regex).is_ok(),
+            None => false,
+        }
+    }
 }
@@ -26,7 +26,10 @@ where
     }
 }
 
-impl<V> Item<V> {
+impl<V> Item<V>
+where
+    V: Clone,
+{
     /// Insert a new item into this node
     pub fn insert(self, regex: &str, id: String, item: V) -> Item<V> {
         match self {
@@ -72,6 +75,16 @@ impl<V> Item<V> {
         }
     }
 
+    /// Remove an item on this tree, only looking into the items which can contain this regex
+    pub fn remove_by_regex(self, regex: &str, id: &str) -> (Self, Option<V>) {
+        match self {
+            Item::Empty(_) => (self, None),
+            Item::Node(node) => node.remove_by_regex(regex, id),
+            Item::Leaf(leaf) if leaf.regex() == regex => leaf.remove(id),
+            Item::Leaf(_) => (self, None),
+        }
+    }
+
     pub fn retain<F>(self, f: &F) -> Item<V>
     where
         F: Fn(&str, &mut V) -> bool,
@@ -117,18 +130,34 @@ impl<V> Item<V> {
     }
 
     pub fn iter(&self) -> ItemIter<'_, V> {
-        ItemIter {
-            children: std::slice::from_ref(self),
-            parent: None,
-            values: None,
+        match self {
+            Item::Empty(_) => ItemIter::default(),
+            Item::Node(node) => ItemIter {
+                children: node.children.as_slice(),
+                parent: None,
+                values: None,
+            },
+            Item::Leaf(leaf) => ItemIter {
+                children: &[],
+                parent: None,
+                values: Some(leaf.values.values()),
+            },
         }
     }
 
     pub fn iter_mut(&mut self) -> ItemIterMut<'_, V> {
-        ItemIterMut {
-            children: std::slice::from_mut(self),
-            parent: None,
-            values: None,
+        match self {
+            Item::Empty(_) => ItemIterMut::default(),
+            Item::Node(node) => ItemIterMut {
+                children: node.children.as_mut_slice(),
+                parent: None,
+                values: None,
+            },
+            Item::Leaf(leaf) => ItemIterMut {
+                children: &mut [],
+                parent: None,
+                values: Some(leaf.values.values_mut()),
+            },
         }
     }
 
@@ -143,7 +172,7 @@ impl<V> Item<V> {
     /// Level argument allow to build cache on first level of the tree by priority
     /// Implementation must retain at which level this node is build and not do any caching
     /// if we are not on the current level
-    pub fn cache(&mut self, left: u64, cache_level: u64, current_level: u64) -> u64 {
+    pub fn cache(&self, left: u64, cache_level: u64, current_level: u64) -> u64 {
         if left == 0 {
             return left;
         }
@@ -169,7 +198,7 @@ impl<V> Item<V> {
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for Item<V>
 where
-    V: DotBuilder,
+    V: DotBuilder + Clone,
 {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
         match self {
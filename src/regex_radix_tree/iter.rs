@@ -1,15 +1,18 @@
-use std::collections::hash_map::{Values, ValuesMut};
+use std::{
+    collections::hash_map::{Values, ValuesMut},
+    sync::Arc,
+};
 
 use crate::regex_radix_tree::item::Item;
 
 pub struct ItemIter<'a, V> {
-    pub(crate) children: &'a [Item<V>],
+    pub(crate) children: &'a [Arc<Item<V>>],
     pub(crate) parent: Option<Box<ItemIter<'a, V>>>,
     pub(crate) values: Option<Values<'a, String, V>>,
 }
 
 pub struct ItemIterMut<'a, V> {
-    pub(crate) children: &'a mut [Item<V>],
+    pub(crate) children: &'a mut [Arc<Item<V>>],
     pub(crate) parent: Option<Box<ItemIterMut<'a, V>>>,
     pub(crate) values: Option<ValuesMut<'a, String, V>>,
 }
@@ -39,7 +42,7 @@ impl<'a, V> Iterator for ItemIter<'a, V> {
 
     fn next(&mut self) -> Option<Self::Item> {
         match &mut self.values {
-            None => match self.children.first() {
+            None => match self.children.first().map(Arc::as_ref) {
                 None => match self.parent.take() {
                     Some(parent) => {
                         // continue with the parent node
@@ -82,7 +85,10 @@ impl<'a, V> Iterator for ItemIter<'a, V> {
     }
 }
 
-impl<'a, V> Iterator for ItemIterMut<'a, V> {
+impl<'a, V> Iterator for ItemIterMut<'a, V>
+where
+    V: Clone,
+{
     type Item = &'a mut V;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -90,7 +96,8 @@ impl<'a, V> Iterator for ItemIterMut<'a, V> {
             None => {
                 let children = std::mem::take(&mut self.children);
 
-                match children.split_first_mut() {
+                // Iterating mutably copies the items shared with other trees
+                match children.split_first_mut().map(|(first, children)| (Arc::make_mut(first), children)) {
                     None => match self.parent.take() {
                         Some(parent) => {
                             // continue with the parent node
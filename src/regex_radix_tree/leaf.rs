@@ -26,7 +26,10 @@ where
     }
 }
 
-impl<V> Leaf<V> {
+impl<V> Leaf<V>
+where
+    V: Clone,
+{
     pub fn new(regex: &str, id: String, item: V, ignore_case: bool) -> Self {
         let mut values = HashMap::new();
         values.insert(id, item);
@@ -56,7 +59,7 @@ impl<V> Leaf<V> {
 
         Item::Node(Node {
             regex: Arc::new(LazyRegex::new_node(prefix, self.regex.ignore_case)),
-            children: vec![Item::Leaf(self), leaf],
+            children: vec![Arc::new(Item::Leaf(self)), Arc::new(leaf)],
         })
     }
 
@@ -121,7 +124,7 @@ impl<V> Leaf<V> {
     }
 
     pub fn cached_len(&self) -> usize {
-        if self.regex.compiled.is_some() {
+        if self.regex.is_compiled() {
             return 1;
         }
 
@@ -147,15 +150,8 @@ impl<V> Leaf<V> {
     /// Level argument allow to build cache on first level of the tree by priority
     /// Implementation must retain at which level this node is build and not do any caching
     /// if we are not on the current level
-    pub fn cache(&mut self, left: u64) -> u64 {
-        // Already cached
-        if self.regex.compiled.is_some() {
-            return left;
-        }
-
-        self.regex = Arc::new(self.regex.compile());
-
-        if self.regex.compiled.is_some() {
+    pub fn cache(&self, left: u64) -> u64 {
+        if self.regex.cache() {
             return left - 1;
         }
 
@@ -166,7 +162,7 @@ impl<V> Leaf<V> {
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for Leaf<V>
 where
-    V: DotBuilder,
+    V: DotBuilder + Clone,
 {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
         let node_name = format!("leaf_regex_{}", id);
@@ -174,7 +170,7 @@ where
 
         let mut node = GraphNode::new(&node_name).label(self.regex.original.as_str());
 
-        if self.regex.compiled.is_some() {
+        if self.regex.is_compiled() {
             node = node.color(Some("green"));
         } else {
             node = node.color(Some("red"));
@@ -6,5 +6,6 @@ mod prefix;
 mod trace;
 mod tree;
 
+pub(crate) use prefix::common_prefix_char_size;
 pub use trace::Trace;
 pub use tree::{RegexTreeMap, UniqueRegexTreeMap};
@@ -15,7 +15,8 @@ use crate::regex::LazyRegex;
 #[derive(Debug)]
 pub struct Node<V> {
     pub(crate) regex: Arc<LazyRegex>,
-    pub(crate) children: Vec<Item<V>>,
+    /// Children are shared between clones of the tree, only the path to a modified item is copied
+    pub(crate) children: Vec<Arc<Item<V>>>,
 }
 
 impl<V> Clone for Node<V>
@@ -30,7 +31,10 @@ where
     }
 }
 
-impl<V> Node<V> {
+impl<V> Node<V>
+where
+    V: Clone,
+{
     /// Insert a new item into this node
     pub fn insert(mut self, regex: &str, id: String, item: V) -> Item<V> {
         let mut max_prefix_size = self.regex.original.len() as u32;
@@ -43,7 +47,7 @@ impl<V> Node<V> {
 
             return Item::Node(Node {
                 regex: Arc::new(LazyRegex::new_node(prefix, self.regex.ignore_case)),
-                children: vec![left, Item::Node(self)],
+                children: vec![Arc::new(left), Arc::new(Item::Node(self))],
             });
         }
 
@@ -60,12 +64,12 @@ impl<V> Node<V> {
 
         match max_prefix_item {
             Some(child_index) => {
-                let mut children = self.children.remove(child_index);
-                children = children.insert(regex, id, item);
-                self.children.push(children);
+                let children = Arc::unwrap_or_clone(self.children.remove(child_index));
+                self.children.push(Arc::new(children.insert(regex, id, item)));
             }
             None => {
-                self.children.push(Item::Leaf(Leaf::new(regex, id, item, self.regex.ignore_case)));
+                self.children
+                    .push(Arc::new(Item::Leaf(Leaf::new(regex, id, item, self.regex.ignore_case))));
             }
         }
 
@@ -102,7 +106,10 @@ impl<V> Node<V> {
 
         if regex.starts_with(self.regex.original.as_str()) {
             for child in &mut self.children {
-                values.extend(child.get_mut(regex));
+                // Only copy the children which can contain this regex
+                if regex.starts_with(child.regex()) {
+                    values.extend(Arc::make_mut(child).get_mut(regex));
+                }
             }
         }
 
@@ -124,20 +131,20 @@ impl<V> Node<V> {
             if removed.is_some() {
                 children.push(child);
             } else {
-                let (child, value) = child.remove(id);
+                let (child, value) = Arc::unwrap_or_clone(child).remove(id);
 
                 if value.is_some() {
                     removed = value;
                 }
 
                 if !child.is_empty() {
-                    children.push(child);
+                    children.push(Arc::new(child));
                 }
             }
         }
 
         if children.len() == 1 {
-            return (children.pop().unwrap(), removed);
+            return (Arc::unwrap_or_clone(children.pop().unwrap()), removed);
         }
 
         self.children = children;
@@ -145,6 +152,40 @@ impl<V> Node<V> {
         (Item::Node(self), removed)
     }
 
+    /// Remove an item on this tree, only looking into the children which can contain this regex
+    pub fn remove_by_regex(mut self, regex: &str, id: &str) -> (Item<V>, Option<V>) {
+        let mut removed = None;
+
+        if !regex.starts_with(self.regex.original.as_str()) {
+            return (Item::Node(self), removed);
+        }
+
+        for child in &mut self.children {
+            if removed.is_some() || !regex.starts_with(child.regex()) {
+                continue;
+            }
+
+            let child = Arc::make_mut(child);
+            let item = std::mem::replace(child, Item::Empty(self.regex.ignore_case));
+            let (item, value) = item.remove_by_regex(regex, id);
+
+            *child = item;
+            removed = value;
+        }
+
+        if removed.is_none() {
+            return (Item::Node(self), removed);
+        }
+
+        self.children.retain(|child| !child.is_empty());
+
+        if self.children.len() == 1 {
+            return (Arc::unwrap_or_clone(self.children.pop().unwrap()), removed);
+        }
+
+        (Item::Node(self), removed)
+    }
+
     pub fn retain<F>(mut self, f: &F) -> Item<V>
     where
         F: Fn(&str, &mut V) -> bool,
@@ -152,10 +193,10 @@ impl<V> Node<V> {
         let mut children = Vec::new();
 
         for child in self.children {
-            let child = child.retain(f);
+            let child = Arc::unwrap_or_clone(child).retain(f);
 
             if !child.is_empty() {
-                children.push(child);
+                children.push(Arc::new(child));
             }
         }
 
@@ -164,7 +205,7 @@ impl<V> Node<V> {
         }
 
         if children.len() == 1 {
-            return children.pop().unwrap();
+            return Arc::unwrap_or_clone(children.pop().unwrap());
         }
 
         self.children = children;
@@ -187,7 +228,7 @@ impl<V> Node<V> {
     pub fn cached_len(&self) -> usize {
         let mut count = 0;
 
-        if self.regex.compiled.is_some() {
+        if self.regex.is_compiled() {
             count += 1;
         }
 
@@ -219,17 +260,12 @@ impl<V> Node<V> {
     /// Level argument allow to build cache on first level of the tree by priority
     /// Implementation must retain at which level this node is build and not do any caching
     /// if we are not on the current level
-    pub fn cache(&mut self, mut left: u64, cache_level: u64, current_level: u64) -> u64 {
-        // Already cached
-        if cache_level == current_level && self.regex.compiled.is_none() {
-            self.regex = Arc::new(self.regex.compile());
-
-            if self.regex.compiled.is_some() {
-                left -= 1;
-            }
+    pub fn cache(&self, mut left: u64, cache_level: u64, current_level: u64) -> u64 {
+        if cache_level == current_level && self.regex.cache() {
+            left -= 1;
         }
 
-        for child in &mut self.children {
+        for child in &self.children {
             left = child.cache(left, cache_level, current_level + 1);
         }
 
@@ -240,7 +276,7 @@ impl<V> Node<V> {
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for Node<V>
 where
-    V: DotBuilder,
+    V: DotBuilder + Clone,
 {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
         let node_name = format!("node_regex_{}", id);
@@ -248,7 +284,7 @@ where
 
         let mut node = GraphNode::new(&node_name).label(self.regex.original.as_str());
 
-        if self.regex.compiled.is_some() {
+        if self.regex.is_compiled() {
             node = node.color(Some("green"));
         } else {
             node = node.color(Some("red"));
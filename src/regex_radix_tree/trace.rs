@@ -9,7 +9,10 @@ pub struct Trace<'a, V> {
     pub(crate) values: Vec<&'a V>,
 }
 
-impl<V> Leaf<V> {
+impl<V> Leaf<V>
+where
+    V: Clone,
+{
     pub fn trace(&self, haystack: &str) -> Trace<'_, V> {
         let matched = self.regex.is_match(haystack);
 
@@ -22,7 +25,10 @@ impl<V> Leaf<V> {
         }
     }
 }
-impl<V> Node<V> {
+impl<V> Node<V>
+where
+    V: Clone,
+{
     pub fn trace(&self, haystack: &str) -> Trace<'_, V> {
         let mut children = Vec::new();
         let matched = self.regex.is_match(haystack);
@@ -43,7 +49,10 @@ impl<V> Node<V> {
     }
 }
 
-impl<V> Item<V> {
+impl<V> Item<V>
+where
+    V: Clone,
+{
     pub fn trace(&self, haystack: &str) -> Trace<'_, V> {
         match self {
             Item::Empty(_) => Trace {
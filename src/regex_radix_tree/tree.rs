@@ -25,7 +25,10 @@ where
     }
 }
 
-impl<V> RegexTreeMap<V> {
+impl<V> RegexTreeMap<V>
+where
+    V: Clone,
+{
     pub fn new(ignore_case: bool) -> Self {
         RegexTreeMap {
             root: Item::Empty(ignore_case),
@@ -48,6 +51,16 @@ impl<V> RegexTreeMap<V> {
         removed
     }
 
+    /// Remove an item associated to this regex, unlike `remove` it does not need to look at the whole tree
+    pub fn remove_by_regex(&mut self, regex: &str, id: &str) -> Option<V> {
+        let mut root = Item::Empty(false);
+        std::mem::swap(&mut self.root, &mut root);
+        let (new_root, removed) = root.remove_by_regex(regex, id);
+        self.root = new_root;
+
+        removed
+    }
+
     pub fn retain<F>(&mut self, f: &F)
     where
         F: Fn(&str, &mut V) -> bool,
@@ -82,7 +95,7 @@ impl<V> RegexTreeMap<V> {
         self.root.get_mut(regex)
     }
 
-    pub fn cache(&mut self, limit: u64, level: Option<u64>) -> u64 {
+    pub fn cache(&self, limit: u64, level: Option<u64>) -> u64 {
         let mut left = limit;
 
         if let Some(level) = level {
@@ -122,7 +135,7 @@ impl<V> RegexTreeMap<V> {
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for RegexTreeMap<V>
 where
-    V: DotBuilder,
+    V: DotBuilder + Clone,
 {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
         self.root.graph(id, graph)
@@ -138,7 +151,10 @@ where
     }
 }
 
-impl<V> UniqueRegexTreeMap<V> {
+impl<V> UniqueRegexTreeMap<V>
+where
+    V: Clone,
+{
     pub fn new(ignore_case: bool) -> Self {
         UniqueRegexTreeMap {
             tree: RegexTreeMap::new(ignore_case),
@@ -150,7 +166,7 @@ impl<V> UniqueRegexTreeMap<V> {
     }
 
     pub fn remove(&mut self, regex: &str) -> Option<V> {
-        self.tree.remove(regex)
+        self.tree.remove_by_regex(regex, regex)
     }
 
     pub fn retain<F>(&mut self, f: &F)
@@ -180,7 +196,7 @@ impl<V> UniqueRegexTreeMap<V> {
         self.tree.get_mut(regex).pop()
     }
 
-    pub fn cache(&mut self, limit: u64, level: Option<u64>) -> u64 {
+    pub fn cache(&self, limit: u64, level: Option<u64>) -> u64 {
         self.tree.cache(limit, level)
     }
 
@@ -200,7 +216,7 @@ impl<V> UniqueRegexTreeMap<V> {
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for UniqueRegexTreeMap<V>
 where
-    V: DotBuilder,
+    V: DotBuilder + Clone,
 {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
         self.tree.graph(id, graph)
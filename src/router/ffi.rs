@@ -0,0 +1,483 @@
+use std::{os::raw::c_char, ptr::null, slice};
+
+use serde_json::from_str as json_decode;
+
+use crate::{
+    Error,
+    action::Action,
+    api::{Rule, RuleChangeSet, RulesMessage},
+    error::ffi::set_last_error,
+    ffi_helpers::c_char_to_str,
+    filter::Buffer,
+    http::Request,
+    router::{Router, RouterRegistry},
+    router_config::RouterConfig,
+};
+
+/// Create a router from a rules message and a router config, both serialized in json
+///
+/// A null config uses the default router config.
+///
+/// Returns null if an error happens, otherwise it returns a pointer to a router
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_router_create(_config: *const c_char, _rules: *const c_char, cache_limit: u64) -> *const Router<Rule> {
+    let config = match c_char_to_str(_config) {
+        None => RouterConfig::default(),
+        Some(config_str) => match json_decode(config_str) {
+            Err(source) => {
+                set_last_error(Error::Decode {
+                    input: "router config",
+                    source,
+                });
+
+                return null();
+            }
+            Ok(config) => config,
+        },
+    };
+
+    let rules_str = match c_char_to_str(_rules) {
+        None => return null(),
+        Some(str) => str,
+    };
+
+    let rules_message: RulesMessage = match json_decode(rules_str) {
+        Err(source) => {
+            set_last_error(Error::Decode {
+                input: "rules message",
+                source,
+            });
+
+            return null();
+        }
+        Ok(rules_message) => rules_message,
+    };
+
+    let mut router = Router::<Rule>::from_config(config);
+
+    for rule in rules_message.rules {
+        router.insert(rule);
+    }
+
+    router.cache(if cache_limit == 0 { None } else { Some(cache_limit) });
+
+    Box::into_raw(Box::new(router))
+}
+
+/// Create a new router by applying a change set, serialized in json, on an existing router
+///
+/// The existing router is left untouched so it can still be used by in-flight requests,
+/// it must be dropped by the caller once it is no longer needed.
+///
+/// Returns null if an error happens, otherwise it returns a pointer to the new router
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_apply_change_set(
+    _router: *const Router<Rule>,
+    _change_set: *const c_char,
+    cache_limit: u64,
+) -> *const Router<Rule> {
+    if _router.is_null() {
+        return null();
+    }
+
+    let change_set_str = match c_char_to_str(_change_set) {
+        None => return null(),
+        Some(str) => str,
+    };
+
+    let change_set: RuleChangeSet = match json_decode(change_set_str) {
+        Err(source) => {
+            set_last_error(Error::Decode {
+                input: "rule change set",
+                source,
+            });
+
+            return null();
+        }
+        Ok(change_set) => change_set,
+    };
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { &*_router };
+    let mut new_router = router.clone();
+
+    new_router.apply_change_set(change_set.added, change_set.updated, change_set.deleted);
+    new_router.cache(if cache_limit == 0 { None } else { Some(cache_limit) });
+
+    Box::into_raw(Box::new(new_router))
+}
+
+/// Create a router from a binary snapshot, the data can be a memory mapped snapshot file
+///
+/// When the config, serialized in json, is not null, the snapshot must have been built with the same config.
+///
+/// Returns null if an error happens, otherwise it returns a pointer to a router
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a buffer of `len` bytes or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_load_snapshot(
+    data: *const u8,
+    len: usize,
+    _config: *const c_char,
+    cache_limit: u64,
+) -> *const Router<Rule> {
+    if data.is_null() {
+        return null();
+    }
+
+    let config: Option<RouterConfig> = match c_char_to_str(_config) {
+        None => None,
+        Some(config_str) => match json_decode(config_str) {
+            Err(source) => {
+                set_last_error(Error::Decode {
+                    input: "router config",
+                    source,
+                });
+
+                return null();
+            }
+            Ok(config) => Some(config),
+        },
+    };
+
+    // Safety: data is a valid pointer to a buffer of len bytes
+    let snapshot = unsafe { slice::from_raw_parts(data, len) };
+
+    let mut router = match Router::<Rule>::from_snapshot(snapshot, config.as_ref()) {
+        Err(error) => {
+            set_last_error(error.into());
+
+            return null();
+        }
+        Ok(router) => router,
+    };
+
+    router.cache(if cache_limit == 0 { None } else { Some(cache_limit) });
+
+    Box::into_raw(Box::new(router))
+}
+
+/// Serialize a router in a binary snapshot, which can be loaded with `redirectionio_router_load_snapshot`
+///
+/// Returns an empty buffer if an error happens, the buffer must be freed with `redirectionio_api_buffer_drop`
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_save_snapshot(_router: *const Router<Rule>) -> Buffer {
+    if _router.is_null() {
+        return Buffer::default();
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { &*_router };
+
+    match router.to_snapshot() {
+        Err(error) => {
+            set_last_error(error.into());
+
+            Buffer::default()
+        }
+        // The buffer is freed as a boxed slice, so its capacity must be its length
+        Ok(snapshot) => Buffer::from_vec(snapshot.into_boxed_slice().into_vec()),
+    }
+}
+
+/// Match a request against a router and compute the resulting action
+///
+/// The request is rebuilt with the router config before matching, so it can be created with
+/// `redirectionio_request_create` or `redirectionio_request_from_str`.
+///
+/// Returns null if an error happens, otherwise it returns a pointer to an action, which may be
+/// empty if no rule matched
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a Router or null pointer
+/// and a valid pointer to a Request or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_match_action(_router: *const Router<Rule>, _request: *const Request) -> *const Action {
+    if _router.is_null() || _request.is_null() {
+        return null();
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { &*_router };
+    // Safety: _request is a valid pointer to a Request
+    let request = unsafe { &*_request };
+
+    let request_rebuild = router.rebuild_request(request);
+    let routes = router.match_request(&request_rebuild);
+    let action = Action::from_routes_rule(routes, &request_rebuild, None);
+
+    Box::into_raw(Box::new(action))
+}
+
+/// Returns the number of rules in a router
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_len(_router: *const Router<Rule>) -> u64 {
+    if _router.is_null() {
+        return 0;
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { &*_router };
+
+    router.len() as u64
+}
+
+/// # Safety
+///
+/// This function must be called with a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_drop(_router: *mut Router<Rule>) {
+    if _router.is_null() {
+        return;
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    drop(unsafe { Box::from_raw(_router) });
+}
+
+/// Create an empty router registry, routers are added with `redirectionio_router_registry_insert`
+#[unsafe(no_mangle)]
+pub extern "C" fn redirectionio_router_registry_create() -> *const RouterRegistry<Rule> {
+    Box::into_raw(Box::new(RouterRegistry::<Rule>::new()))
+}
+
+/// Register a router for a project and its hosts, serialized as a json array, replacing the project if it exists
+///
+/// The registry takes ownership of the router, which must not be used or dropped by the caller afterwards,
+/// even if an error happens.
+///
+/// Returns false if an error happens
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+/// and a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_insert(
+    _registry: *const RouterRegistry<Rule>,
+    _project_id: *const c_char,
+    _hosts: *const c_char,
+    _router: *mut Router<Rule>,
+) -> bool {
+    if _router.is_null() {
+        return false;
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { Box::from_raw(_router) };
+
+    if _registry.is_null() {
+        return false;
+    }
+
+    let project_id = match c_char_to_str(_project_id) {
+        None => return false,
+        Some(str) => str,
+    };
+
+    let hosts_str = match c_char_to_str(_hosts) {
+        None => return false,
+        Some(str) => str,
+    };
+
+    let hosts: Vec<String> = match json_decode(hosts_str) {
+        Err(source) => {
+            set_last_error(Error::Decode { input: "hosts", source });
+
+            return false;
+        }
+        Ok(hosts) => hosts,
+    };
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    let registry = unsafe { &*_registry };
+    registry.insert(project_id, hosts, *router);
+
+    true
+}
+
+/// Replace the router of a registered project, requests being matched against the previous router are not affected
+///
+/// The registry takes ownership of the router, which must not be used or dropped by the caller afterwards,
+/// even if an error happens.
+///
+/// Returns false if the project is not registered or an error happens
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+/// and a valid pointer to a Router or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_swap(
+    _registry: *const RouterRegistry<Rule>,
+    _project_id: *const c_char,
+    _router: *mut Router<Rule>,
+) -> bool {
+    if _router.is_null() {
+        return false;
+    }
+
+    // Safety: _router is a valid pointer to a Router
+    let router = unsafe { Box::from_raw(_router) };
+
+    if _registry.is_null() {
+        return false;
+    }
+
+    let project_id = match c_char_to_str(_project_id) {
+        None => return false,
+        Some(str) => str,
+    };
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    let registry = unsafe { &*_registry };
+
+    registry.swap(project_id, *router).is_some()
+}
+
+/// Remove a project from a registry
+///
+/// Returns false if the project is not registered
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_remove(_registry: *const RouterRegistry<Rule>, _project_id: *const c_char) -> bool {
+    if _registry.is_null() {
+        return false;
+    }
+
+    let project_id = match c_char_to_str(_project_id) {
+        None => return false,
+        Some(str) => str,
+    };
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    let registry = unsafe { &*_registry };
+
+    registry.remove(project_id).is_some()
+}
+
+/// Match a request against the router of the project serving its host and compute the resulting action
+///
+/// The request is rebuilt with the config of this router before matching.
+///
+/// Returns null if no project serves the request host or an error happens, otherwise it returns a pointer
+/// to an action, which may be empty if no rule matched
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+/// and a valid pointer to a Request or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_match_action(
+    _registry: *const RouterRegistry<Rule>,
+    _request: *const Request,
+) -> *const Action {
+    if _registry.is_null() || _request.is_null() {
+        return null();
+    }
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    let registry = unsafe { &*_registry };
+    // Safety: _request is a valid pointer to a Request
+    let request = unsafe { &*_request };
+
+    let (request_rebuild, routes) = match registry.match_request(request) {
+        None => return null(),
+        Some(matched) => matched,
+    };
+
+    let action = Action::from_routes_rule(routes, &request_rebuild, None);
+
+    Box::into_raw(Box::new(action))
+}
+
+/// Returns the number of projects in a registry
+///
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_len(_registry: *const RouterRegistry<Rule>) -> u64 {
+    if _registry.is_null() {
+        return 0;
+    }
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    let registry = unsafe { &*_registry };
+
+    registry.len() as u64
+}
+
+/// # Safety
+///
+/// This function must be called with a valid pointer to a RouterRegistry or null pointer
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn redirectionio_router_registry_drop(_registry: *mut RouterRegistry<Rule>) {
+    if _registry.is_null() {
+        return;
+    }
+
+    // Safety: _registry is a valid pointer to a RouterRegistry
+    drop(unsafe { Box::from_raw(_registry) });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::http::ffi::{redirectionio_request_drop, redirectionio_request_from_str};
+
+    #[test]
+    fn test_router_create_match_drop() {
+        let rules = CString::new(
+            r#"{"hydra:member": [{"id": "foo", "rank": 0, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}]}"#,
+        )
+        .unwrap();
+        let router = redirectionio_router_create(null(), rules.as_ptr(), 0);
+
+        assert!(!router.is_null());
+        assert_eq!(unsafe { redirectionio_router_len(router) }, 1);
+
+        for (url, status_code) in [("/foo", 301), ("/other", 0)] {
+            let url = CString::new(url).unwrap();
+            let request = redirectionio_request_from_str(url.as_ptr());
+            let action = unsafe { redirectionio_router_match_action(router, request) };
+
+            assert!(!action.is_null());
+
+            // Safety: action is a valid pointer returned by the router, it is only dropped here
+            let mut action = unsafe { Box::from_raw(action as *mut Action) };
+
+            assert_eq!(action.get_status_code(0, None), status_code);
+            unsafe { redirectionio_request_drop(request as *mut Request) };
+        }
+
+        unsafe { redirectionio_router_drop(router as *mut Router<Rule>) };
+    }
+
+    #[test]
+    fn test_router_create_invalid_rules() {
+        let rules = CString::new("not json").unwrap();
+
+        assert!(redirectionio_router_create(null(), rules.as_ptr(), 0).is_null());
+    }
+}
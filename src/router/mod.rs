@@ -1,38 +1,69 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod ffi;
+mod registry;
 pub mod request_matcher;
 mod route;
+mod route_cookie;
 mod route_datetime;
 mod route_header;
 mod route_ip;
+mod route_language;
+mod route_query_param;
+mod route_schedule;
 mod route_time;
+mod route_timezone;
 mod route_weekday;
+mod snapshot;
 mod trace;
 
 use core::cmp::Reverse;
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::{collections::HashSet, sync::Arc};
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Kind, Node};
-pub use request_matcher::{DateTimeMatcher, HostMatcher, IpMatcher, MethodMatcher, PathAndQueryMatcher, SchemeMatcher};
+use imbl::HashMap;
+pub use registry::RouterRegistry;
+pub use request_matcher::{
+    CookieMatcher, DateTimeMatcher, HostMatcher, IpMatcher, LanguageMatcher, MethodMatcher, PathAndQueryMatcher, QueryParamMatcher,
+    SchemeMatcher,
+};
 pub use route::{IntoRoute, Route};
+pub use route_cookie::RouteCookie;
 pub use route_datetime::RouteDateTime;
 pub use route_header::{RouteHeader, RouteHeaderKind};
 pub use route_ip::RouteIp;
+pub use route_language::RouteLanguage;
+pub use route_query_param::RouteQueryParam;
+pub use route_schedule::RouteSchedule;
 pub use route_time::RouteTime;
+pub use route_timezone::RouteTimezone;
 pub use route_weekday::RouteWeekday;
+pub use snapshot::{SNAPSHOT_VERSION, SnapshotError, SnapshotHeader};
 pub use trace::{RouteTrace, Trace};
 
 #[cfg(feature = "dot")]
 use crate::dot::DotBuilder;
 use crate::{http::Request, router_config::RouterConfig};
 
-#[derive(Debug, Clone)]
+/// Router matching requests against routes
+///
+/// Matchers and routes are shared between clones of a router, so cloning it is cheap and modifying a clone only
+/// copies the matchers on the path of the modified routes, other routers sharing them are not affected.
+#[derive(Debug)]
 pub struct Router<T> {
     matcher: SchemeMatcher<T>,
     pub config: Arc<RouterConfig>,
-    pub routes: HashMap<String, Arc<Route<T>>>,
+    routes: HashMap<String, Arc<Route<T>>>,
+}
+
+impl<T> Clone for Router<T> {
+    fn clone(&self) -> Self {
+        Router {
+            matcher: self.matcher.clone(),
+            config: self.config.clone(),
+            routes: self.routes.clone(),
+        }
+    }
 }
 
 impl<T> Default for Router<T> {
@@ -52,7 +83,11 @@ impl<T> Router<T> {
         Self::from_arc_config(Arc::new(config))
     }
 
-    pub fn from_arc_config(config: Arc<RouterConfig>) -> Self {
+    pub fn from_arc_config(mut config: Arc<RouterConfig>) -> Self {
+        if config.geoip_database_path.is_some() && !config.geoip_database.is_loaded() {
+            Arc::make_mut(&mut config).load_geoip_database();
+        }
+
         Self {
             matcher: SchemeMatcher::new(config.clone()),
             config,
@@ -60,7 +95,10 @@ impl<T> Router<T> {
         }
     }
 
+    /// Insert a route, replacing the route with the same id if any
     pub fn insert_route(&mut self, route: Route<T>) {
+        self.remove(route.id());
+
         let arc_route = Arc::new(route);
 
         self.matcher.insert(arc_route.clone());
@@ -72,18 +110,17 @@ impl<T> Router<T> {
     }
 
     pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        if self.routes.contains_key(id) {
-            self.routes.remove(id);
+        let route = self.routes.remove(id)?;
 
-            self.matcher.remove(id)
-        } else {
-            None
-        }
+        self.matcher.remove(&route);
+
+        Some(route)
     }
 
     pub fn batch_remove(&mut self, ids: &HashSet<String>) {
-        self.routes.retain(|id, _| !ids.contains(id));
-        self.matcher.batch_remove(ids);
+        for id in ids {
+            self.remove(id);
+        }
     }
 
     pub fn rebuild_request(&self, request: &Request) -> Request {
@@ -102,8 +139,9 @@ impl<T> Router<T> {
         self.routes.is_empty()
     }
 
-    pub fn routes(&self) -> &HashMap<String, Arc<Route<T>>> {
-        &self.routes
+    /// Routes of the router with their id, in no particular order
+    pub fn routes(&self) -> impl Iterator<Item = (&String, &Arc<Route<T>>)> {
+        self.routes.iter()
     }
 
     pub fn trace_request(&self, request: &Request) -> Vec<Trace<T>> {
@@ -215,3 +253,155 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Rule;
+
+    fn rule(json: &str) -> Rule {
+        Rule::from_json(json).unwrap()
+    }
+
+    #[test]
+    fn test_router_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Router<Rule>>();
+        assert_send_sync::<Arc<std::sync::RwLock<Router<Rule>>>>();
+        assert_send_sync::<RouterRegistry<Rule>>();
+    }
+
+    #[test]
+    fn test_change_set_does_not_modify_shared_router() {
+        let mut router = Router::<Rule>::default();
+
+        router.insert(rule(
+            r#"{"id": "static", "rank": 0, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+        ));
+        router.insert(rule(
+            r#"{"id": "marker", "rank": 0, "source": {"host": "example.com", "path": "/blog/@slug"}, "markers": [{"name": "slug", "regex": "[a-z]+"}], "target": "/news/@slug", "status_code": 302}"#,
+        ));
+        router.cache(None);
+
+        let mut updated = router.clone();
+
+        updated.apply_change_set(
+            vec![rule(
+                r#"{"id": "added", "rank": 0, "source": {"path": "/added"}, "target": "/bar", "status_code": 301}"#,
+            )],
+            vec![rule(
+                r#"{"id": "static", "rank": 0, "source": {"path": "/moved"}, "target": "/bar", "status_code": 301}"#,
+            )],
+            HashSet::from(["marker".to_string()]),
+        );
+
+        let blog_request = Request::from_config(
+            &router.config,
+            "/blog/hello".to_string(),
+            Some("example.com".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        assert_eq!(router.len(), 2);
+        assert_eq!(router.match_request(&"/foo".parse().unwrap()).len(), 1);
+        assert_eq!(router.match_request(&"/moved".parse().unwrap()).len(), 0);
+        assert_eq!(router.match_request(&"/added".parse().unwrap()).len(), 0);
+        assert_eq!(router.match_request(&blog_request).len(), 1);
+
+        assert_eq!(updated.len(), 2);
+        assert_eq!(updated.match_request(&"/foo".parse().unwrap()).len(), 0);
+        assert_eq!(updated.match_request(&"/moved".parse().unwrap()).len(), 1);
+        assert_eq!(updated.match_request(&"/added".parse().unwrap()).len(), 1);
+        assert_eq!(updated.match_request(&blog_request).len(), 0);
+    }
+
+    #[test]
+    fn test_insert_replaces_route_with_same_id() {
+        let mut router = Router::<Rule>::default();
+
+        router.insert(rule(
+            r#"{"id": "rule", "rank": 0, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+        ));
+        router.insert(rule(
+            r#"{"id": "rule", "rank": 0, "source": {"path": "/baz"}, "target": "/bar", "status_code": 301}"#,
+        ));
+
+        assert_eq!(router.len(), 1);
+        assert_eq!(router.match_request(&"/foo".parse().unwrap()).len(), 0);
+        assert_eq!(router.match_request(&"/baz".parse().unwrap()).len(), 1);
+
+        router.remove("rule");
+
+        assert!(router.is_empty());
+        assert_eq!(router.match_request(&"/baz".parse().unwrap()).len(), 0);
+    }
+
+    #[test]
+    fn test_invalid_range_bound_keeps_the_other_one() {
+        let route = rule(
+            r#"{"id": "rule", "rank": 0, "source": {"path": "/foo", "datetime": [["2020-01-01T00:00:00Z", "garbage"]], "time": [["garbage", "18:00:00"]]}, "target": "/bar", "status_code": 301}"#,
+        )
+        .into_route(&RouterConfig::default());
+
+        let datetime = &route.datetime().unwrap()[0];
+        let time = &route.time().unwrap()[0];
+
+        assert!(datetime.start.is_some());
+        assert!(datetime.end.is_none());
+        assert!(!datetime.match_datetime(&"2019-06-01T12:00:00Z".parse().unwrap()));
+        assert!(datetime.match_datetime(&"2021-06-01T12:00:00Z".parse().unwrap()));
+
+        assert!(time.start.is_none());
+        assert!(time.match_datetime(&"2021-06-01T12:00:00Z".parse().unwrap()));
+        assert!(!time.match_datetime(&"2021-06-01T19:00:00Z".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_next_activation_with_other_time_conditions() {
+        let config = RouterConfig::default();
+        let after = "2026-01-15T00:00:00Z".parse().unwrap();
+        let next_activation = |json: &str| rule(json).into_route(&config).next_activation(&after);
+
+        assert_eq!(
+            next_activation(
+                r#"{"id": "ended", "rank": 0, "source": {"path": "/foo", "schedules": ["0 9 * * *"], "datetime": [["2024-01-01T00:00:00Z", "2025-01-01T00:00:00Z"]]}, "target": "/bar", "status_code": 302}"#
+            ),
+            None
+        );
+        assert_eq!(
+            next_activation(
+                r#"{"id": "later", "rank": 0, "source": {"path": "/foo", "schedules": ["* 9-17 * * *"], "datetime": [["2026-02-01T12:30:00Z", null]]}, "target": "/bar", "status_code": 302}"#
+            ),
+            Some("2026-02-01T12:30:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            next_activation(
+                r#"{"id": "weekend", "rank": 0, "source": {"path": "/foo", "schedules": ["* 9-17 * * *"], "weekdays": ["sat"], "time": [["10:00:00", null]]}, "target": "/bar", "status_code": 302}"#
+            ),
+            Some("2026-01-17T10:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_timezone_falls_back_to_default() {
+        let config = RouterConfig {
+            default_timezone: Some("Europe/Paris".to_string()),
+            ..Default::default()
+        };
+        let route = rule(
+            r#"{"id": "rule", "rank": 0, "source": {"path": "/foo", "time": [["09:00:00", "18:00:00"]], "timezone": "Europe/Nowhere"}, "target": "/bar", "status_code": 302}"#,
+        )
+        .into_route(&config);
+
+        let time = &route.time().unwrap()[0];
+
+        assert_eq!(time.timezone, RouteTimezone::parse("Europe/Paris"));
+        // 08:30 in utc is 09:30 in Paris during winter
+        assert!(time.match_datetime(&"2026-01-15T08:30:00Z".parse().unwrap()));
+        assert!(!time.match_datetime(&"2026-01-15T17:30:00Z".parse().unwrap()));
+    }
+}
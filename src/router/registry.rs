@@ -0,0 +1,279 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use super::{Route, Router};
+use crate::http::Request;
+
+/// Registry of routers, one per project, dispatching requests to a project by their host
+///
+/// Each project has its own router, so its own config, and a list of hosts which can be an exact host like
+/// `example.com`, a wildcard host like `*.example.com` matching any sub domain, or `*` matching any host.
+///
+/// Routers are stored behind an `Arc`, a router can be swapped while requests are still matched against
+/// the previous one.
+#[derive(Debug)]
+pub struct RouterRegistry<T> {
+    projects: RwLock<Projects<T>>,
+}
+
+#[derive(Debug)]
+struct Projects<T> {
+    routers: HashMap<String, Arc<Router<T>>>,
+    hosts: HashMap<String, Vec<String>>,
+    projects_by_host: HashMap<String, String>,
+}
+
+impl<T> Default for RouterRegistry<T> {
+    fn default() -> Self {
+        RouterRegistry {
+            projects: RwLock::new(Projects {
+                routers: HashMap::new(),
+                hosts: HashMap::new(),
+                projects_by_host: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl<T> RouterRegistry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a project with its hosts and router, replacing the existing one with the same id if any
+    ///
+    /// A host already used by another project is moved to this project.
+    ///
+    /// Returns the router previously registered for this project
+    pub fn insert(&self, project_id: &str, hosts: Vec<String>, router: Router<T>) -> Option<Arc<Router<T>>> {
+        let mut projects = self.projects.write().unwrap_or_else(PoisonError::into_inner);
+        let previous = projects.remove(project_id);
+        let hosts = hosts.iter().map(|host| normalize_host(host)).collect::<Vec<_>>();
+
+        for host in &hosts {
+            if let Some(other_project_id) = projects.projects_by_host.insert(host.clone(), project_id.to_string())
+                && let Some(other_hosts) = projects.hosts.get_mut(&other_project_id)
+            {
+                other_hosts.retain(|other_host| other_host != host);
+            }
+        }
+
+        projects.hosts.insert(project_id.to_string(), hosts);
+        projects.routers.insert(project_id.to_string(), Arc::new(router));
+
+        previous
+    }
+
+    /// Replace the router of a registered project, its hosts are kept
+    ///
+    /// Returns the previous router, or None if the project is not registered, in which case the router is not
+    /// inserted
+    pub fn swap(&self, project_id: &str, router: Router<T>) -> Option<Arc<Router<T>>> {
+        let mut projects = self.projects.write().unwrap_or_else(PoisonError::into_inner);
+        let current = projects.routers.get_mut(project_id)?;
+
+        Some(std::mem::replace(current, Arc::new(router)))
+    }
+
+    /// Remove a project and its hosts, returns its router if it was registered
+    pub fn remove(&self, project_id: &str) -> Option<Arc<Router<T>>> {
+        self.projects.write().unwrap_or_else(PoisonError::into_inner).remove(project_id)
+    }
+
+    pub fn get(&self, project_id: &str) -> Option<Arc<Router<T>>> {
+        self.projects
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .routers
+            .get(project_id)
+            .cloned()
+    }
+
+    /// Get the project id and router for a host
+    ///
+    /// An exact host is preferred, then the most specific wildcard host, then the `*` host.
+    pub fn get_by_host(&self, host: &str) -> Option<(String, Arc<Router<T>>)> {
+        let projects = self.projects.read().unwrap_or_else(PoisonError::into_inner);
+        let host = normalize_host(host);
+        let mut candidates = vec![host.clone()];
+        let mut domain = host.as_str();
+
+        while let Some((_, parent)) = domain.split_once('.') {
+            candidates.push(format!("*.{parent}"));
+            domain = parent;
+        }
+
+        candidates.push("*".to_string());
+
+        candidates.iter().find_map(|candidate| {
+            let project_id = projects.projects_by_host.get(candidate)?;
+            let router = projects.routers.get(project_id)?;
+
+            Some((project_id.clone(), router.clone()))
+        })
+    }
+
+    /// Get the project id and router for a request, a request without host only matches the `*` host
+    pub fn get_by_request(&self, request: &Request) -> Option<(String, Arc<Router<T>>)> {
+        self.get_by_host(request.host().unwrap_or("*"))
+    }
+
+    /// Match a request against the router of its project, the request is rebuilt with the config of this router
+    ///
+    /// Returns None when no project is registered for the request host
+    pub fn match_request(&self, request: &Request) -> Option<(Request, Vec<Arc<Route<T>>>)> {
+        let (_, router) = self.get_by_request(request)?;
+        let request = router.rebuild_request(request);
+        let routes = router.match_request(&request);
+
+        Some((request, routes))
+    }
+
+    pub fn project_ids(&self) -> Vec<String> {
+        let projects = self.projects.read().unwrap_or_else(PoisonError::into_inner);
+        let mut project_ids = projects.routers.keys().cloned().collect::<Vec<_>>();
+        project_ids.sort();
+
+        project_ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.projects.read().unwrap_or_else(PoisonError::into_inner).routers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Projects<T> {
+    fn remove(&mut self, project_id: &str) -> Option<Arc<Router<T>>> {
+        for host in self.hosts.remove(project_id).unwrap_or_default() {
+            self.projects_by_host.remove(&host);
+        }
+
+        self.routers.remove(project_id)
+    }
+}
+
+// Hosts are compared without case and without port
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().to_lowercase();
+
+    // Ipv6 address with or without port
+    if let Some(rest) = host.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((address, _)) => format!("[{address}]"),
+            None => host,
+        };
+    }
+
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') && port.chars().all(|c| c.is_ascii_digit()) => name.to_string(),
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RouterConfig, api::Rule};
+
+    fn router(target: &str) -> Router<Rule> {
+        let mut router = Router::<Rule>::default();
+        let rule = format!(r#"{{"id": "rule", "rank": 0, "source": {{"path": "/foo"}}, "target": "{target}", "status_code": 301}}"#);
+
+        router.insert(Rule::from_json(rule.as_str()).unwrap());
+
+        router
+    }
+
+    fn target(registry: &RouterRegistry<Rule>, host: &str) -> Option<String> {
+        let (_, router) = registry.get_by_host(host)?;
+        let route = router.match_request(&"/foo".parse().unwrap()).pop()?;
+
+        route.handler().target.clone()
+    }
+
+    #[test]
+    fn test_get_by_host() {
+        let registry = RouterRegistry::new();
+
+        registry.insert(
+            "exact",
+            vec!["Example.com".to_string(), "www.example.com:8080".to_string()],
+            router("/exact"),
+        );
+        registry.insert("wildcard", vec!["*.example.com".to_string()], router("/wildcard"));
+        registry.insert("nested", vec!["*.shop.example.com".to_string()], router("/nested"));
+
+        assert_eq!(target(&registry, "example.com"), Some("/exact".to_string()));
+        assert_eq!(target(&registry, "WWW.example.com:443"), Some("/exact".to_string()));
+        assert_eq!(target(&registry, "blog.example.com"), Some("/wildcard".to_string()));
+        assert_eq!(target(&registry, "fr.shop.example.com"), Some("/nested".to_string()));
+        assert_eq!(target(&registry, "other.com"), None);
+
+        registry.insert("default", vec!["*".to_string()], router("/default"));
+
+        assert_eq!(target(&registry, "other.com"), Some("/default".to_string()));
+        assert_eq!(registry.project_ids(), vec!["default", "exact", "nested", "wildcard"]);
+    }
+
+    #[test]
+    fn test_swap_and_remove() {
+        let registry = RouterRegistry::new();
+
+        registry.insert("project", vec!["example.com".to_string()], router("/before"));
+
+        let (_, in_flight) = registry.get_by_host("example.com").unwrap();
+
+        assert!(registry.swap("unknown", router("/unknown")).is_none());
+        assert!(registry.swap("project", router("/after")).is_some());
+        assert_eq!(target(&registry, "example.com"), Some("/after".to_string()));
+        assert_eq!(
+            in_flight.match_request(&"/foo".parse().unwrap())[0].handler().target,
+            Some("/before".to_string())
+        );
+
+        registry.insert("other", vec!["example.com".to_string()], router("/other"));
+        registry.remove("project");
+
+        assert_eq!(target(&registry, "example.com"), Some("/other".to_string()));
+
+        registry.remove("other");
+
+        assert!(registry.is_empty());
+        assert_eq!(target(&registry, "example.com"), None);
+    }
+
+    #[test]
+    fn test_match_request_with_project_config() {
+        let registry = RouterRegistry::new();
+        let mut router = Router::<Rule>::from_config(RouterConfig {
+            ignore_path_and_query_case: true,
+            ..Default::default()
+        });
+
+        router.insert(
+            Rule::from_json(r#"{"id": "rule", "rank": 0, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#).unwrap(),
+        );
+        registry.insert("project", vec!["example.com".to_string()], router);
+
+        let request = Request::from_config(
+            &RouterConfig::default(),
+            "/FOO".to_string(),
+            Some("example.com".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let (_, routes) = registry.match_request(&request).unwrap();
+
+        assert_eq!(routes.len(), 1);
+        assert!(registry.match_request(&"/foo".parse().unwrap()).is_none());
+    }
+}
@@ -0,0 +1,35 @@
+use super::super::{
+    Route, RouteHeaderKind, RouterConfig,
+    request_matcher::{DateTimeMatcher, ValueCondition, ValueMatcher, ValueSource},
+    trace::{TraceInfo, TraceInfoValueCondition},
+};
+use crate::http::Request;
+
+pub type CookieMatcher<T> = ValueMatcher<T, Cookies, DateTimeMatcher<T>>;
+
+#[derive(Debug)]
+pub struct Cookies;
+
+impl ValueSource for Cookies {
+    const LABEL: &'static str = "cookie";
+
+    fn conditions<T>(route: &Route<T>) -> Vec<(&str, &RouteHeaderKind)> {
+        route.cookies().iter().map(|cookie| (cookie.name.as_str(), &cookie.kind)).collect()
+    }
+
+    fn ignore_case(config: &RouterConfig) -> bool {
+        config.ignore_header_case
+    }
+
+    fn name(name: &str, _config: &RouterConfig) -> String {
+        name.to_string()
+    }
+
+    fn match_condition(request: &Request, name: &str, condition: &ValueCondition, config: &RouterConfig) -> bool {
+        condition.match_values(request.cookie_values(name, config.ignore_header_case).as_slice())
+    }
+
+    fn trace_info<T>(conditions: Vec<TraceInfoValueCondition>) -> TraceInfo<T> {
+        TraceInfo::CookieGroup { conditions }
+    }
+}
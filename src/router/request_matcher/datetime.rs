@@ -1,14 +1,15 @@
 use std::{
-    collections::{BTreeMap, BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet},
     sync::Arc,
 };
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
+use imbl::OrdMap;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
-    Route, RouterConfig, Trace,
+    Route, RouteSchedule, RouteTimezone, RouterConfig, Trace,
     request_matcher::PathAndQueryMatcher,
     route_datetime::RouteDateTime,
     route_time::RouteTime,
@@ -19,15 +20,25 @@ use super::super::{
 use crate::dot::DotBuilder;
 use crate::http::Request;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct DateTimeMatcher<T> {
-    any_datetime: PathAndQueryMatcher<T>,
-    conditions: BTreeSet<DateTimeCondition>,
-    condition_groups: BTreeMap<BTreeSet<DateTimeCondition>, PathAndQueryMatcher<T>>,
+    any_datetime: Arc<PathAndQueryMatcher<T>>,
+    condition_groups: OrdMap<BTreeSet<DateTimeCondition>, Arc<PathAndQueryMatcher<T>>>,
     count: usize,
     config: Arc<RouterConfig>,
 }
 
+impl<T> Clone for DateTimeMatcher<T> {
+    fn clone(&self) -> Self {
+        DateTimeMatcher {
+            any_datetime: self.any_datetime.clone(),
+            condition_groups: self.condition_groups.clone(),
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type", content = "date_time_type")]
@@ -35,14 +46,14 @@ pub enum DateTimeCondition {
     DateTimeRange(Vec<RouteDateTime>),
     TimeRange(Vec<RouteTime>),
     Weekdays(RouteWeekday),
+    Schedule(Vec<RouteSchedule>),
 }
 
 impl<T> DateTimeMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         DateTimeMatcher {
-            any_datetime: PathAndQueryMatcher::new(config.clone()),
-            conditions: BTreeSet::new(),
-            condition_groups: BTreeMap::new(),
+            any_datetime: Arc::new(PathAndQueryMatcher::new(config.clone())),
+            condition_groups: OrdMap::new(),
             count: 0,
             config,
         }
@@ -51,80 +62,73 @@ impl<T> DateTimeMatcher<T> {
     pub fn insert(&mut self, route: Arc<Route<T>>) {
         self.count += 1;
 
-        let mut condition_group = BTreeSet::new();
-        let mut route_conditions = BTreeSet::new();
+        let condition_group = Self::condition_group(&route);
 
-        if let Some(route_datetime) = route.datetime() {
-            let condition = DateTimeCondition::DateTimeRange(route_datetime.clone());
-            condition_group.insert(condition.clone());
-            route_conditions.insert(condition.clone());
-            self.conditions.insert(condition);
-        }
-
-        if let Some(route_weekdays) = route.weekdays() {
-            let condition = DateTimeCondition::Weekdays(route_weekdays.clone());
-            condition_group.insert(condition.clone());
-            route_conditions.insert(condition.clone());
-            self.conditions.insert(condition);
-        }
-
-        if let Some(route_time) = route.time() {
-            let condition = DateTimeCondition::TimeRange(route_time.clone());
-            condition_group.insert(condition.clone());
-            route_conditions.insert(condition.clone());
-            self.conditions.insert(condition);
-        }
-
-        if route_conditions.is_empty() {
-            self.any_datetime.insert(route);
+        if condition_group.is_empty() {
+            Arc::make_mut(&mut self.any_datetime).insert(route);
 
             return;
         }
 
         if !self.condition_groups.contains_key(&condition_group) {
             self.condition_groups
-                .insert(condition_group.clone(), PathAndQueryMatcher::new(self.config.clone()));
+                .insert(condition_group.clone(), Arc::new(PathAndQueryMatcher::new(self.config.clone())));
         }
 
         let matcher = self.condition_groups.get_mut(&condition_group).unwrap();
 
-        matcher.insert(route)
+        Arc::make_mut(matcher).insert(route)
     }
 
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        let mut removed = self.any_datetime.remove(id);
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let condition_group = Self::condition_group(route);
 
-        if removed.is_some() {
-            self.count -= 1;
-
-            return removed;
-        }
+        let removed = if condition_group.is_empty() {
+            Arc::make_mut(&mut self.any_datetime).remove(route)
+        } else {
+            match self.condition_groups.get_mut(&condition_group) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.condition_groups.remove(&condition_group);
+                    }
 
-        self.condition_groups.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
+                    removed
+                }
             }
+        };
 
-            !matcher.is_empty()
-        });
-
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
         removed
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.any_datetime.batch_remove(ids);
+    fn condition_group(route: &Route<T>) -> BTreeSet<DateTimeCondition> {
+        let mut condition_group = BTreeSet::new();
 
-        self.condition_groups.retain(|_, matcher| {
-            matcher.batch_remove(ids);
+        if let Some(route_datetime) = route.datetime() {
+            condition_group.insert(DateTimeCondition::DateTimeRange(route_datetime.clone()));
+        }
 
-            !matcher.is_empty()
-        });
+        if let Some(route_weekdays) = route.weekdays() {
+            condition_group.insert(DateTimeCondition::Weekdays(route_weekdays.clone()));
+        }
 
-        self.any_datetime.is_empty() && self.condition_groups.is_empty()
+        if let Some(route_time) = route.time() {
+            condition_group.insert(DateTimeCondition::TimeRange(route_time.clone()));
+        }
+
+        if let Some(route_schedules) = route.schedules() {
+            condition_group.insert(DateTimeCondition::Schedule(route_schedules.clone()));
+        }
+
+        condition_group
     }
 
     pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
@@ -182,6 +186,7 @@ impl<T> DateTimeMatcher<T> {
 
                         traces_info_datetime.push(TraceInfoDateTimeCondition {
                             result: if executed { Some(result) } else { None },
+                            local_datetime: condition.local_datetime(request),
                             condition: condition.clone(),
                             cached: false,
                         });
@@ -193,6 +198,7 @@ impl<T> DateTimeMatcher<T> {
 
                         traces_info_datetime.push(TraceInfoDateTimeCondition {
                             result: if executed { Some(*result) } else { None },
+                            local_datetime: condition.local_datetime(request),
                             condition: condition.clone(),
                             cached: true,
                         });
@@ -216,10 +222,10 @@ impl<T> DateTimeMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         let mut new_limit = self.any_datetime.cache(limit, level);
 
-        for matcher in self.condition_groups.values_mut() {
+        for matcher in self.condition_groups.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
@@ -236,6 +242,24 @@ impl<T> DateTimeMatcher<T> {
 }
 
 impl DateTimeCondition {
+    pub fn timezone(&self) -> Option<RouteTimezone> {
+        match self {
+            DateTimeCondition::DateTimeRange(route_date_time) => route_date_time.first()?.timezone,
+            DateTimeCondition::TimeRange(route_time) => route_time.first()?.timezone,
+            DateTimeCondition::Weekdays(route_weekday) => route_weekday.timezone,
+            DateTimeCondition::Schedule(route_schedules) => route_schedules.first()?.timezone(),
+        }
+    }
+
+    pub fn local_datetime(&self, request: &Request) -> Option<String> {
+        let datetime = request.created_at.as_ref()?;
+
+        Some(match self.timezone() {
+            Some(timezone) => timezone.local(datetime).to_rfc3339(),
+            None => datetime.to_rfc3339(),
+        })
+    }
+
     pub fn match_value(&self, request: &Request) -> bool {
         if let Some(datetime) = request.created_at.as_ref() {
             match self {
@@ -258,6 +282,7 @@ impl DateTimeCondition {
                     false
                 }
                 DateTimeCondition::Weekdays(route_weekday) => route_weekday.match_datetime(datetime),
+                DateTimeCondition::Schedule(route_schedules) => route_schedules.iter().any(|schedule| schedule.match_datetime(datetime)),
             }
         } else {
             false
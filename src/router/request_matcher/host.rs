@@ -1,10 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::sync::Arc;
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
+use imbl::HashMap;
 
 use super::super::{IpMatcher, Route, RouterConfig, Trace, trace::TraceInfo};
 #[cfg(feature = "dot")]
@@ -15,21 +13,34 @@ use crate::{
     regex_radix_tree::{Trace as TreeTrace, UniqueRegexTreeMap},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct HostMatcher<T> {
-    static_hosts: HashMap<String, IpMatcher<T>>,
-    regex_tree_rule: UniqueRegexTreeMap<IpMatcher<T>>,
-    any_host: IpMatcher<T>,
+    static_hosts: HashMap<String, Arc<IpMatcher<T>>>,
+    regex_tree_rule: UniqueRegexTreeMap<Arc<IpMatcher<T>>>,
+    any_host: Arc<IpMatcher<T>>,
     always_match_any_host: bool,
     count: usize,
     config: Arc<RouterConfig>,
 }
 
+impl<T> Clone for HostMatcher<T> {
+    fn clone(&self) -> Self {
+        HostMatcher {
+            static_hosts: self.static_hosts.clone(),
+            regex_tree_rule: self.regex_tree_rule.clone(),
+            any_host: self.any_host.clone(),
+            always_match_any_host: self.always_match_any_host,
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
 impl<T> HostMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         HostMatcher {
             static_hosts: HashMap::new(),
-            any_host: IpMatcher::new(config.clone()),
+            any_host: Arc::new(IpMatcher::new(config.clone())),
             count: 0,
             regex_tree_rule: UniqueRegexTreeMap::new(config.ignore_host_case),
             always_match_any_host: config.always_match_any_host,
@@ -41,81 +52,74 @@ impl<T> HostMatcher<T> {
         self.count += 1;
 
         match route.host() {
-            None => self.any_host.insert(route.clone()),
+            None => Arc::make_mut(&mut self.any_host).insert(route.clone()),
             Some(host) => match host {
                 StaticOrDynamic::Static(static_host) => {
                     if static_host.is_empty() {
-                        self.any_host.insert(route.clone());
+                        Arc::make_mut(&mut self.any_host).insert(route.clone());
 
                         return;
                     }
 
                     if !self.static_hosts.contains_key(static_host) {
-                        self.static_hosts.insert(static_host.clone(), IpMatcher::new(self.config.clone()));
+                        self.static_hosts
+                            .insert(static_host.clone(), Arc::new(IpMatcher::new(self.config.clone())));
                     }
 
-                    self.static_hosts.get_mut(static_host).unwrap().insert(route.clone());
+                    Arc::make_mut(self.static_hosts.get_mut(static_host).unwrap()).insert(route.clone());
                 }
                 StaticOrDynamic::Dynamic(dynamic_host) => match self.regex_tree_rule.get_mut(dynamic_host.regex.as_str()) {
-                    Some(matcher) => matcher.insert(route.clone()),
+                    Some(matcher) => Arc::make_mut(matcher).insert(route.clone()),
                     None => {
                         let mut matcher = IpMatcher::new(self.config.clone());
                         matcher.insert(route.clone());
 
-                        self.regex_tree_rule.insert(dynamic_host.regex.as_str(), matcher);
+                        self.regex_tree_rule.insert(dynamic_host.regex.as_str(), Arc::new(matcher));
                     }
                 },
             },
         }
     }
 
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        let mut removed = self.any_host.remove(id);
-
-        if removed.is_some() {
-            self.count -= 1;
-
-            return removed;
-        }
-
-        self.static_hosts.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
-            }
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.host() {
+            Some(StaticOrDynamic::Static(static_host)) if !static_host.is_empty() => match self.static_hosts.get_mut(static_host) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.static_hosts.remove(static_host);
+                    }
 
-            !matcher.is_empty()
-        });
+                    removed
+                }
+            },
+            Some(StaticOrDynamic::Dynamic(dynamic_host)) => match self.regex_tree_rule.get_mut(dynamic_host.regex.as_str()) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.regex_tree_rule.remove(dynamic_host.regex.as_str());
+                    }
 
-        self.regex_tree_rule.retain(&|_, matcher| {
-            matcher.remove(id);
-            !matcher.is_empty()
-        });
+                    removed
+                }
+            },
+            _ => Arc::make_mut(&mut self.any_host).remove(route),
+        };
 
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
         removed
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.any_host.batch_remove(ids);
-
-        self.static_hosts.retain(|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.regex_tree_rule.retain(&|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.any_host.is_empty() && self.static_hosts.is_empty() && self.regex_tree_rule.is_empty()
-    }
-
     pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
         let mut routes = Vec::new();
 
@@ -196,14 +200,14 @@ impl<T> HostMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         let mut new_limit = self.regex_tree_rule.cache(limit, Some(level));
 
-        for matcher in self.static_hosts.values_mut() {
+        for matcher in self.static_hosts.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
-        for matcher in self.regex_tree_rule.iter_mut() {
+        for matcher in self.regex_tree_rule.iter() {
             new_limit = matcher.cache(new_limit, level);
         }
 
@@ -219,7 +223,7 @@ impl<T> HostMatcher<T> {
     }
 }
 
-fn tree_trace_to_trace<T>(haystack: &str, tree_trace: TreeTrace<IpMatcher<T>>, request: &Request) -> Trace<T> {
+fn tree_trace_to_trace<T>(haystack: &str, tree_trace: TreeTrace<Arc<IpMatcher<T>>>, request: &Request) -> Trace<T> {
     let mut children = Vec::new();
 
     for child in tree_trace.children {
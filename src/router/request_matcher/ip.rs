@@ -1,29 +1,38 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::sync::Arc;
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
+use imbl::HashMap;
 
 use super::super::{MethodMatcher, Route, RouterConfig, Trace, route_ip::RouteIp, trace::TraceInfo};
 #[cfg(feature = "dot")]
 use crate::dot::DotBuilder;
-use crate::http::Request;
+use crate::{geoip::GeoIpLocation, http::Request};
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct IpMatcher<T> {
-    matchers: HashMap<RouteIp, MethodMatcher<T>>,
-    no_matcher: MethodMatcher<T>,
+    matchers: HashMap<RouteIp, Arc<MethodMatcher<T>>>,
+    no_matcher: Arc<MethodMatcher<T>>,
     count: usize,
     config: Arc<RouterConfig>,
 }
 
+impl<T> Clone for IpMatcher<T> {
+    fn clone(&self) -> Self {
+        IpMatcher {
+            matchers: self.matchers.clone(),
+            no_matcher: self.no_matcher.clone(),
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
 impl<T> IpMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         IpMatcher {
             matchers: HashMap::new(),
-            no_matcher: MethodMatcher::new(config.clone()),
+            no_matcher: Arc::new(MethodMatcher::new(config.clone())),
             count: 0,
             config,
         }
@@ -37,60 +46,55 @@ impl<T> IpMatcher<T> {
         match route.ips() {
             Some(ips) => {
                 for ip in ips {
-                    self.matchers
+                    let matcher = self
+                        .matchers
                         .entry(ip.clone())
-                        .or_insert_with(|| MethodMatcher::new(config.clone()))
-                        .insert(route.clone());
+                        .or_insert_with(|| Arc::new(MethodMatcher::new(config.clone())));
+
+                    Arc::make_mut(matcher).insert(route.clone());
                 }
             }
             None => {
-                self.no_matcher.insert(route);
+                Arc::make_mut(&mut self.no_matcher).insert(route);
             }
         }
     }
 
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        let mut removed = self.no_matcher.remove(id);
-
-        if removed.is_some() {
-            self.count -= 1;
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.ips() {
+            Some(ips) => {
+                let mut removed = false;
 
-            return removed;
-        }
+                for ip in ips {
+                    if let Some(matcher) = self.matchers.get_mut(ip) {
+                        let matcher = Arc::make_mut(matcher);
+                        removed = matcher.remove(route) || removed;
+
+                        if matcher.is_empty() {
+                            self.matchers.remove(ip);
+                        }
+                    }
+                }
 
-        self.matchers.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
+                removed
             }
+            None => Arc::make_mut(&mut self.no_matcher).remove(route),
+        };
 
-            !matcher.is_empty()
-        });
-
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
         removed
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.no_matcher.batch_remove(ids);
-
-        self.matchers.retain(|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.no_matcher.is_empty() && self.matchers.is_empty()
-    }
-
     pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
         let mut routes = self.no_matcher.match_request(request);
 
         if let Some(remote_addr) = request.remote_addr.as_ref() {
             for (ip_cidr, matcher) in &self.matchers {
-                if ip_cidr.match_ip(remote_addr) {
+                if ip_cidr.match_ip(remote_addr, request.geo_location.as_ref()) {
                     routes.extend(matcher.match_request(request));
                 }
             }
@@ -104,7 +108,13 @@ impl<T> IpMatcher<T> {
 
         if let Some(remote_addr) = request.remote_addr.as_ref() {
             for (ip_cidr, matcher) in &self.matchers {
-                if ip_cidr.match_ip(remote_addr) {
+                let request_ip = if ip_cidr.is_geo() {
+                    trace_geo_location(remote_addr.to_string(), request.geo_location.as_ref())
+                } else {
+                    remote_addr.to_string()
+                };
+
+                if ip_cidr.match_ip(remote_addr, request.geo_location.as_ref()) {
                     let ip_traces = matcher.trace(request);
 
                     traces.push(Trace::new(
@@ -113,7 +123,7 @@ impl<T> IpMatcher<T> {
                         matcher.len() as u64,
                         ip_traces,
                         TraceInfo::Ip {
-                            request: remote_addr.to_string(),
+                            request: request_ip.clone(),
                             against: ip_cidr.to_string(),
                         },
                     ));
@@ -124,7 +134,7 @@ impl<T> IpMatcher<T> {
                         matcher.len() as u64,
                         Vec::new(),
                         TraceInfo::Ip {
-                            request: remote_addr.to_string(),
+                            request: request_ip.clone(),
                             against: ip_cidr.to_string(),
                         },
                     ))
@@ -135,10 +145,10 @@ impl<T> IpMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         let mut new_limit = self.no_matcher.cache(limit, level);
 
-        for matcher in self.matchers.values_mut() {
+        for matcher in self.matchers.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
@@ -154,6 +164,28 @@ impl<T> IpMatcher<T> {
     }
 }
 
+fn trace_geo_location(remote_addr: String, location: Option<&GeoIpLocation>) -> String {
+    let Some(location) = location else {
+        return format!("{remote_addr} (unknown location)");
+    };
+
+    let mut parts = Vec::new();
+
+    if let Some(country) = &location.country {
+        parts.push(format!("country: {country}"));
+    }
+
+    if let Some(continent) = &location.continent {
+        parts.push(format!("continent: {continent}"));
+    }
+
+    if let Some(asn) = location.asn {
+        parts.push(format!("asn: {asn}"));
+    }
+
+    format!("{remote_addr} ({})", parts.join(", "))
+}
+
 #[cfg(feature = "dot")]
 impl<V> DotBuilder for IpMatcher<V> {
     fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
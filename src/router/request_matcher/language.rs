@@ -0,0 +1,169 @@
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "dot")]
+use dot_graph::{Edge, Graph, Node};
+use imbl::OrdMap;
+
+use super::super::{
+    Route, RouteLanguage, RouterConfig, Trace,
+    request_matcher::{NestedMatcher, QueryParamMatcher},
+    trace::TraceInfo,
+};
+#[cfg(feature = "dot")]
+use crate::dot::DotBuilder;
+use crate::http::Request;
+
+#[derive(Debug)]
+pub struct LanguageMatcher<T> {
+    any_language: Arc<QueryParamMatcher<T>>,
+    condition_groups: OrdMap<RouteLanguage, Arc<QueryParamMatcher<T>>>,
+    count: usize,
+    config: Arc<RouterConfig>,
+}
+
+impl<T> Clone for LanguageMatcher<T> {
+    fn clone(&self) -> Self {
+        LanguageMatcher {
+            any_language: self.any_language.clone(),
+            condition_groups: self.condition_groups.clone(),
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<T> LanguageMatcher<T> {
+    pub fn new(config: Arc<RouterConfig>) -> Self {
+        LanguageMatcher {
+            any_language: Arc::new(QueryParamMatcher::new(config.clone())),
+            condition_groups: OrdMap::new(),
+            count: 0,
+            config,
+        }
+    }
+
+    pub fn insert(&mut self, route: Arc<Route<T>>) {
+        self.count += 1;
+
+        match route.language() {
+            None => Arc::make_mut(&mut self.any_language).insert(route),
+            Some(language) => {
+                if !self.condition_groups.contains_key(language) {
+                    self.condition_groups
+                        .insert(language.clone(), Arc::new(QueryParamMatcher::new(self.config.clone())));
+                }
+
+                let matcher = self.condition_groups.get_mut(language).unwrap();
+
+                Arc::make_mut(matcher).insert(route)
+            }
+        }
+    }
+
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.language() {
+            None => Arc::make_mut(&mut self.any_language).remove(route),
+            Some(language) => match self.condition_groups.get_mut(language) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.condition_groups.remove(language);
+                    }
+
+                    removed
+                }
+            },
+        };
+
+        if removed {
+            self.count -= 1;
+        }
+
+        removed
+    }
+
+    pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
+        let mut rules = self.any_language.match_request(request);
+        let mut negotiated_languages = HashMap::new();
+
+        for (language, matcher) in &self.condition_groups {
+            // Routes often share the same supported languages, only negotiate once for them
+            let negotiated = negotiated_languages
+                .entry((&language.supported, &language.default))
+                .or_insert_with(|| language.negotiate(request));
+
+            if language.match_language(negotiated.as_deref()) {
+                rules.extend(matcher.match_request(request));
+            }
+        }
+
+        rules
+    }
+
+    pub fn trace(&self, request: &Request) -> Vec<Trace<T>> {
+        let mut traces = self.any_language.trace(request);
+        let accept_language = request.header_value("Accept-Language");
+
+        for (language, matcher) in &self.condition_groups {
+            let negotiated = language.negotiate(request);
+            let matched = language.match_language(negotiated.as_deref());
+
+            traces.push(Trace::new(
+                matched,
+                true,
+                matcher.len() as u64,
+                if matched { matcher.trace(request) } else { Vec::new() },
+                TraceInfo::Language {
+                    request: accept_language.clone(),
+                    negotiated,
+                    against: language.to_string(),
+                },
+            ));
+        }
+
+        traces
+    }
+
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
+        let mut new_limit = self.any_language.cache(limit, level);
+
+        for matcher in self.condition_groups.values() {
+            new_limit = matcher.cache(new_limit, level);
+        }
+
+        new_limit
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+#[cfg(feature = "dot")]
+impl<V> DotBuilder for LanguageMatcher<V> {
+    fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
+        let node_name = format!("language_matcher_{}", id);
+        *id += 1;
+        graph.add_node(Node::new(&node_name).label("language matcher"));
+
+        if let Some(key) = self.any_language.graph(id, graph) {
+            graph.add_edge(Edge::new(&node_name, &key, "any language"));
+        }
+
+        for (language, matcher) in &self.condition_groups {
+            if let Some(key) = matcher.graph(id, graph) {
+                graph.add_edge(Edge::new(&node_name, &key, language.to_string().as_str()));
+            }
+        }
+
+        Some(node_name)
+    }
+}
@@ -1,31 +1,45 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::sync::Arc;
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
+use imbl::HashMap;
 
-use super::super::{Route, RouterConfig, Trace, request_matcher::HeaderMatcher, trace::TraceInfo};
+use super::super::{
+    Route, RouterConfig, Trace,
+    request_matcher::{HeaderMatcher, NestedMatcher},
+    trace::TraceInfo,
+};
 #[cfg(feature = "dot")]
 use crate::dot::DotBuilder;
 use crate::http::Request;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct MethodMatcher<T> {
-    methods: HashMap<String, HeaderMatcher<T>>,
-    exclude_methods: HashMap<Vec<String>, HeaderMatcher<T>>,
-    any_method: HeaderMatcher<T>,
+    methods: HashMap<String, Arc<HeaderMatcher<T>>>,
+    exclude_methods: HashMap<Vec<String>, Arc<HeaderMatcher<T>>>,
+    any_method: Arc<HeaderMatcher<T>>,
     count: usize,
     config: Arc<RouterConfig>,
 }
 
+impl<T> Clone for MethodMatcher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            methods: self.methods.clone(),
+            exclude_methods: self.exclude_methods.clone(),
+            any_method: self.any_method.clone(),
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
 impl<T> MethodMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         Self {
             methods: HashMap::new(),
             exclude_methods: HashMap::new(),
-            any_method: HeaderMatcher::new(config.clone()),
+            any_method: Arc::new(HeaderMatcher::new(config.clone())),
             count: 0,
             config,
         }
@@ -37,81 +51,76 @@ impl<T> MethodMatcher<T> {
         let config = self.config.clone();
 
         match route.methods() {
-            None => self.any_method.insert(route),
+            None => Arc::make_mut(&mut self.any_method).insert(route),
             Some(methods) => {
                 if methods.is_empty() {
-                    self.any_method.insert(route);
+                    Arc::make_mut(&mut self.any_method).insert(route);
                 } else {
                     if route.exclude_methods().is_some() {
-                        self.exclude_methods
+                        let matcher = self
+                            .exclude_methods
                             .entry(methods.clone())
-                            .or_insert_with(|| HeaderMatcher::new(config.clone()))
-                            .insert(route.clone());
+                            .or_insert_with(|| Arc::new(HeaderMatcher::new(config.clone())));
+
+                        Arc::make_mut(matcher).insert(route.clone());
 
                         return;
                     }
                     for method in methods {
                         if !self.methods.contains_key(method) {
-                            self.methods.insert(method.to_string(), HeaderMatcher::new(config.clone()));
+                            self.methods
+                                .insert(method.to_string(), Arc::new(HeaderMatcher::new(config.clone())));
                         }
 
-                        self.methods.get_mut(method).unwrap().insert(route.clone());
+                        Arc::make_mut(self.methods.get_mut(method).unwrap()).insert(route.clone());
                     }
                 }
             }
         }
     }
 
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        let mut removed = self.any_method.remove(id);
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.methods() {
+            Some(methods) if !methods.is_empty() && route.exclude_methods().is_some() => match self.exclude_methods.get_mut(methods) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.exclude_methods.remove(methods);
+                    }
 
-        if removed.is_some() {
-            self.count -= 1;
+                    removed
+                }
+            },
+            Some(methods) if !methods.is_empty() => {
+                let mut removed = false;
 
-            return removed;
-        }
+                for method in methods {
+                    if let Some(matcher) = self.methods.get_mut(method) {
+                        let matcher = Arc::make_mut(matcher);
+                        removed = matcher.remove(route) || removed;
 
-        self.methods.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
-            }
-
-            !matcher.is_empty()
-        });
+                        if matcher.is_empty() {
+                            self.methods.remove(method);
+                        }
+                    }
+                }
 
-        self.exclude_methods.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
+                removed
             }
+            _ => Arc::make_mut(&mut self.any_method).remove(route),
+        };
 
-            !matcher.is_empty()
-        });
-
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
         removed
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.any_method.batch_remove(ids);
-
-        self.methods.retain(|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.exclude_methods.retain(|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.any_method.is_empty() && self.methods.is_empty() && self.exclude_methods.is_empty()
-    }
-
     pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
         let mut routes = self.any_method.match_request(request);
 
@@ -207,14 +216,14 @@ impl<T> MethodMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         let mut new_limit = self.any_method.cache(limit, level);
 
-        for matcher in self.methods.values_mut() {
+        for matcher in self.methods.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
-        for matcher in self.exclude_methods.values_mut() {
+        for matcher in self.exclude_methods.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
@@ -1,15 +1,23 @@
+mod cookie;
 mod datetime;
 mod header;
 mod host;
 mod ip;
+mod language;
 mod method;
 mod path_and_query;
+mod query_param;
 mod scheme;
+mod value;
 
+pub use cookie::CookieMatcher;
 pub use datetime::{DateTimeCondition, DateTimeMatcher};
-pub use header::{HeaderMatcher, ValueCondition as HeaderValueCondition};
+pub use header::HeaderMatcher;
 pub use host::HostMatcher;
 pub use ip::IpMatcher;
+pub use language::LanguageMatcher;
 pub use method::MethodMatcher;
 pub use path_and_query::PathAndQueryMatcher;
+pub use query_param::QueryParamMatcher;
 pub use scheme::SchemeMatcher;
+pub use value::{NestedMatcher, ValueCondition, ValueMatcher, ValueSource};
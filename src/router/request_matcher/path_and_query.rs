@@ -1,7 +1,4 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::{collections::HashMap, sync::Arc};
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
@@ -15,18 +12,29 @@ use crate::{
     regex_radix_tree::{RegexTreeMap, Trace as TreeTrace},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct PathAndQueryMatcher<T> {
     regex_tree_rule: RegexTreeMap<Arc<Route<T>>>,
-    static_rules: HashMap<String, HashMap<String, Arc<Route<T>>>>,
+    /// Routes by id for each static path, a persistent map is used as it can hold most of the routes
+    static_rules: imbl::HashMap<String, HashMap<String, Arc<Route<T>>>>,
     count: usize,
 }
 
+impl<T> Clone for PathAndQueryMatcher<T> {
+    fn clone(&self) -> Self {
+        PathAndQueryMatcher {
+            regex_tree_rule: self.regex_tree_rule.clone(),
+            static_rules: self.static_rules.clone(),
+            count: self.count,
+        }
+    }
+}
+
 impl<T> PathAndQueryMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         PathAndQueryMatcher {
             regex_tree_rule: RegexTreeMap::new(config.ignore_path_and_query_case),
-            static_rules: HashMap::new(),
+            static_rules: imbl::HashMap::new(),
             count: 0,
         }
     }
@@ -51,41 +59,25 @@ impl<T> PathAndQueryMatcher<T> {
         }
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.static_rules.retain(|_, matcher| {
-            matcher.retain(|id, _| !ids.contains(id));
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.path_and_query() {
+            StaticOrDynamic::Static(path) => match self.static_rules.get_mut(path) {
+                None => false,
+                Some(routes) => {
+                    let removed = routes.remove(route.id()).is_some();
 
-            !matcher.is_empty()
-        });
-
-        self.regex_tree_rule.retain(&|id, _| !ids.contains(id));
-
-        self.static_rules.is_empty() && self.regex_tree_rule.is_empty()
-    }
-
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        match self.regex_tree_rule.remove(id) {
-            None => (),
-            Some(route) => {
-                self.count -= 1;
+                    if routes.is_empty() {
+                        self.static_rules.remove(path);
+                    }
 
-                return Some(route);
-            }
-        }
-
-        let mut removed = None;
-
-        self.static_rules.retain(|_, matcher| {
-            if removed.is_some() {
-                return true;
-            }
-
-            removed = matcher.remove(id);
-
-            !matcher.is_empty()
-        });
+                    removed
+                }
+            },
+            StaticOrDynamic::Dynamic(path) => self.regex_tree_rule.remove_by_regex(path.regex.as_str(), route.id()).is_some(),
+        };
 
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
@@ -149,7 +141,7 @@ impl<T> PathAndQueryMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         self.regex_tree_rule.cache(limit, Some(level))
     }
 
@@ -0,0 +1,43 @@
+use super::super::{
+    Route, RouteHeaderKind, RouterConfig,
+    request_matcher::{CookieMatcher, ValueCondition, ValueMatcher, ValueSource},
+    trace::{TraceInfo, TraceInfoValueCondition},
+};
+use crate::http::Request;
+
+pub type QueryParamMatcher<T> = ValueMatcher<T, QueryParams, CookieMatcher<T>>;
+
+#[derive(Debug)]
+pub struct QueryParams;
+
+impl ValueSource for QueryParams {
+    const LABEL: &'static str = "query param";
+
+    fn conditions<T>(route: &Route<T>) -> Vec<(&str, &RouteHeaderKind)> {
+        route
+            .query_params()
+            .iter()
+            .map(|query_param| (query_param.name.as_str(), &query_param.kind))
+            .collect()
+    }
+
+    fn ignore_case(config: &RouterConfig) -> bool {
+        config.ignore_path_and_query_case
+    }
+
+    fn name(name: &str, config: &RouterConfig) -> String {
+        if config.ignore_path_and_query_case {
+            name.to_lowercase()
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn match_condition(request: &Request, name: &str, condition: &ValueCondition, config: &RouterConfig) -> bool {
+        condition.match_values(request.query_param_values(name, config.ignore_path_and_query_case).as_slice())
+    }
+
+    fn trace_info<T>(conditions: Vec<TraceInfoValueCondition>) -> TraceInfo<T> {
+        TraceInfo::QueryParamGroup { conditions }
+    }
+}
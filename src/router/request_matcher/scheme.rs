@@ -1,29 +1,38 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
-};
+use std::sync::Arc;
 
 #[cfg(feature = "dot")]
 use dot_graph::{Edge, Graph, Node};
+use imbl::HashMap;
 
 use super::super::{HostMatcher, Route, RouterConfig, Trace, trace::TraceInfo};
 #[cfg(feature = "dot")]
 use crate::dot::DotBuilder;
 use crate::http::Request;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct SchemeMatcher<T> {
-    schemes: HashMap<String, HostMatcher<T>>,
-    any_scheme: HostMatcher<T>,
+    schemes: HashMap<String, Arc<HostMatcher<T>>>,
+    any_scheme: Arc<HostMatcher<T>>,
     count: usize,
     config: Arc<RouterConfig>,
 }
 
+impl<T> Clone for SchemeMatcher<T> {
+    fn clone(&self) -> Self {
+        SchemeMatcher {
+            schemes: self.schemes.clone(),
+            any_scheme: self.any_scheme.clone(),
+            count: self.count,
+            config: self.config.clone(),
+        }
+    }
+}
+
 impl<T> SchemeMatcher<T> {
     pub fn new(config: Arc<RouterConfig>) -> Self {
         SchemeMatcher {
             schemes: HashMap::new(),
-            any_scheme: HostMatcher::new(config.clone()),
+            any_scheme: Arc::new(HostMatcher::new(config.clone())),
             config,
             count: 0,
         }
@@ -33,57 +42,48 @@ impl<T> SchemeMatcher<T> {
         self.count += 1;
 
         match route.scheme() {
-            None => self.any_scheme.insert(route),
+            None => Arc::make_mut(&mut self.any_scheme).insert(route),
             Some(scheme) => {
                 if scheme.is_empty() {
-                    self.any_scheme.insert(route)
+                    Arc::make_mut(&mut self.any_scheme).insert(route)
                 } else {
                     if !self.schemes.contains_key(scheme) {
-                        self.schemes.insert(scheme.to_string(), HostMatcher::new(self.config.clone()));
+                        self.schemes
+                            .insert(scheme.to_string(), Arc::new(HostMatcher::new(self.config.clone())));
                     }
 
-                    self.schemes.get_mut(scheme).unwrap().insert(route);
+                    Arc::make_mut(self.schemes.get_mut(scheme).unwrap()).insert(route);
                 }
             }
         }
     }
 
-    pub fn remove(&mut self, id: &str) -> Option<Arc<Route<T>>> {
-        let mut removed = self.any_scheme.remove(id);
-
-        if removed.is_some() {
-            self.count -= 1;
-
-            return removed;
-        }
-
-        self.schemes.retain(|_, matcher| {
-            if let Some(value) = matcher.remove(id) {
-                removed = Some(value);
-            }
+    /// Remove a route, only the matchers where it has been inserted are modified
+    pub fn remove(&mut self, route: &Route<T>) -> bool {
+        let removed = match route.scheme() {
+            Some(scheme) if !scheme.is_empty() => match self.schemes.get_mut(scheme) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.schemes.remove(scheme);
+                    }
 
-            !matcher.is_empty()
-        });
+                    removed
+                }
+            },
+            _ => Arc::make_mut(&mut self.any_scheme).remove(route),
+        };
 
-        if removed.is_some() {
+        if removed {
             self.count -= 1;
         }
 
         removed
     }
 
-    pub fn batch_remove(&mut self, ids: &HashSet<String>) -> bool {
-        self.any_scheme.batch_remove(ids);
-
-        self.schemes.retain(|_, matcher| {
-            matcher.batch_remove(ids);
-
-            !matcher.is_empty()
-        });
-
-        self.any_scheme.is_empty() && self.schemes.is_empty()
-    }
-
     pub fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
         let mut routes = self.any_scheme.match_request(request);
 
@@ -147,10 +147,10 @@ impl<T> SchemeMatcher<T> {
         traces
     }
 
-    pub fn cache(&mut self, limit: u64, level: u64) -> u64 {
+    pub fn cache(&self, limit: u64, level: u64) -> u64 {
         let mut new_limit = self.any_scheme.cache(limit, level);
 
-        for matcher in self.schemes.values_mut() {
+        for matcher in self.schemes.values() {
             new_limit = matcher.cache(new_limit, level);
         }
 
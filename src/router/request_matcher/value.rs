@@ -0,0 +1,441 @@
+// Conditions are used as keys, LazyRegex only orders and hashes on its pattern, its compiled cache never changes the key
+#![allow(clippy::mutable_key_type)]
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+#[cfg(feature = "dot")]
+use dot_graph::{Edge, Graph, Node};
+use imbl::OrdMap;
+use serde::Serialize;
+
+use super::super::{
+    Route, RouteHeaderKind, RouterConfig, Trace,
+    request_matcher::{DateTimeMatcher, LanguageMatcher},
+    trace::{TraceInfo, TraceInfoValueCondition},
+};
+#[cfg(feature = "dot")]
+use crate::dot::DotBuilder;
+use crate::{http::Request, regex::LazyRegex};
+
+/// Where the named values of a request are read from, e.g. its headers, query params or cookies
+pub trait ValueSource {
+    /// Name of the source in the graph of the router, e.g. `header`
+    const LABEL: &'static str;
+
+    /// Conditions of the route on this source, as pairs of name and kind
+    fn conditions<T>(route: &Route<T>) -> Vec<(&str, &RouteHeaderKind)>;
+
+    /// Whether names and regexes of the conditions ignore the case
+    fn ignore_case(config: &RouterConfig) -> bool;
+
+    /// Name of a condition as it is compared against the request
+    fn name(name: &str, config: &RouterConfig) -> String;
+
+    fn match_condition(request: &Request, name: &str, condition: &ValueCondition, config: &RouterConfig) -> bool;
+
+    fn trace_info<T>(conditions: Vec<TraceInfoValueCondition>) -> TraceInfo<T>;
+}
+
+/// Matcher nested under a value matcher, where routes with the same conditions are inserted
+pub trait NestedMatcher<T>: Clone {
+    fn new(config: Arc<RouterConfig>) -> Self;
+
+    fn insert(&mut self, route: Arc<Route<T>>);
+
+    fn remove(&mut self, route: &Route<T>) -> bool;
+
+    fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>>;
+
+    fn trace(&self, request: &Request) -> Vec<Trace<T>>;
+
+    fn cache(&self, limit: u64, level: u64) -> u64;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool;
+}
+
+/// Group routes by their conditions on the named values of a source, each group is matched once per request and
+/// conditions shared by several groups are only executed once
+#[derive(Debug)]
+pub struct ValueMatcher<T, S, M> {
+    any_value: Arc<M>,
+    condition_groups: OrdMap<BTreeSet<ValueNamedCondition>, Arc<M>>,
+    count: usize,
+    config: Arc<RouterConfig>,
+    source: PhantomData<(T, S)>,
+}
+
+#[derive(Serialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type", content = "value")]
+pub enum ValueCondition {
+    IsDefined,
+    IsNotDefined,
+    IsEquals(String),
+    IsNotEqualTo(String),
+    Contains(String),
+    DoesNotContain(String),
+    EndsWith(String),
+    StartsWith(String),
+    MatchRegex(LazyRegex),
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ValueNamedCondition {
+    name: String,
+    condition: ValueCondition,
+}
+
+impl<T, S, M> Clone for ValueMatcher<T, S, M> {
+    fn clone(&self) -> Self {
+        ValueMatcher {
+            any_value: self.any_value.clone(),
+            condition_groups: self.condition_groups.clone(),
+            count: self.count,
+            config: self.config.clone(),
+            source: PhantomData,
+        }
+    }
+}
+
+impl<T, S: ValueSource, M: NestedMatcher<T>> NestedMatcher<T> for ValueMatcher<T, S, M> {
+    fn new(config: Arc<RouterConfig>) -> Self {
+        ValueMatcher {
+            any_value: Arc::new(M::new(config.clone())),
+            condition_groups: OrdMap::new(),
+            count: 0,
+            config,
+            source: PhantomData,
+        }
+    }
+
+    fn insert(&mut self, route: Arc<Route<T>>) {
+        self.count += 1;
+
+        let condition_group = self.condition_group(&route);
+
+        if condition_group.is_empty() {
+            Arc::make_mut(&mut self.any_value).insert(route);
+
+            return;
+        }
+
+        if !self.condition_groups.contains_key(&condition_group) {
+            self.condition_groups
+                .insert(condition_group.clone(), Arc::new(M::new(self.config.clone())));
+        }
+
+        let matcher = self.condition_groups.get_mut(&condition_group).unwrap();
+
+        Arc::make_mut(matcher).insert(route)
+    }
+
+    /// Remove a route, only the matchers where it has been inserted are modified
+    fn remove(&mut self, route: &Route<T>) -> bool {
+        let condition_group = self.condition_group(route);
+
+        let removed = if condition_group.is_empty() {
+            Arc::make_mut(&mut self.any_value).remove(route)
+        } else {
+            match self.condition_groups.get_mut(&condition_group) {
+                None => false,
+                Some(matcher) => {
+                    let matcher = Arc::make_mut(matcher);
+                    let removed = matcher.remove(route);
+
+                    if matcher.is_empty() {
+                        self.condition_groups.remove(&condition_group);
+                    }
+
+                    removed
+                }
+            }
+        };
+
+        if removed {
+            self.count -= 1;
+        }
+
+        removed
+    }
+
+    fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
+        let mut rules = self.any_value.match_request(request);
+        let mut execute_conditions = BTreeMap::new();
+
+        'group: for (conditions, matcher) in &self.condition_groups {
+            for condition in conditions {
+                match execute_conditions.get(condition) {
+                    None => {
+                        // Execute condition
+                        let result = self.match_condition(request, condition);
+
+                        // Save result
+                        execute_conditions.insert(condition.clone(), result);
+
+                        if !result {
+                            continue 'group;
+                        }
+                    }
+                    Some(result) => {
+                        if !result {
+                            continue 'group;
+                        }
+                    }
+                }
+            }
+
+            rules.extend(matcher.match_request(request));
+        }
+
+        rules
+    }
+
+    fn trace(&self, request: &Request) -> Vec<Trace<T>> {
+        let mut traces = self.any_value.trace(request);
+        let mut execute_conditions = BTreeMap::new();
+
+        for (conditions, matcher) in &self.condition_groups {
+            let mut matched = true;
+            let mut executed = true;
+            let mut traces_info_value = Vec::new();
+
+            for condition in conditions {
+                match execute_conditions.get(condition) {
+                    None => {
+                        // Execute condition
+                        let result = self.match_condition(request, condition);
+                        matched = matched && result;
+
+                        // Save result (only if executed to mimic cache behavior)
+                        if executed {
+                            execute_conditions.insert(condition.clone(), matched);
+                        }
+
+                        traces_info_value.push(TraceInfoValueCondition {
+                            result: if executed { Some(result) } else { None },
+                            name: condition.name.clone(),
+                            condition: condition.condition.clone(),
+                            cached: false,
+                        });
+
+                        executed = matched;
+                    }
+                    Some(result) => {
+                        matched = matched && *result;
+
+                        traces_info_value.push(TraceInfoValueCondition {
+                            result: if executed { Some(*result) } else { None },
+                            name: condition.name.clone(),
+                            condition: condition.condition.clone(),
+                            cached: true,
+                        });
+
+                        executed = matched;
+                    }
+                }
+            }
+
+            traces.push(Trace::new(
+                matched,
+                true,
+                matcher.len() as u64,
+                if matched { matcher.trace(request) } else { Vec::new() },
+                S::trace_info(traces_info_value),
+            ));
+        }
+
+        traces
+    }
+
+    fn cache(&self, limit: u64, level: u64) -> u64 {
+        let mut new_limit = self.any_value.cache(limit, level);
+
+        for (conditions, matcher) in &self.condition_groups {
+            for condition in conditions {
+                new_limit = condition.condition.cache(new_limit);
+            }
+
+            new_limit = matcher.cache(new_limit, level);
+        }
+
+        new_limit
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<T, S: ValueSource, M> ValueMatcher<T, S, M> {
+    fn condition_group(&self, route: &Route<T>) -> BTreeSet<ValueNamedCondition> {
+        let mut condition_group = BTreeSet::new();
+
+        for (name, kind) in S::conditions(route) {
+            let condition = match kind {
+                RouteHeaderKind::IsDefined => ValueCondition::IsDefined,
+                RouteHeaderKind::IsNotDefined => ValueCondition::IsNotDefined,
+                RouteHeaderKind::IsEquals(str) => ValueCondition::IsEquals(str.clone()),
+                RouteHeaderKind::IsNotEqualTo(str) => ValueCondition::IsNotEqualTo(str.clone()),
+                RouteHeaderKind::Contains(str) => ValueCondition::Contains(str.clone()),
+                RouteHeaderKind::DoesNotContain(str) => ValueCondition::DoesNotContain(str.clone()),
+                RouteHeaderKind::EndsWith(str) => ValueCondition::EndsWith(str.clone()),
+                RouteHeaderKind::StartsWith(str) => ValueCondition::StartsWith(str.clone()),
+                RouteHeaderKind::MatchRegex(marker) => {
+                    ValueCondition::MatchRegex(LazyRegex::new(marker.regex.clone(), S::ignore_case(&self.config)))
+                }
+            };
+
+            condition_group.insert(ValueNamedCondition {
+                name: S::name(name, &self.config),
+                condition,
+            });
+        }
+
+        condition_group
+    }
+
+    fn match_condition(&self, request: &Request, condition: &ValueNamedCondition) -> bool {
+        S::match_condition(request, condition.name.as_str(), &condition.condition, &self.config)
+    }
+}
+
+impl<T> NestedMatcher<T> for LanguageMatcher<T> {
+    fn new(config: Arc<RouterConfig>) -> Self {
+        LanguageMatcher::new(config)
+    }
+
+    fn insert(&mut self, route: Arc<Route<T>>) {
+        LanguageMatcher::insert(self, route)
+    }
+
+    fn remove(&mut self, route: &Route<T>) -> bool {
+        LanguageMatcher::remove(self, route)
+    }
+
+    fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
+        LanguageMatcher::match_request(self, request)
+    }
+
+    fn trace(&self, request: &Request) -> Vec<Trace<T>> {
+        LanguageMatcher::trace(self, request)
+    }
+
+    fn cache(&self, limit: u64, level: u64) -> u64 {
+        LanguageMatcher::cache(self, limit, level)
+    }
+
+    fn len(&self) -> usize {
+        LanguageMatcher::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        LanguageMatcher::is_empty(self)
+    }
+}
+
+impl<T> NestedMatcher<T> for DateTimeMatcher<T> {
+    fn new(config: Arc<RouterConfig>) -> Self {
+        DateTimeMatcher::new(config)
+    }
+
+    fn insert(&mut self, route: Arc<Route<T>>) {
+        DateTimeMatcher::insert(self, route)
+    }
+
+    fn remove(&mut self, route: &Route<T>) -> bool {
+        DateTimeMatcher::remove(self, route)
+    }
+
+    fn match_request(&self, request: &Request) -> Vec<Arc<Route<T>>> {
+        DateTimeMatcher::match_request(self, request)
+    }
+
+    fn trace(&self, request: &Request) -> Vec<Trace<T>> {
+        DateTimeMatcher::trace(self, request)
+    }
+
+    fn cache(&self, limit: u64, level: u64) -> u64 {
+        DateTimeMatcher::cache(self, limit, level)
+    }
+
+    fn len(&self) -> usize {
+        DateTimeMatcher::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        DateTimeMatcher::is_empty(self)
+    }
+}
+
+impl ValueCondition {
+    /// Match the condition against all the values found for a name, an empty list meaning the name is not defined
+    pub fn match_values<V: AsRef<str>>(&self, values: &[V]) -> bool {
+        match self {
+            ValueCondition::IsNotDefined => values.is_empty(),
+            ValueCondition::IsDefined => !values.is_empty(),
+            ValueCondition::IsEquals(str) => values.iter().any(|value| value.as_ref() == str),
+            ValueCondition::IsNotEqualTo(str) => values.iter().all(|value| value.as_ref() != str),
+            ValueCondition::Contains(str) => values.iter().any(|value| value.as_ref().contains(str.as_str())),
+            ValueCondition::DoesNotContain(str) => values.iter().all(|value| !value.as_ref().contains(str.as_str())),
+            ValueCondition::EndsWith(str) => values.iter().any(|value| value.as_ref().ends_with(str.as_str())),
+            ValueCondition::StartsWith(str) => values.iter().any(|value| value.as_ref().starts_with(str.as_str())),
+            ValueCondition::MatchRegex(regex) => values.iter().any(|value| regex.is_match(value.as_ref())),
+        }
+    }
+
+    pub fn cache(&self, limit: u64) -> u64 {
+        if limit == 0 {
+            return 0;
+        }
+
+        match self {
+            ValueCondition::MatchRegex(regex_string) if regex_string.cache() => limit - 1,
+            _ => limit,
+        }
+    }
+
+    pub fn format(&self) -> String {
+        match self {
+            ValueCondition::IsDefined => "is defined".to_string(),
+            ValueCondition::IsNotDefined => "is not defined".to_string(),
+            ValueCondition::IsEquals(str) => format!("equals {str}"),
+            ValueCondition::IsNotEqualTo(str) => format!("is not equal to {str}"),
+            ValueCondition::Contains(str) => format!("contains {str}"),
+            ValueCondition::DoesNotContain(str) => format!("does not contain {str}"),
+            ValueCondition::EndsWith(str) => format!("ends with {str}"),
+            ValueCondition::StartsWith(str) => format!("starts with {str}"),
+            ValueCondition::MatchRegex(str) => format!("match regex {str}"),
+        }
+    }
+}
+
+#[cfg(feature = "dot")]
+impl<T, S: ValueSource, M: DotBuilder> DotBuilder for ValueMatcher<T, S, M> {
+    fn graph(&self, id: &mut u32, graph: &mut Graph) -> Option<String> {
+        let node_name = format!("{}_matcher_{}", S::LABEL.replace(' ', "_"), id);
+        *id += 1;
+        graph.add_node(Node::new(&node_name).label(format!("{} matcher", S::LABEL).as_str()));
+
+        if let Some(key) = self.any_value.graph(id, graph) {
+            graph.add_edge(Edge::new(&node_name, &key, format!("any {}", S::LABEL).as_str()));
+        }
+
+        for (conditions, matcher) in &self.condition_groups {
+            if let Some(key) = matcher.graph(id, graph) {
+                graph.add_edge(Edge::new(&node_name, &key, format!("{} group {:?}", S::LABEL, conditions).as_str()));
+            }
+        }
+
+        Some(node_name)
+    }
+}
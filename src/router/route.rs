@@ -2,16 +2,24 @@
 use std::sync::Arc;
 use std::{cmp::Ordering, collections::HashMap, fmt::Debug};
 
+use chrono::{DateTime, Duration, Utc};
 #[cfg(feature = "dot")]
 use dot_graph::{Graph, Node as GraphNode};
-use serde::Serialize;
-
-use super::{RouteHeader, route_datetime::RouteDateTime, route_ip::RouteIp, route_time::RouteTime, route_weekday::RouteWeekday};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    RouteCookie, RouteHeader, RouteLanguage, RouteQueryParam,
+    route_datetime::RouteDateTime,
+    route_ip::RouteIp,
+    route_schedule::{MAX_SEARCH_DAYS, RouteSchedule},
+    route_time::RouteTime,
+    route_weekday::RouteWeekday,
+};
 #[cfg(feature = "dot")]
 use crate::dot::DotBuilder;
 use crate::{http::Request, marker::StaticOrDynamic, router::RouterConfig};
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Route<T> {
     handler: T,
     scheme: Option<String>,
@@ -20,10 +28,14 @@ pub struct Route<T> {
     exclude_methods: Option<bool>,
     path_and_query: StaticOrDynamic,
     headers: Vec<RouteHeader>,
+    query_params: Vec<RouteQueryParam>,
+    cookies: Vec<RouteCookie>,
+    language: Option<RouteLanguage>,
     ips: Option<Vec<RouteIp>>,
     datetime: Option<Vec<RouteDateTime>>,
     time: Option<Vec<RouteTime>>,
     weekdays: Option<RouteWeekday>,
+    schedules: Option<Vec<RouteSchedule>>,
     id: String,
     priority: i64,
 }
@@ -37,10 +49,14 @@ impl<T> Route<T> {
         host: Option<StaticOrDynamic>,
         path_and_query: StaticOrDynamic,
         headers: Vec<RouteHeader>,
+        query_params: Vec<RouteQueryParam>,
+        cookies: Vec<RouteCookie>,
+        language: Option<RouteLanguage>,
         ips: Option<Vec<RouteIp>>,
         datetime: Option<Vec<RouteDateTime>>,
         time: Option<Vec<RouteTime>>,
         weekdays: Option<RouteWeekday>,
+        schedules: Option<Vec<RouteSchedule>>,
         id: String,
         priority: i64,
         handler: T,
@@ -53,10 +69,14 @@ impl<T> Route<T> {
             exclude_methods,
             path_and_query,
             headers,
+            query_params,
+            cookies,
+            language,
             ips,
             datetime,
             time,
             weekdays,
+            schedules,
             id,
             priority,
         }
@@ -78,6 +98,18 @@ impl<T> Route<T> {
         self.headers.as_ref()
     }
 
+    pub fn query_params(&self) -> &Vec<RouteQueryParam> {
+        self.query_params.as_ref()
+    }
+
+    pub fn cookies(&self) -> &Vec<RouteCookie> {
+        self.cookies.as_ref()
+    }
+
+    pub fn language(&self) -> Option<&RouteLanguage> {
+        self.language.as_ref()
+    }
+
     pub fn methods(&self) -> Option<&Vec<String>> {
         self.methods.as_ref()
     }
@@ -110,6 +142,75 @@ impl<T> Route<T> {
         self.weekdays.as_ref()
     }
 
+    pub fn schedules(&self) -> Option<&Vec<RouteSchedule>> {
+        self.schedules.as_ref()
+    }
+
+    /// Next time the route becomes active after the given datetime: one of its schedules is active and its datetime,
+    /// time and weekdays conditions hold, while it was not the minute before
+    pub fn next_activation(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedules = self.schedules.as_ref()?;
+
+        if self
+            .datetime
+            .as_ref()
+            .is_some_and(|ranges| ranges.iter().all(|range| range.ended(after)))
+        {
+            return None;
+        }
+
+        let limit = *after + Duration::days(MAX_SEARCH_DAYS);
+        let mut after = *after;
+        let mut schedule_activations = schedules
+            .iter()
+            .map(|schedule| schedule.next_activation(&after))
+            .collect::<Vec<_>>();
+
+        // The route can only become active when one of its conditions does, so only those datetimes are checked
+        while after < limit {
+            for (schedule, activation) in schedules.iter().zip(schedule_activations.iter_mut()) {
+                if activation.is_some_and(|activation| activation <= after) {
+                    *activation = schedule.next_activation(&after);
+                }
+            }
+
+            // Schedules are never active again
+            if schedule_activations.iter().all(Option::is_none) && !schedules.iter().any(|schedule| schedule.match_datetime(&after)) {
+                return None;
+            }
+
+            let activation = schedule_activations
+                .iter()
+                .flatten()
+                .copied()
+                .chain(self.datetime.iter().flatten().filter_map(|range| range.next_start(&after)))
+                .chain(self.time.iter().flatten().filter_map(|range| range.next_start(&after)))
+                .chain(self.weekdays.iter().filter_map(|weekdays| weekdays.next_start(&after)))
+                .min()?;
+
+            if self.match_time_conditions(&activation) && !self.match_time_conditions(&(activation - Duration::minutes(1))) {
+                return Some(activation);
+            }
+
+            after = activation;
+        }
+
+        None
+    }
+
+    fn match_time_conditions(&self, datetime: &DateTime<Utc>) -> bool {
+        self.schedules.iter().flatten().any(|schedule| schedule.match_datetime(datetime))
+            && self
+                .datetime
+                .as_ref()
+                .is_none_or(|ranges| ranges.iter().any(|range| range.match_datetime(datetime)))
+            && self
+                .time
+                .as_ref()
+                .is_none_or(|ranges| ranges.iter().any(|range| range.match_datetime(datetime)))
+            && self.weekdays.as_ref().is_none_or(|weekdays| weekdays.match_datetime(datetime))
+    }
+
     pub fn id(&self) -> &str {
         self.id.as_str()
     }
@@ -134,6 +235,18 @@ impl<T> Route<T> {
             }
         }
 
+        for query_param in self.query_params() {
+            for value in request.query_param_values(query_param.name.as_str(), false) {
+                parameters.extend(query_param.capture(value.as_str()));
+            }
+        }
+
+        for cookie in self.cookies() {
+            for value in request.cookie_values(cookie.name.as_str(), false) {
+                parameters.extend(cookie.capture(value.as_str()));
+            }
+        }
+
         parameters
     }
 
@@ -3,38 +3,57 @@ use std::fmt::Display;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::RouteTimezone;
+use crate::Error;
+
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
 pub struct RouteDateTime {
     pub start: Option<NaiveDateTime>,
     pub end: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<RouteTimezone>,
 }
 
 impl RouteDateTime {
-    pub fn from_range(start: &Option<String>, end: &Option<String>) -> RouteDateTime {
-        let mut route_start = None;
-        let mut route_end = None;
-        match start {
-            None => (),
-            Some(datetime) => match datetime.parse::<DateTime<Utc>>() {
-                Ok(dt) => route_start = Some(dt.naive_utc()),
-                Err(err) => {
-                    log::error!("cannot parse datetime {datetime}: {err}");
-                }
-            },
+    /// An invalid bound is ignored, the other one is kept, it is reported by the validation of the rule
+    pub fn from_range(start: &Option<String>, end: &Option<String>, timezone: Option<RouteTimezone>) -> RouteDateTime {
+        RouteDateTime {
+            start: Self::parse_bound(start.as_deref(), timezone.as_ref()),
+            end: Self::parse_bound(end.as_deref(), timezone.as_ref()),
+            timezone,
         }
-        match end {
-            None => (),
-            Some(datetime) => match datetime.parse::<DateTime<Utc>>() {
-                Ok(dt) => route_end = Some(dt.naive_utc()),
-                Err(err) => {
-                    log::error!("cannot parse datetime {datetime}: {err}");
-                }
-            },
+    }
+
+    fn parse_bound(datetime: Option<&str>, timezone: Option<&RouteTimezone>) -> Option<NaiveDateTime> {
+        match Self::parse_datetime(datetime, timezone) {
+            Ok(datetime) => datetime,
+            Err(err) => {
+                log::error!("ignoring bound of datetime range: {err}");
+
+                None
+            }
         }
+    }
 
-        RouteDateTime {
-            start: route_start,
-            end: route_end,
+    // Datetimes without offset are local to the timezone, or utc when there is none
+    fn parse_datetime(datetime: Option<&str>, timezone: Option<&RouteTimezone>) -> Result<Option<NaiveDateTime>, Error> {
+        let Some(datetime) = datetime else {
+            return Ok(None);
+        };
+
+        if let Ok(dt) = datetime.parse::<DateTime<Utc>>() {
+            return Ok(Some(dt.naive_utc()));
+        }
+
+        match datetime.parse::<NaiveDateTime>() {
+            Ok(local) => Ok(match timezone {
+                Some(timezone) => timezone.to_utc(&local),
+                None => Some(local),
+            }),
+            Err(source) => Err(Error::InvalidDateTime {
+                value: datetime.to_string(),
+                source,
+            }),
         }
     }
 
@@ -51,6 +70,23 @@ impl RouteDateTime {
             },
         }
     }
+
+    /// Start of the range when it is after the given datetime
+    pub fn next_start(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.start.map(|start| start.and_utc()).filter(|start| start > after)
+    }
+
+    /// Whether the range ends before the given datetime, so it never matches again
+    pub fn ended(&self, at: &DateTime<Utc>) -> bool {
+        self.end.is_some_and(|end| end <= at.naive_utc())
+    }
+
+    fn format(&self, datetime: &NaiveDateTime) -> String {
+        match &self.timezone {
+            Some(timezone) => timezone.local(&datetime.and_utc()).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            None => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
 }
 
 impl Display for RouteDateTime {
@@ -58,13 +94,17 @@ impl Display for RouteDateTime {
         let str = match self.start {
             None => match self.end {
                 None => "always".to_string(),
-                Some(end) => format!("before({})", end.format("%Y-%m-%d %H:%M:%S")),
+                Some(end) => format!("before({})", self.format(&end)),
             },
             Some(start) => match self.end {
-                None => format!("after({})", start.format("%Y-%m-%d %H:%M:%S")),
-                Some(end) => format!("in({}, {})", start.format("%Y-%m-%d %H:%M:%S"), end.format("%Y-%m-%d %H:%M:%S")),
+                None => format!("after({})", self.format(&start)),
+                Some(end) => format!("in({}, {})", self.format(&start), self.format(&end)),
             },
         };
-        write!(f, "{str}")
+
+        match &self.timezone {
+            Some(timezone) => write!(f, "{str} ({timezone})"),
+            None => write!(f, "{str}"),
+        }
     }
 }
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::marker::MarkerString;
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RouteHeaderKind {
     IsDefined,
     IsNotDefined,
@@ -17,7 +17,7 @@ pub enum RouteHeaderKind {
     MatchRegex(MarkerString),
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RouteHeader {
     pub kind: RouteHeaderKind,
     pub name: String,
@@ -3,21 +3,39 @@ use std::{fmt::Display, net::IpAddr};
 use cidr::AnyIpCidr;
 use serde::{Deserialize, Serialize};
 
+use crate::geoip::GeoIpLocation;
+
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq)]
 pub enum RouteIp {
     InRange(AnyIpCidr),
     NotInRange(AnyIpCidr),
     NotOneOf(Vec<IpAddr>),
+    InCountries(Vec<String>),
+    NotInCountries(Vec<String>),
+    InContinents(Vec<String>),
+    NotInContinents(Vec<String>),
+    InAsns(Vec<u32>),
+    NotInAsns(Vec<u32>),
 }
 
 impl RouteIp {
-    pub fn match_ip(&self, ip: &IpAddr) -> bool {
+    pub fn match_ip(&self, ip: &IpAddr, location: Option<&GeoIpLocation>) -> bool {
         match self {
             Self::InRange(in_range) => in_range.contains(ip),
             Self::NotInRange(not_in_range) => !not_in_range.contains(ip),
             Self::NotOneOf(disallowed_ips) => !disallowed_ips.contains(ip),
+            Self::InCountries(countries) => location.and_then(|l| l.country.as_ref()).is_some_and(|c| countries.contains(c)),
+            Self::NotInCountries(countries) => !location.and_then(|l| l.country.as_ref()).is_some_and(|c| countries.contains(c)),
+            Self::InContinents(continents) => location.and_then(|l| l.continent.as_ref()).is_some_and(|c| continents.contains(c)),
+            Self::NotInContinents(continents) => !location.and_then(|l| l.continent.as_ref()).is_some_and(|c| continents.contains(c)),
+            Self::InAsns(asns) => location.and_then(|l| l.asn).is_some_and(|asn| asns.contains(&asn)),
+            Self::NotInAsns(asns) => !location.and_then(|l| l.asn).is_some_and(|asn| asns.contains(&asn)),
         }
     }
+
+    pub fn is_geo(&self) -> bool {
+        !matches!(self, Self::InRange(_) | Self::NotInRange(_) | Self::NotOneOf(_))
+    }
 }
 
 impl Display for RouteIp {
@@ -29,6 +47,18 @@ impl Display for RouteIp {
                 let ips = list.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
                 format!("not_one_of([{ips}])")
             }
+            Self::InCountries(list) => format!("in_countries([{}])", list.join(", ")),
+            Self::NotInCountries(list) => format!("not_in_countries([{}])", list.join(", ")),
+            Self::InContinents(list) => format!("in_continents([{}])", list.join(", ")),
+            Self::NotInContinents(list) => format!("not_in_continents([{}])", list.join(", ")),
+            Self::InAsns(list) => {
+                let asns = list.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                format!("in_asns([{asns}])")
+            }
+            Self::NotInAsns(list) => {
+                let asns = list.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                format!("not_in_asns([{asns}])")
+            }
         };
         write!(f, "{str}")
     }
@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::Request;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RouteLanguage {
+    pub supported: Vec<String>,
+    pub matches: Vec<String>,
+    pub default: Option<String>,
+}
+
+impl RouteLanguage {
+    pub fn negotiate(&self, request: &Request) -> Option<String> {
+        request.preferred_language(&self.supported).or_else(|| self.default.clone())
+    }
+
+    pub fn match_language(&self, language: Option<&str>) -> bool {
+        match language {
+            None => false,
+            Some(language) => self.matches.is_empty() || self.matches.iter().any(|m| m.eq_ignore_ascii_case(language)),
+        }
+    }
+}
+
+impl Display for RouteLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "negotiate([{}])", self.supported.join(", "))?;
+
+        if !self.matches.is_empty() {
+            write!(f, " in([{}])", self.matches.join(", "))?;
+        }
+
+        if let Some(default) = &self.default {
+            write!(f, " default({default})")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::RouteHeaderKind;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RouteQueryParam {
+    pub kind: RouteHeaderKind,
+    pub name: String,
+}
+
+impl RouteQueryParam {
+    pub fn capture(&self, str: &str) -> HashMap<String, String> {
+        match &self.kind {
+            RouteHeaderKind::MatchRegex(marker_string) => marker_string.capture(str),
+            _ => HashMap::new(),
+        }
+    }
+}
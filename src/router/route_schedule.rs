@@ -0,0 +1,413 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::RouteTimezone;
+
+// Every day of the 28 years cycle of the gregorian calendar is checked before giving up on finding a next activation
+pub(super) const MAX_SEARCH_DAYS: i64 = 366 * 28;
+const ALL_MINUTES: u64 = (1 << 60) - 1;
+const ALL_HOURS: u32 = (1 << 24) - 1;
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Recurring schedule, written as a cron expression `minute hour day-of-month month day-of-week`
+///
+/// Fields accept `*`, lists (`1,15`), ranges (`20-31`), steps (`*/15`, `0-30/10`) and month or day names (`dec`,
+/// `mon`). The day of month also accepts `L` for the last day of the month, and the day of week `day#n` for the
+/// n-th day of the month (`mon#1`). Unlike cron, a minute is in the schedule only when all the fields match it.
+#[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(try_from = "RouteScheduleDefinition", into = "RouteScheduleDefinition")]
+pub struct RouteSchedule {
+    expression: String,
+    timezone: Option<RouteTimezone>,
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    last_day: bool,
+    months: u16,
+    weekdays: u8,
+    nth_weekdays: Vec<(u8, u8)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RouteScheduleDefinition {
+    expression: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    timezone: Option<RouteTimezone>,
+}
+
+impl RouteSchedule {
+    pub fn parse(expression: &str, timezone: Option<RouteTimezone>) -> Result<RouteSchedule, String> {
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(format!("expected 5 fields, got {}", fields.len()));
+        };
+
+        let (days, last_day) = match day.split(',').any(|item| item.eq_ignore_ascii_case("l")) {
+            false => (parse_field(day, 1, 31, &[])? as u32, false),
+            true => {
+                let others = day.split(',').filter(|item| !item.eq_ignore_ascii_case("l")).collect::<Vec<_>>();
+
+                if others.is_empty() {
+                    (0, true)
+                } else {
+                    (parse_field(others.join(",").as_str(), 1, 31, &[])? as u32, true)
+                }
+            }
+        };
+
+        let mut weekdays = 0;
+        let mut nth_weekdays = Vec::new();
+
+        for item in weekday.split(',') {
+            match item.split_once('#') {
+                None => weekdays |= parse_field(item, 0, 7, &WEEKDAY_NAMES)?,
+                Some((day, nth)) => {
+                    let day = parse_value(day, 0, 7, &WEEKDAY_NAMES)? % 7;
+                    let nth = parse_value(nth, 1, 5, &[])?;
+
+                    nth_weekdays.push((day as u8, nth as u8));
+                }
+            }
+        }
+
+        // 7 is also sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        nth_weekdays.sort();
+        nth_weekdays.dedup();
+
+        Ok(RouteSchedule {
+            expression: fields.join(" "),
+            timezone,
+            minutes: parse_field(minute, 0, 59, &[])?,
+            hours: parse_field(hour, 0, 23, &[])? as u32,
+            days,
+            last_day,
+            months: parse_field(month, 1, 12, &MONTH_NAMES)? as u16,
+            weekdays: weekdays as u8,
+            nth_weekdays,
+        })
+    }
+
+    pub fn from_expression(expression: &str, timezone: Option<RouteTimezone>) -> Option<RouteSchedule> {
+        match Self::parse(expression, timezone) {
+            Ok(schedule) => Some(schedule),
+            Err(err) => {
+                log::error!("cannot parse schedule {expression}: {err}");
+
+                None
+            }
+        }
+    }
+
+    pub fn timezone(&self) -> Option<RouteTimezone> {
+        self.timezone
+    }
+
+    pub fn match_datetime(&self, datetime: &DateTime<Utc>) -> bool {
+        self.match_local(&self.local(datetime))
+    }
+
+    /// First minute after `after` where the schedule becomes active, minutes where the schedule was already
+    /// active the minute before are skipped
+    pub fn next_activation(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start_day = self.local(after).date();
+        let all_day = self.hours == ALL_HOURS && self.minutes == ALL_MINUTES;
+        // Only the first minute of each run of consecutive minutes can be an activation
+        let first_minutes = self.minutes & !(self.minutes << 1);
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let day = start_day + Duration::days(offset);
+
+            if !self.match_date(&day) {
+                continue;
+            }
+
+            // A schedule active all day long can only become active at midnight, when it was not active the day before
+            if all_day && day.pred_opt().is_some_and(|previous_day| self.match_date(&previous_day)) {
+                continue;
+            }
+
+            for hour in (0..24).filter(|hour| self.hours & (1 << hour) != 0) {
+                for minute in (0..60).filter(|minute| first_minutes & (1 << minute) != 0) {
+                    let Some(local) = NaiveTime::from_hms_opt(hour, minute, 0).map(|time| day.and_time(time)) else {
+                        continue;
+                    };
+
+                    let Some(activation) = self.utc(&local) else {
+                        continue;
+                    };
+
+                    if activation <= *after || self.match_local(&(local - Duration::minutes(1))) {
+                        continue;
+                    }
+
+                    return Some(activation);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// First minute after `after` where at least one of the schedules becomes active while none of them was active
+    /// the minute before, so adjacent or overlapping schedules only activate once
+    pub fn next_activation_of_all(schedules: &[RouteSchedule], after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = *after + Duration::days(MAX_SEARCH_DAYS);
+        let mut after = *after;
+
+        while after < limit {
+            let activation = schedules.iter().filter_map(|schedule| schedule.next_activation(&after)).min()?;
+            let previous_minute = activation - Duration::minutes(1);
+
+            if !schedules.iter().any(|schedule| schedule.match_datetime(&previous_minute)) {
+                return Some(activation);
+            }
+
+            after = activation;
+        }
+
+        None
+    }
+
+    fn local(&self, datetime: &DateTime<Utc>) -> NaiveDateTime {
+        match &self.timezone {
+            Some(timezone) => timezone.local(datetime).naive_local(),
+            None => datetime.naive_utc(),
+        }
+    }
+
+    fn utc(&self, local: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        match &self.timezone {
+            Some(timezone) => timezone.to_utc(local).map(|datetime| datetime.and_utc()),
+            None => Some(local.and_utc()),
+        }
+    }
+
+    fn match_local(&self, local: &NaiveDateTime) -> bool {
+        self.minutes & (1 << local.minute()) != 0 && self.hours & (1 << local.hour()) != 0 && self.match_date(&local.date())
+    }
+
+    fn match_date(&self, date: &NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let is_last_day = (*date + Duration::days(1)).month() != date.month();
+
+        if self.days & (1 << date.day()) == 0 && !(self.last_day && is_last_day) {
+            return false;
+        }
+
+        let weekday = date.weekday().num_days_from_sunday() as u8;
+        let nth = ((date.day() - 1) / 7 + 1) as u8;
+
+        self.weekdays & (1 << weekday) != 0 || self.nth_weekdays.contains(&(weekday, nth))
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            None => (item, 1),
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step \"{step}\"")),
+            },
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            None if step > 1 => (parse_value(range, min, max, names)?, max),
+            None => {
+                let value = parse_value(range, min, max, names)?;
+
+                (value, value)
+            }
+            Some((start, end)) => (parse_value(start, min, max, names)?, parse_value(end, min, max, names)?),
+        };
+
+        if start > end {
+            return Err(format!("invalid range \"{range}\""));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let lowercase_value = value.to_lowercase();
+
+    // Names are indexed from the min value: months start at 1 and weekdays at 0
+    if let Some(index) = names.iter().position(|name| *name == lowercase_value) {
+        return Ok(index as u32 + min);
+    }
+
+    match value.parse::<u32>() {
+        Ok(number) if (min..=max).contains(&number) => Ok(number),
+        _ => Err(format!("invalid value \"{value}\", expected a value between {min} and {max}")),
+    }
+}
+
+impl TryFrom<RouteScheduleDefinition> for RouteSchedule {
+    type Error = String;
+
+    fn try_from(definition: RouteScheduleDefinition) -> Result<Self, Self::Error> {
+        Self::parse(definition.expression.as_str(), definition.timezone)
+    }
+}
+
+impl From<RouteSchedule> for RouteScheduleDefinition {
+    fn from(schedule: RouteSchedule) -> Self {
+        RouteScheduleDefinition {
+            expression: schedule.expression,
+            timezone: schedule.timezone,
+        }
+    }
+}
+
+impl Display for RouteSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.timezone {
+            Some(timezone) => write!(f, "schedule({}) ({timezone})", self.expression),
+            None => write!(f, "schedule({})", self.expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(datetime: &str) -> DateTime<Utc> {
+        datetime.parse::<DateTime<Utc>>().unwrap()
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(RouteSchedule::parse("* * * *", None).is_err());
+        assert!(RouteSchedule::parse("60 * * * *", None).is_err());
+        assert!(RouteSchedule::parse("* * 31-20 * *", None).is_err());
+        assert!(RouteSchedule::parse("* * * foo *", None).is_err());
+        assert!(RouteSchedule::parse("*/0 * * * *", None).is_err());
+        assert!(RouteSchedule::parse("* * * * mon#6", None).is_err());
+    }
+
+    #[test]
+    fn test_match_december_range() {
+        let schedule = RouteSchedule::parse("* * 20-31 dec *", None).unwrap();
+
+        assert!(schedule.match_datetime(&utc("2024-12-20T00:00:00Z")));
+        assert!(schedule.match_datetime(&utc("2024-12-31T23:59:00Z")));
+        assert!(!schedule.match_datetime(&utc("2024-12-19T23:59:00Z")));
+        assert!(!schedule.match_datetime(&utc("2024-11-25T10:00:00Z")));
+    }
+
+    #[test]
+    fn test_match_first_monday() {
+        let schedule = RouteSchedule::parse("* 9-17 * * mon#1", None).unwrap();
+        let same = RouteSchedule::parse("* 9-17 1-7 * 1", None).unwrap();
+
+        for (datetime, expected) in [
+            ("2024-07-01T10:00:00Z", true),
+            ("2024-07-01T18:00:00Z", false),
+            ("2024-07-08T10:00:00Z", false),
+            ("2024-07-02T10:00:00Z", false),
+        ] {
+            assert_eq!(schedule.match_datetime(&utc(datetime)), expected, "{datetime}");
+            assert_eq!(same.match_datetime(&utc(datetime)), expected, "{datetime}");
+        }
+    }
+
+    #[test]
+    fn test_match_last_day() {
+        let schedule = RouteSchedule::parse("*/15 * L * *", None).unwrap();
+
+        assert!(schedule.match_datetime(&utc("2024-02-29T12:30:00Z")));
+        assert!(!schedule.match_datetime(&utc("2024-02-29T12:31:00Z")));
+        assert!(!schedule.match_datetime(&utc("2024-02-28T12:30:00Z")));
+    }
+
+    #[test]
+    fn test_next_activation() {
+        let schedule = RouteSchedule::parse("* * 20-31 dec *", None).unwrap();
+
+        assert_eq!(
+            schedule.next_activation(&utc("2024-07-01T10:00:00Z")),
+            Some(utc("2024-12-20T00:00:00Z"))
+        );
+        assert_eq!(
+            schedule.next_activation(&utc("2024-12-22T10:00:00Z")),
+            Some(utc("2025-12-20T00:00:00Z"))
+        );
+
+        let schedule = RouteSchedule::parse("0 9 * * mon#1", RouteTimezone::parse("Europe/Paris")).unwrap();
+
+        assert_eq!(
+            schedule.next_activation(&utc("2024-07-01T10:00:00Z")),
+            Some(utc("2024-08-05T07:00:00Z"))
+        );
+
+        let schedule = RouteSchedule::parse("0 0 29 feb *", None).unwrap();
+
+        assert_eq!(
+            schedule.next_activation(&utc("2024-03-01T00:00:00Z")),
+            Some(utc("2028-02-29T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_next_activation_all_day() {
+        let schedule = RouteSchedule::parse("* * * * *", None).unwrap();
+
+        assert_eq!(schedule.next_activation(&utc("2024-07-01T10:00:00Z")), None);
+
+        let schedule = RouteSchedule::parse("* * * * sat,sun", None).unwrap();
+
+        assert_eq!(
+            schedule.next_activation(&utc("2024-07-01T10:00:00Z")),
+            Some(utc("2024-07-06T00:00:00Z"))
+        );
+        assert_eq!(
+            schedule.next_activation(&utc("2024-07-06T10:00:00Z")),
+            Some(utc("2024-07-13T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_next_activation_of_adjacent_schedules() {
+        let schedules = [
+            RouteSchedule::parse("* 8-11 * * *", None).unwrap(),
+            RouteSchedule::parse("* 12-17 * * *", None).unwrap(),
+            RouteSchedule::parse("* 16-19 * * *", None).unwrap(),
+        ];
+
+        assert_eq!(
+            RouteSchedule::next_activation_of_all(&schedules, &utc("2024-07-01T09:00:00Z")),
+            Some(utc("2024-07-02T08:00:00Z"))
+        );
+
+        let schedules = [
+            RouteSchedule::parse("* 0-11 * * *", None).unwrap(),
+            RouteSchedule::parse("* 12-23 * * *", None).unwrap(),
+        ];
+
+        assert_eq!(
+            RouteSchedule::next_activation_of_all(&schedules, &utc("2024-07-01T09:00:00Z")),
+            None
+        );
+    }
+}
@@ -1,45 +1,55 @@
 use std::fmt::Display;
 
-use chrono::{DateTime, NaiveTime, Utc};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::RouteTimezone;
+use crate::Error;
+
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
 pub struct RouteTime {
     pub start: Option<NaiveTime>,
     pub end: Option<NaiveTime>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<RouteTimezone>,
 }
 
 impl RouteTime {
-    pub fn from_range(start: &Option<String>, end: &Option<String>) -> RouteTime {
-        let mut route_start = None;
-        let mut route_end = None;
-        match start {
-            None => (),
-            Some(time) => match time.parse::<NaiveTime>() {
-                Ok(dt) => route_start = Some(dt),
-                Err(err) => {
-                    log::error!("cannot parse time {time}: {err}");
-                }
-            },
+    /// An invalid bound is ignored, the other one is kept, it is reported by the validation of the rule
+    pub fn from_range(start: &Option<String>, end: &Option<String>, timezone: Option<RouteTimezone>) -> RouteTime {
+        RouteTime {
+            start: Self::parse_bound(start.as_deref()),
+            end: Self::parse_bound(end.as_deref()),
+            timezone,
         }
-        match end {
-            None => (),
-            Some(time) => match time.parse::<NaiveTime>() {
-                Ok(dt) => route_end = Some(dt),
-                Err(err) => {
-                    log::error!("cannot parse time {time}: {err}");
-                }
-            },
+    }
+
+    fn parse_bound(time: Option<&str>) -> Option<NaiveTime> {
+        match Self::parse_time(time) {
+            Ok(time) => time,
+            Err(err) => {
+                log::error!("ignoring bound of time range: {err}");
+
+                None
+            }
         }
+    }
 
-        RouteTime {
-            start: route_start,
-            end: route_end,
+    fn parse_time(time: Option<&str>) -> Result<Option<NaiveTime>, Error> {
+        match time {
+            None => Ok(None),
+            Some(time) => time.parse::<NaiveTime>().map(Some).map_err(|source| Error::InvalidDateTime {
+                value: time.to_string(),
+                source,
+            }),
         }
     }
 
     pub fn match_datetime(&self, datetime: &DateTime<Utc>) -> bool {
-        let naive_time = datetime.naive_utc().time();
+        let naive_time = match &self.timezone {
+            Some(timezone) => timezone.local(datetime).time(),
+            None => datetime.naive_utc().time(),
+        };
         match self.start {
             None => match self.end {
                 None => true,
@@ -51,6 +61,27 @@ impl RouteTime {
             },
         }
     }
+
+    /// Next time the range starts after the given datetime, a range without start starts at midnight
+    pub fn next_start(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = self.start.unwrap_or(NaiveTime::MIN);
+        let date = match &self.timezone {
+            Some(timezone) => timezone.local(after).date_naive(),
+            None => after.date_naive(),
+        };
+
+        // Days are checked until the start is after the datetime, a transition may skip or repeat a local time
+        (0..3)
+            .filter_map(|offset| {
+                let local = (date + Duration::days(offset)).and_time(start);
+
+                match &self.timezone {
+                    Some(timezone) => timezone.to_utc(&local).map(|datetime| datetime.and_utc()),
+                    None => Some(local.and_utc()),
+                }
+            })
+            .find(|datetime| datetime > after)
+    }
 }
 
 impl Display for RouteTime {
@@ -65,6 +96,9 @@ impl Display for RouteTime {
                 Some(end) => format!("in({}, {})", start.format("%H:%M:%S"), end.format("%H:%M:%S")),
             },
         };
-        write!(f, "{str}")
+        match &self.timezone {
+            Some(timezone) => write!(f, "{str} ({timezone})"),
+            None => write!(f, "{str}"),
+        }
     }
 }
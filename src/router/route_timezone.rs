@@ -0,0 +1,54 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// IANA timezone used to evaluate date and time conditions in local time
+#[derive(Clone, Copy, Debug, Hash, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RouteTimezone(pub Tz);
+
+impl RouteTimezone {
+    pub fn parse(name: &str) -> Option<RouteTimezone> {
+        match name.parse::<Tz>() {
+            Ok(tz) => Some(RouteTimezone(tz)),
+            Err(err) => {
+                log::error!("cannot parse timezone {name}: {err}");
+
+                None
+            }
+        }
+    }
+
+    pub fn local(&self, datetime: &DateTime<Utc>) -> DateTime<Tz> {
+        datetime.with_timezone(&self.0)
+    }
+
+    /// Utc datetime of a local one, the earliest is used for ambiguous datetimes and datetimes skipped by a
+    /// transition are moved after it
+    pub fn to_utc(&self, local: &NaiveDateTime) -> Option<NaiveDateTime> {
+        self.0
+            .from_local_datetime(local)
+            .earliest()
+            .or_else(|| self.0.from_local_datetime(&(*local + Duration::hours(1))).earliest())
+            .map(|datetime| datetime.naive_utc())
+    }
+}
+
+impl Ord for RouteTimezone {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.name().cmp(other.0.name())
+    }
+}
+
+impl PartialOrd for RouteTimezone {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for RouteTimezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.name())
+    }
+}
@@ -1,13 +1,17 @@
 use std::{cmp::Ordering, fmt::Display};
 
-use chrono::{DateTime, Datelike, Utc, Weekday};
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 
+use super::RouteTimezone;
+
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Weekdays(pub Vec<Weekday>);
 #[derive(Clone, Debug, Hash, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd)]
 pub struct RouteWeekday {
     pub weekdays: Weekdays,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub timezone: Option<RouteTimezone>,
 }
 
 impl Ord for Weekdays {
@@ -26,7 +30,7 @@ impl PartialOrd for Weekdays {
 }
 
 impl RouteWeekday {
-    pub fn from_weekdays(weekdays: &Vec<String>) -> Option<RouteWeekday> {
+    pub fn from_weekdays(weekdays: &Vec<String>, timezone: Option<RouteTimezone>) -> Option<RouteWeekday> {
         let mut route_weekdays = Vec::new();
 
         for weekday in weekdays {
@@ -44,16 +48,40 @@ impl RouteWeekday {
 
         Some(RouteWeekday {
             weekdays: Weekdays(route_weekdays),
+            timezone,
         })
     }
 
     pub fn match_datetime(&self, datetime: &DateTime<Utc>) -> bool {
-        self.weekdays.0.contains(&datetime.weekday())
+        let weekday = match &self.timezone {
+            Some(timezone) => timezone.local(datetime).weekday(),
+            None => datetime.weekday(),
+        };
+
+        self.weekdays.0.contains(&weekday)
+    }
+
+    /// Next midnight after the given datetime, when the weekday changes
+    pub fn next_start(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let midnight = match &self.timezone {
+            Some(timezone) => timezone.local(after).date_naive(),
+            None => after.date_naive(),
+        }
+        .succ_opt()?
+        .and_time(NaiveTime::MIN);
+
+        match &self.timezone {
+            Some(timezone) => timezone.to_utc(&midnight).map(|datetime| datetime.and_utc()),
+            None => Some(midnight.and_utc()),
+        }
     }
 }
 
 impl Display for RouteWeekday {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "in({:?})", self.weekdays)
+        match &self.timezone {
+            Some(timezone) => write!(f, "in({:?}) ({timezone})", self.weekdays),
+            None => write!(f, "in({:?})", self.weekdays),
+        }
     }
 }
@@ -0,0 +1,303 @@
+use std::{
+    fmt::Display,
+    fs,
+    hash::{Hash, Hasher},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{Route, Router};
+use crate::{router_config::RouterConfig, stable_hash::StableHasher};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"RIOSNAP\0";
+const HEADER_LEN: usize = SNAPSHOT_MAGIC.len() + 2 + 8;
+
+/// Version of the snapshot format, it must be incremented each time the serialized routes or config change
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+/// Header of a router snapshot, it can be read without loading the whole router
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    pub version: u16,
+    /// Hash of the router config used to build the routes, see `RouterConfig::snapshot_hash`
+    pub config_hash: u64,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// The data does not start with the snapshot magic bytes
+    InvalidFormat,
+    /// The snapshot was written with another version of the format
+    UnsupportedVersion(u16),
+    /// The snapshot was built with another router config
+    ConfigMismatch {
+        expected: u64,
+        found: u64,
+    },
+    /// Error while reading or writing the snapshot file
+    IoError(std::io::Error),
+    EncodeError(rmp_serde::encode::Error),
+    DecodeError(rmp_serde::decode::Error),
+}
+
+// Only the routes are serialized, with the patterns of their regexes, the matcher tree and the compiled regexes are
+// not: the tree is rebuilt when loading the snapshot and the regexes are compiled again by `Router::cache`
+#[derive(Serialize)]
+struct SnapshotPayloadRef<'a, T> {
+    config: &'a RouterConfig,
+    routes: Vec<&'a Route<T>>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotPayload<T> {
+    config: RouterConfig,
+    routes: Vec<Route<T>>,
+}
+
+impl SnapshotHeader {
+    pub fn read(data: &[u8]) -> Result<SnapshotHeader, SnapshotError> {
+        if data.len() < HEADER_LEN || &data[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidFormat);
+        }
+
+        let version = u16::from_le_bytes([data[8], data[9]]);
+
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let mut config_hash = [0; 8];
+        config_hash.copy_from_slice(&data[10..HEADER_LEN]);
+
+        Ok(SnapshotHeader {
+            version,
+            config_hash: u64::from_le_bytes(config_hash),
+        })
+    }
+}
+
+impl<T> Router<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Serialize the router in a binary snapshot, routes are sorted by id
+    pub fn to_snapshot(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut routes = self.routes.values().map(|route| route.as_ref()).collect::<Vec<_>>();
+        routes.sort_by(|a, b| a.id().cmp(b.id()));
+
+        let payload = SnapshotPayloadRef {
+            config: self.config.as_ref(),
+            routes,
+        };
+
+        let mut data = Vec::with_capacity(HEADER_LEN);
+        data.extend_from_slice(SNAPSHOT_MAGIC);
+        data.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        data.extend_from_slice(&self.config.snapshot_hash().to_le_bytes());
+
+        rmp_serde::encode::write_named(&mut data, &payload)?;
+
+        Ok(data)
+    }
+
+    /// Load a router from a binary snapshot, the data can come from a memory mapped file
+    ///
+    /// When an expected config is given, the snapshot must have been built with the same config. The router is not
+    /// cached, `Router::cache` must be called after loading it if needed.
+    ///
+    /// Loading a snapshot only skips the conversion of rules into routes, each route is inserted again in the matcher
+    /// tree, which is most of the time spent building a router, so it is not faster than building the router from its
+    /// rules, see the `router_snapshot` benchmark. It gives a self contained file, tied to a config and a format version,
+    /// to start a router from.
+    pub fn from_snapshot(data: &[u8], expected_config: Option<&RouterConfig>) -> Result<Router<T>, SnapshotError> {
+        let header = SnapshotHeader::read(data)?;
+
+        if let Some(expected_config) = expected_config {
+            let expected = expected_config.snapshot_hash();
+
+            if expected != header.config_hash {
+                return Err(SnapshotError::ConfigMismatch {
+                    expected,
+                    found: header.config_hash,
+                });
+            }
+        }
+
+        let mut payload: SnapshotPayload<T> = rmp_serde::from_slice(&data[HEADER_LEN..])?;
+
+        // Both configs have the same geoip database path, reuse the database when it is already loaded
+        if let Some(expected_config) = expected_config {
+            payload.config.geoip_database = expected_config.geoip_database.clone();
+        }
+
+        let mut router = Router::from_arc_config(Arc::new(payload.config));
+
+        for route in payload.routes {
+            router.insert_route(route);
+        }
+
+        Ok(router)
+    }
+
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        fs::write(path, self.to_snapshot()?)?;
+
+        Ok(())
+    }
+
+    pub fn load_snapshot<P: AsRef<Path>>(path: P, expected_config: Option<&RouterConfig>) -> Result<Router<T>, SnapshotError> {
+        Self::from_snapshot(fs::read(path)?.as_slice(), expected_config)
+    }
+}
+
+impl RouterConfig {
+    /// Hash of the config which stays the same across platforms and rust versions, unlike `DefaultHasher`
+    pub fn snapshot_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.hash(&mut hasher);
+
+        hasher.finish()
+    }
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "data is not a router snapshot"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "router snapshot version {version} is not supported, expected version {SNAPSHOT_VERSION}"
+            ),
+            Self::ConfigMismatch { expected, found } => write!(
+                f,
+                "router snapshot was built with config {found:016x}, expected config {expected:016x}"
+            ),
+            Self::IoError(source) => write!(f, "{source}"),
+            Self::EncodeError(source) => write!(f, "cannot encode router snapshot: {source}"),
+            Self::DecodeError(source) => write!(f, "cannot decode router snapshot: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+impl From<rmp_serde::encode::Error> for SnapshotError {
+    fn from(error: rmp_serde::encode::Error) -> Self {
+        Self::EncodeError(error)
+    }
+}
+
+impl From<rmp_serde::decode::Error> for SnapshotError {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        Self::DecodeError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{api::Rule, http::Request};
+
+    fn router() -> Router<Rule> {
+        let mut router = Router::<Rule>::default();
+
+        for rule in [
+            r#"{"id": "static", "rank": 0, "source": {"path": "/foo"}, "target": "/bar", "status_code": 301}"#,
+            r#"{"id": "marker", "rank": 1, "source": {"host": "example.com", "path": "/blog/@slug", "headers": [{"name": "X-Test", "type": "match_regex", "value": "@token"}]}, "markers": [{"name": "slug", "regex": "[a-z]+"}, {"name": "token", "regex": "[0-9]+"}], "target": "/news/@slug", "status_code": 302}"#,
+        ] {
+            router.insert(Rule::from_json(rule).unwrap());
+        }
+
+        router
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let router = router();
+        let snapshot = router.to_snapshot().unwrap();
+
+        assert_eq!(
+            SnapshotHeader::read(&snapshot).unwrap(),
+            SnapshotHeader {
+                version: SNAPSHOT_VERSION,
+                config_hash: router.config.snapshot_hash(),
+            }
+        );
+        let loaded = Router::<Rule>::from_snapshot(&snapshot, Some(&RouterConfig::default())).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.config, router.config);
+
+        let mut request = Request::from_config(
+            &loaded.config,
+            "/blog/hello".to_string(),
+            Some("example.com".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+        request.add_header("X-Test".to_string(), "123".to_string(), true);
+
+        let routes = loaded.match_request(&request);
+
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].capture(&request).get("slug").map(String::as_str), Some("hello"));
+        assert_eq!(routes[0].capture(&request).get("token").map(String::as_str), Some("123"));
+        assert_eq!(loaded.match_request(&"/foo".parse().unwrap()).len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        let snapshot = router().to_snapshot().unwrap();
+        let config = RouterConfig {
+            ignore_path_and_query_case: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            Router::<Rule>::from_snapshot(&snapshot, Some(&config)),
+            Err(SnapshotError::ConfigMismatch { .. })
+        ));
+        assert!(matches!(
+            Router::<Rule>::from_snapshot(b"{}", None),
+            Err(SnapshotError::InvalidFormat)
+        ));
+
+        let mut other_version = snapshot.clone();
+        other_version[8] = 0xff;
+
+        assert!(matches!(
+            Router::<Rule>::from_snapshot(&other_version, None),
+            Err(SnapshotError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            Router::<Rule>::from_snapshot(&snapshot[..snapshot.len() - 10], None),
+            Err(SnapshotError::DecodeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_reuses_geoip_database() {
+        let router = Router::<Rule>::from_config(RouterConfig {
+            geoip_database_path: Some("tests/data/geoip-test.mmdb".to_string()),
+            ..Default::default()
+        });
+        let loaded = Router::<Rule>::from_snapshot(&router.to_snapshot().unwrap(), Some(&router.config)).unwrap();
+
+        assert_eq!(loaded.config, router.config);
+        assert!(std::ptr::eq(
+            loaded.config.geoip_database().unwrap(),
+            router.config.geoip_database().unwrap()
+        ));
+    }
+}
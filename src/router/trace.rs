@@ -3,7 +3,7 @@ use std::sync::Arc;
 use serde::Serialize;
 
 use super::{
-    request_matcher::{DateTimeCondition, HeaderValueCondition},
+    request_matcher::{DateTimeCondition, ValueCondition},
     route::Route,
 };
 
@@ -28,31 +28,72 @@ pub struct Trace<T> {
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
 pub enum TraceInfo<T> {
-    Scheme { request: String, against: Option<String> },
-    HostStatic { request: String, against: Option<String> },
+    Scheme {
+        request: String,
+        against: Option<String>,
+    },
+    HostStatic {
+        request: String,
+        against: Option<String>,
+    },
     HostRegex,
-    Ip { request: String, against: String },
-    DateTimeGroup { conditions: Vec<TraceInfoDateTimeCondition> },
-    Method { request: String, against: Option<String> },
-    ExcludeMethods { request: String, against: Option<Vec<String>> },
-    HeaderGroup { conditions: Vec<TraceInfoHeaderCondition> },
-    PathAndQueryStatic { request: String },
+    Ip {
+        request: String,
+        against: String,
+    },
+    DateTimeGroup {
+        conditions: Vec<TraceInfoDateTimeCondition>,
+    },
+    Method {
+        request: String,
+        against: Option<String>,
+    },
+    ExcludeMethods {
+        request: String,
+        against: Option<Vec<String>>,
+    },
+    HeaderGroup {
+        conditions: Vec<TraceInfoValueCondition>,
+    },
+    QueryParamGroup {
+        conditions: Vec<TraceInfoValueCondition>,
+    },
+    CookieGroup {
+        conditions: Vec<TraceInfoValueCondition>,
+    },
+    Language {
+        request: Option<String>,
+        negotiated: Option<String>,
+        against: String,
+    },
+    PathAndQueryStatic {
+        request: String,
+    },
     PathAndQueryRegex,
-    Regex { request: String, against: String },
-    Storage { routes: Vec<Arc<Route<T>>> },
+    Regex {
+        request: String,
+        against: String,
+    },
+    Storage {
+        routes: Vec<Arc<Route<T>>>,
+    },
 }
 
+/// Condition on a named value of the request, e.g. a header, a query param or a cookie
 #[derive(Serialize, Debug, Clone)]
-pub struct TraceInfoHeaderCondition {
+pub struct TraceInfoValueCondition {
     pub result: Option<bool>,
     pub name: String,
-    pub condition: HeaderValueCondition,
+    pub condition: ValueCondition,
     pub cached: bool,
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct TraceInfoDateTimeCondition {
     pub result: Option<bool>,
+    /// Request datetime in the timezone of the condition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_datetime: Option<String>,
     pub condition: DateTimeCondition,
     pub cached: bool,
 }
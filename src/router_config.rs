@@ -2,6 +2,8 @@ use std::{collections::HashSet, hash::Hash};
 
 use serde::{Deserialize, Serialize};
 
+use crate::geoip::{GeoIpDatabase, SharedGeoIpDatabase};
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RouterConfig {
     #[serde(default = "default_as_true")]
@@ -22,6 +24,15 @@ pub struct RouterConfig {
     pub always_match_any_host: bool,
     #[serde(default = "default_as_true")]
     pub ignore_query_param_order: bool,
+    /// Path of a MaxMind format database used to resolve the country, continent and asn of the remote address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geoip_database_path: Option<String>,
+    /// Database opened from `geoip_database_path`, it is loaded once when a router is created with this config
+    #[serde(skip)]
+    pub geoip_database: SharedGeoIpDatabase,
+    /// IANA timezone used for rules with date or time conditions that do not set their own timezone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_timezone: Option<String>,
 }
 
 impl Hash for RouterConfig {
@@ -40,6 +51,10 @@ impl Hash for RouterConfig {
         marketing_query_params.sort();
 
         marketing_query_params.hash(state);
+
+        self.geoip_database_path.hash(state);
+        self.geoip_database.hash(state);
+        self.default_timezone.hash(state);
     }
 }
 
@@ -83,6 +98,31 @@ impl Default for RouterConfig {
             pass_marketing_query_params_to_target: true,
             always_match_any_host: true,
             ignore_query_param_order: true,
+            geoip_database_path: None,
+            geoip_database: SharedGeoIpDatabase::default(),
+            default_timezone: None,
+        }
+    }
+}
+
+impl RouterConfig {
+    pub fn geoip_database(&self) -> Option<&GeoIpDatabase> {
+        self.geoip_database.get()
+    }
+
+    /// Open the database of `geoip_database_path` unless it is already loaded
+    pub fn load_geoip_database(&mut self) {
+        if self.geoip_database.is_loaded() {
+            return;
+        }
+
+        let Some(path) = self.geoip_database_path.as_deref() else {
+            return;
+        };
+
+        match GeoIpDatabase::open(path) {
+            Ok(database) => self.geoip_database = SharedGeoIpDatabase::new(database),
+            Err(err) => log::error!("cannot open geoip database {path}: {err}"),
         }
     }
 }
@@ -1,20 +1,34 @@
+#[cfg(feature = "router")]
+use std::sync::Arc;
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
 };
 
 use chrono::Utc;
+#[cfg(feature = "router")]
+use serde::{Serialize, de::DeserializeOwned};
 use serde_json::{from_str as json_decode, to_string as json_encode};
 use trusted_proxies::{Config, Trusted};
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    RouterConfig,
+    RateLimiter as RedirectionioRateLimiter, RouterConfig,
     action::Action as RedirectionioAction,
     api::Log,
     filter::FilterBodyAction,
     http::{Addr, Header, PathAndQueryWithSkipped, Request as RedirectionioRequest},
 };
+#[cfg(feature = "router")]
+use crate::{
+    api::{
+        ExplainRequestInput, ExplainRequestOutput, ExplainRequestProjectInput, FlattenRedirectionsInput, FlattenRedirectionsOutput,
+        FlattenRedirectionsProjectInput, ImpactInput, ImpactOutput, ImpactProjectInput, LintInput, LintOutput, LintProjectInput,
+        NextActivationInput, NextActivationOutput, NextActivationProjectInput, Rule, RuleChangeSet, RulesMessage, TestExamplesInput,
+        TestExamplesOutput, TestExamplesProjectInput, UnitIdsInput, UnitIdsOutput, UnitIdsProjectInput,
+    },
+    router::{Router as RedirectionioRouter, RouterRegistry as RedirectionioRouterRegistry},
+};
 
 #[wasm_bindgen()]
 pub struct Request {
@@ -34,12 +48,32 @@ pub struct Action {
     pub action: Option<RedirectionioAction>,
 }
 
+#[wasm_bindgen()]
+pub struct RateLimiter {
+    #[wasm_bindgen(skip)]
+    pub rate_limiter: RedirectionioRateLimiter,
+}
+
 #[wasm_bindgen()]
 pub struct BodyFilter {
     #[wasm_bindgen(skip)]
     pub filter: Option<FilterBodyAction>,
 }
 
+#[cfg(feature = "router")]
+#[wasm_bindgen(js_name = Router)]
+pub struct RuleRouter {
+    #[wasm_bindgen(skip)]
+    pub router: Arc<RedirectionioRouter<Rule>>,
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen(js_name = RouterRegistry)]
+pub struct RuleRouterRegistry {
+    #[wasm_bindgen(skip)]
+    pub registry: RedirectionioRouterRegistry<Rule>,
+}
+
 #[wasm_bindgen()]
 impl Request {
     #[wasm_bindgen(constructor)]
@@ -57,6 +91,7 @@ impl Request {
                 remote_addr: None,
                 created_at: Some(Utc::now()),
                 sampling_override: None,
+                geo_location: None,
             },
         }
     }
@@ -95,6 +130,17 @@ impl Request {
     }
 }
 
+#[wasm_bindgen()]
+impl RateLimiter {
+    #[allow(clippy::new_without_default)]
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RateLimiter {
+        RateLimiter {
+            rate_limiter: RedirectionioRateLimiter::default(),
+        }
+    }
+}
+
 #[wasm_bindgen()]
 impl HeaderMap {
     #[allow(clippy::new_without_default)]
@@ -162,6 +208,14 @@ impl Action {
         0
     }
 
+    pub fn get_status_code_with_headers(&mut self, response_status_code: u16, headers: &HeaderMap) -> u16 {
+        if let Some(action) = self.action.as_mut() {
+            return action.get_status_code_with_headers(response_status_code, &headers.headers, None);
+        }
+
+        0
+    }
+
     pub fn filter_headers(&mut self, headers: HeaderMap, response_status_code: u16, add_rule_ids_header: bool) -> HeaderMap {
         if self.action.is_none() {
             return headers;
@@ -173,6 +227,37 @@ impl Action {
         HeaderMap { headers: new_headers }
     }
 
+    pub fn check_rate_limits(&mut self, rate_limiter: &RateLimiter) -> u16 {
+        match self.action.as_mut() {
+            None => 0,
+            Some(action) => action.check_rate_limits(&rate_limiter.rate_limiter, None),
+        }
+    }
+
+    pub fn has_static_response(&self) -> bool {
+        match self.action.as_ref() {
+            None => false,
+            Some(action) => action.has_static_response(),
+        }
+    }
+
+    pub fn get_static_response_body(&mut self) -> Option<String> {
+        let action = self.action.as_mut()?;
+
+        action.get_static_response_body(None).map(|body| body.to_string())
+    }
+
+    pub fn filter_request_headers(&mut self, headers: HeaderMap) -> HeaderMap {
+        if self.action.is_none() {
+            return headers;
+        }
+
+        let action = self.action.as_mut().unwrap();
+        let new_headers = action.filter_request_headers(headers.headers, None);
+
+        HeaderMap { headers: new_headers }
+    }
+
     pub fn create_body_filter(&mut self, response_status_code: u16, headers: &HeaderMap) -> BodyFilter {
         if self.action.is_none() {
             return BodyFilter { filter: None };
@@ -224,12 +309,321 @@ impl BodyFilter {
     }
 }
 
+#[cfg(feature = "router")]
+#[wasm_bindgen(js_class = Router)]
+impl RuleRouter {
+    /// Create a router from a json rules message, throws if the rules or the config cannot be decoded
+    #[wasm_bindgen(constructor)]
+    pub fn new(rules_serialized: String, config_serialized: Option<String>) -> Result<RuleRouter, JsError> {
+        let config = match config_serialized {
+            None => RouterConfig::default(),
+            Some(config_serialized) => {
+                decode_input(config_serialized.as_str(), "router config").ok_or_else(|| JsError::new("invalid router config"))?
+            }
+        };
+        let rules_message: RulesMessage =
+            decode_input(rules_serialized.as_str(), "rules message").ok_or_else(|| JsError::new("invalid rules message"))?;
+        let mut router = RedirectionioRouter::<Rule>::from_config(config);
+
+        for rule in rules_message.rules {
+            router.insert(rule);
+        }
+
+        router.cache(None);
+
+        Ok(RuleRouter { router: Arc::new(router) })
+    }
+
+    /// Load a router from a binary snapshot created with `to_snapshot`, returns undefined if the snapshot is invalid
+    /// or was not built with the given config
+    ///
+    /// The matcher tree is rebuilt and the router is cached as with `new`, so it is not faster than creating the router
+    /// from its rules
+    pub fn from_snapshot(snapshot: &[u8], config_serialized: Option<String>) -> Option<RuleRouter> {
+        let config = match config_serialized {
+            None => None,
+            Some(config_serialized) => Some(decode_input::<RouterConfig>(config_serialized.as_str(), "router config")?),
+        };
+
+        let mut router = match RedirectionioRouter::<Rule>::from_snapshot(snapshot, config.as_ref()) {
+            Ok(router) => router,
+            Err(err) => {
+                log::error!("cannot load router snapshot: {err}");
+
+                return None;
+            }
+        };
+
+        router.cache(None);
+
+        Some(RuleRouter { router: Arc::new(router) })
+    }
+
+    pub fn to_snapshot(&self) -> Option<Vec<u8>> {
+        match self.router.to_snapshot() {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                log::error!("cannot create router snapshot: {err}");
+
+                None
+            }
+        }
+    }
+
+    pub fn apply_change_set(&mut self, change_set_serialized: String) {
+        let change_set: RuleChangeSet = match decode_input(change_set_serialized.as_str(), "rule change set") {
+            None => return,
+            Some(change_set) => change_set,
+        };
+
+        if change_set.is_empty() {
+            return;
+        }
+
+        let mut router = change_set.update_existing_router(self.router.clone());
+        router.cache(None);
+
+        self.router = Arc::new(router);
+    }
+
+    pub fn len(&self) -> usize {
+        self.router.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.router.is_empty()
+    }
+
+    pub fn get_rule(&self, request: &Request) -> Option<String> {
+        let request = self.router.rebuild_request(&request.request);
+        let route = self.router.get_route(&request)?;
+
+        json_encode(route.handler()).ok()
+    }
+
+    pub fn match_action(&self, request: &Request) -> Action {
+        let request = self.router.rebuild_request(&request.request);
+        let routes = self.router.match_request(&request);
+
+        Action {
+            action: Some(RedirectionioAction::from_routes_rule(routes, &request, None)),
+        }
+    }
+
+    pub fn explain_request(&self, input_serialized: String) -> String {
+        let input: ExplainRequestProjectInput = match decode_input(input_serialized.as_str(), "explain request input") {
+            None => return "".to_string(),
+            Some(input) => input,
+        };
+
+        match ExplainRequestOutput::create_result_from_project(input, self.router.clone()) {
+            Err(error) => encode_output(&error),
+            Ok(output) => encode_output(&output),
+        }
+    }
+
+    pub fn impact(&self, input_serialized: String) -> String {
+        match decode_input::<ImpactProjectInput>(input_serialized.as_str(), "impact input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&ImpactOutput::from_impact_project(input, self.router.clone())),
+        }
+    }
+
+    pub fn test_examples(&self, input_serialized: String) -> String {
+        match decode_input::<TestExamplesProjectInput>(input_serialized.as_str(), "test examples input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&TestExamplesOutput::from_project(input, self.router.clone())),
+        }
+    }
+
+    pub fn flatten_redirections(&self, input_serialized: String) -> String {
+        match decode_input::<FlattenRedirectionsProjectInput>(input_serialized.as_str(), "flatten redirections input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&FlattenRedirectionsOutput::from_project(input, self.router.clone())),
+        }
+    }
+
+    pub fn lint_rules(&self, input_serialized: String) -> String {
+        match decode_input::<LintProjectInput>(input_serialized.as_str(), "lint input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&LintOutput::from_project(input, self.router.clone())),
+        }
+    }
+
+    pub fn unit_ids(&self, input_serialized: String) -> String {
+        match decode_input::<UnitIdsProjectInput>(input_serialized.as_str(), "unit ids input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&UnitIdsOutput::create_result_from_project(input, self.router.clone())),
+        }
+    }
+
+    pub fn next_activations(&self, input_serialized: String) -> String {
+        match decode_input::<NextActivationProjectInput>(input_serialized.as_str(), "next activation input") {
+            None => "".to_string(),
+            Some(input) => encode_output(&NextActivationOutput::create_result_from_project(input, self.router.clone())),
+        }
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen(js_class = RouterRegistry)]
+impl RuleRouterRegistry {
+    #[allow(clippy::new_without_default)]
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RuleRouterRegistry {
+        RuleRouterRegistry {
+            registry: RedirectionioRouterRegistry::new(),
+        }
+    }
+
+    /// Register a router for a project and its hosts, serialized as a json array, replacing the project if it exists
+    pub fn insert(&self, project_id: String, hosts_serialized: String, router: &RuleRouter) -> bool {
+        let hosts: Vec<String> = match decode_input(hosts_serialized.as_str(), "hosts") {
+            None => return false,
+            Some(hosts) => hosts,
+        };
+
+        self.registry.insert(project_id.as_str(), hosts, router.router.as_ref().clone());
+
+        true
+    }
+
+    /// Replace the router of a registered project, returns false if the project is not registered
+    pub fn swap(&self, project_id: String, router: &RuleRouter) -> bool {
+        self.registry.swap(project_id.as_str(), router.router.as_ref().clone()).is_some()
+    }
+
+    pub fn remove(&self, project_id: String) -> bool {
+        self.registry.remove(project_id.as_str()).is_some()
+    }
+
+    /// Get the id of the project serving a host, returns undefined if there is none
+    pub fn project_id(&self, host: String) -> Option<String> {
+        self.registry.get_by_host(host.as_str()).map(|(project_id, _)| project_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.registry.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registry.is_empty()
+    }
+
+    /// Match a request against the router of the project serving its host, returns undefined if there is none
+    pub fn match_action(&self, request: &Request) -> Option<Action> {
+        let (request, routes) = self.registry.match_request(&request.request)?;
+
+        Some(Action {
+            action: Some(RedirectionioAction::from_routes_rule(routes, &request, None)),
+        })
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn explain_request(input_serialized: String) -> String {
+    let input: ExplainRequestInput = match decode_input(input_serialized.as_str(), "explain request input") {
+        None => return "".to_string(),
+        Some(input) => input,
+    };
+
+    match ExplainRequestOutput::create_result_without_project(input) {
+        Err(error) => encode_output(&error),
+        Ok(output) => encode_output(&output),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn impact(input_serialized: String) -> String {
+    match decode_input::<ImpactInput>(input_serialized.as_str(), "impact input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&ImpactOutput::create_result(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn test_examples(input_serialized: String) -> String {
+    match decode_input::<TestExamplesInput>(input_serialized.as_str(), "test examples input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&TestExamplesOutput::create_result_without_project(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn flatten_redirections(input_serialized: String) -> String {
+    match decode_input::<FlattenRedirectionsInput>(input_serialized.as_str(), "flatten redirections input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&FlattenRedirectionsOutput::create_result_without_project(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn lint_rules(input_serialized: String) -> String {
+    match decode_input::<LintInput>(input_serialized.as_str(), "lint input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&LintOutput::create_result_without_project(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn unit_ids(input_serialized: String) -> String {
+    match decode_input::<UnitIdsInput>(input_serialized.as_str(), "unit ids input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&UnitIdsOutput::create_result_without_project(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn next_activations(input_serialized: String) -> String {
+    match decode_input::<NextActivationInput>(input_serialized.as_str(), "next activation input") {
+        None => "".to_string(),
+        Some(input) => encode_output(&NextActivationOutput::create_result_without_project(input)),
+    }
+}
+
+#[cfg(feature = "router")]
+#[wasm_bindgen()]
+pub fn validate_rules(rules_serialized: String) -> String {
+    match decode_input::<RulesMessage>(rules_serialized.as_str(), "rules message") {
+        None => "".to_string(),
+        Some(rules_message) => encode_output(&rules_message.validate()),
+    }
+}
+
+#[cfg(feature = "router")]
+fn decode_input<T: DeserializeOwned>(input_serialized: &str, kind: &str) -> Option<T> {
+    match json_decode(input_serialized) {
+        Err(error) => {
+            log::error!("Unable to deserialize \"{}\" to {}: {}", input_serialized, kind, error);
+
+            None
+        }
+        Ok(input) => Some(input),
+    }
+}
+
+#[cfg(feature = "router")]
+fn encode_output<T: Serialize>(output: &T) -> String {
+    match json_encode(output) {
+        Err(_) => "".to_string(),
+        Ok(output_serialized) => output_serialized,
+    }
+}
+
 #[wasm_bindgen()]
 pub fn init_log() {
     wasm_logger::init(wasm_logger::Config::new(log::Level::Error));
 }
 
 #[wasm_bindgen()]
+#[allow(clippy::too_many_arguments)]
 pub fn create_log_in_json(
     request: Request,
     status_code: u16,
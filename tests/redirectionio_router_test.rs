@@ -8317,7 +8317,7 @@ fn test_rule_date_trigger_after_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8341,7 +8341,7 @@ fn test_rule_date_trigger_after_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8365,7 +8365,7 @@ fn test_rule_date_trigger_after_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8411,7 +8411,7 @@ fn test_rule_date_trigger_before_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8429,7 +8429,7 @@ fn test_rule_date_trigger_before_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8447,7 +8447,7 @@ fn test_rule_date_trigger_before_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8471,7 +8471,7 @@ fn test_rule_date_trigger_before_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-08-31T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-08-31T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8523,7 +8523,7 @@ fn test_rule_date_trigger_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8547,7 +8547,7 @@ fn test_rule_date_trigger_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8571,7 +8571,7 @@ fn test_rule_date_trigger_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8589,7 +8589,7 @@ fn test_rule_date_trigger_between_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8635,7 +8635,7 @@ fn test_rule_date_trigger_equals_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8659,7 +8659,7 @@ fn test_rule_date_trigger_equals_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-02T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-02T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8689,7 +8689,7 @@ fn test_rule_date_trigger_multiple_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-26T22:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-26T22:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8713,7 +8713,7 @@ fn test_rule_date_trigger_multiple_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-04-16T17:42:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-04-16T17:42:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8737,7 +8737,7 @@ fn test_rule_date_trigger_multiple_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-03-12T22:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-03-12T22:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8755,7 +8755,7 @@ fn test_rule_date_trigger_multiple_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-24T22:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-24T22:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8773,7 +8773,7 @@ fn test_rule_date_trigger_multiple_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-26T12:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-26T12:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8825,7 +8825,7 @@ fn test_rule_date_trigger_not_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8843,7 +8843,7 @@ fn test_rule_date_trigger_not_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8861,7 +8861,7 @@ fn test_rule_date_trigger_not_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T12:00:01+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T12:00:01+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8885,7 +8885,7 @@ fn test_rule_date_trigger_not_between_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8943,7 +8943,7 @@ fn test_rule_date_trigger_not_equals_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8961,7 +8961,7 @@ fn test_rule_date_trigger_not_equals_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-02T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-02T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -8997,7 +8997,7 @@ fn test_rule_date_trigger_weekdays_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-19T17:56:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-19T17:56:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9021,7 +9021,7 @@ fn test_rule_date_trigger_weekdays_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-20T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-20T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9045,7 +9045,7 @@ fn test_rule_date_trigger_weekdays_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-23T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-23T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9063,7 +9063,7 @@ fn test_rule_date_trigger_weekdays_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2023-02-18T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2023-02-18T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9115,7 +9115,7 @@ fn test_rule_datetime_trigger_after_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9139,7 +9139,7 @@ fn test_rule_datetime_trigger_after_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9163,7 +9163,7 @@ fn test_rule_datetime_trigger_after_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9209,7 +9209,7 @@ fn test_rule_datetime_trigger_before_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9227,7 +9227,7 @@ fn test_rule_datetime_trigger_before_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9245,7 +9245,7 @@ fn test_rule_datetime_trigger_before_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9269,7 +9269,7 @@ fn test_rule_datetime_trigger_before_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-08-31T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-08-31T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9321,7 +9321,7 @@ fn test_rule_datetime_trigger_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9345,7 +9345,7 @@ fn test_rule_datetime_trigger_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9369,7 +9369,7 @@ fn test_rule_datetime_trigger_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9387,7 +9387,7 @@ fn test_rule_datetime_trigger_between_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9439,7 +9439,7 @@ fn test_rule_datetime_trigger_not_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T00:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9457,7 +9457,7 @@ fn test_rule_datetime_trigger_not_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-10T14:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9475,7 +9475,7 @@ fn test_rule_datetime_trigger_not_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T12:00:01+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T12:00:01+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -9499,7 +9499,7 @@ fn test_rule_datetime_trigger_not_between_5() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2019-12-31T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11203,7 +11203,7 @@ fn test_rule_time_trigger_after_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11221,7 +11221,7 @@ fn test_rule_time_trigger_after_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T15:21:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T15:21:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11245,7 +11245,7 @@ fn test_rule_time_trigger_after_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11263,7 +11263,7 @@ fn test_rule_time_trigger_after_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T15:21:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T15:21:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11299,7 +11299,7 @@ fn test_rule_time_trigger_before_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11323,7 +11323,7 @@ fn test_rule_time_trigger_before_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T15:21:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T15:21:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11341,7 +11341,7 @@ fn test_rule_time_trigger_before_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T14:30:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T14:30:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11365,7 +11365,7 @@ fn test_rule_time_trigger_before_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T15:21:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T15:21:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11395,7 +11395,7 @@ fn test_rule_time_trigger_between_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:40:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:40:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11419,7 +11419,7 @@ fn test_rule_time_trigger_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-10-15T14:55:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-10-15T14:55:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11443,7 +11443,7 @@ fn test_rule_time_trigger_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T13:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T13:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11461,7 +11461,7 @@ fn test_rule_time_trigger_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T16:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T16:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11491,7 +11491,7 @@ fn test_rule_time_trigger_not_between_1() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T14:40:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T14:40:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11509,7 +11509,7 @@ fn test_rule_time_trigger_not_between_2() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-10-15T14:55:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-10-15T14:55:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11527,7 +11527,7 @@ fn test_rule_time_trigger_not_between_3() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-01T13:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-01T13:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
@@ -11551,7 +11551,7 @@ fn test_rule_time_trigger_not_between_4() {
     let default_config = RouterConfig::default();
     let mut request = Request::new(PathAndQueryWithSkipped::from_config(&default_config, r#"/foo"#), r#"/foo"#.to_string(),None,None,None,None,None);
     
-    request.set_created_at(Some(r#"2020-01-15T16:00:00+00:00"#.to_string()));
+    request.set_created_at(Some(r#"2020-01-15T16:00:00+00:00"#.to_string())).expect("cannot parse datetime");
     
     let request_configured = Request::rebuild_with_config(&router.config, &request);
     let matched = router.match_request(&request_configured);
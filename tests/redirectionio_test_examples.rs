@@ -6,6 +6,11 @@ mod generated_tests {
     use serde_json::{from_str as json_decode, to_string_pretty as json_encode};
     use std::env;
 
+    #[test]
+    fn test_examples_accept_language() {
+        do_test("accept_language");
+    }
+
     #[test]
     fn test_examples_configuration_log_off() {
         do_test("configuration_log_off");
@@ -16,6 +21,16 @@ mod generated_tests {
         do_test("configuration_reset_on");
     }
 
+    #[test]
+    fn test_examples_cookies() {
+        do_test("cookies");
+    }
+
+    #[test]
+    fn test_examples_geoip() {
+        do_test("geoip");
+    }
+
     #[test]
     fn test_examples_header_add() {
         do_test("header_add");
@@ -61,6 +76,21 @@ mod generated_tests {
         do_test("one_rule_one_example");
     }
 
+    #[test]
+    fn test_examples_query_params() {
+        do_test("query_params");
+    }
+
+    #[test]
+    fn test_examples_schedules() {
+        do_test("schedules");
+    }
+
+    #[test]
+    fn test_examples_timezone() {
+        do_test("timezone");
+    }
+
     fn do_test(name: &str) {
         let json_in = std::fs::read_to_string(format!("tests/test_examples/{}.in.json", name)).unwrap();
         let test_examples_input: TestExamplesInput = json_decode(&json_in).unwrap();